@@ -1,27 +1,31 @@
-use std::mem;
-
-use crate::{string_to_ptr, unwrap_or_err, utils::{array::array_as_json, cstring}};
+use pk_ffi::{cstring, export, LPCWSTR};
 use serde_json::Value;
 
+use crate::utils::array::array_as_json;
+
 #[no_mangle]
-pub extern "stdcall" fn from_list(list_ptr: *const u16) -> *const u16 {
-    let list = cstring::from_ptr(list_ptr).unwrap();
-    let splitted: Vec<&str> = list.split("\r\n").collect();
-    let json = array_as_json( splitted);
+pub unsafe extern "system" fn from_list(list_ptr: LPCWSTR) -> LPCWSTR {
+    export(|| {
+        let list = cstring::from_widechar_ptr(list_ptr)?;
+        let splitted: Vec<&str> = list.split("\r\n").collect();
 
-    string_to_ptr!(&json)
+        Ok(array_as_json(splitted))
+    })
 }
 
 #[no_mangle]
-pub extern "stdcall" fn to_list(array_ptr: *const u16) -> *const u16 {
-    let array = cstring::from_ptr(array_ptr).unwrap();
-    let v: Value = unwrap_or_err!(serde_json::from_str(&array));
-    let list = v.as_array()
-                        .unwrap_or(&Vec::new())
-                        .iter()
-                        .map(|element| element.as_str().unwrap_or_default())
-                        .collect::<Vec<&str>>()
-                        .join("\r\n");
+pub unsafe extern "system" fn to_list(array_ptr: LPCWSTR) -> LPCWSTR {
+    export(|| {
+        let array = cstring::from_widechar_ptr(array_ptr)?;
+        let v: Value = serde_json::from_str(&array)?;
+        let list = v
+            .as_array()
+            .unwrap_or(&Vec::new())
+            .iter()
+            .map(|element| element.as_str().unwrap_or_default())
+            .collect::<Vec<&str>>()
+            .join("\r\n");
 
-    string_to_ptr!(&list)
-}
\ No newline at end of file
+        Ok(list)
+    })
+}
@@ -1,14 +1,12 @@
 mod array;
-mod dllmain;
 
 mod utils {
-    pub mod cstring;
     pub mod array;
-    pub mod macros;
 }
 
-pub use crate::{
-    utils::*,
-};
-
-pub const ERR: &str = "ERR|";
\ No newline at end of file
+pk_ffi::dll_main!();
+pk_ffi::info! {
+    author: "_Skill_",
+    version: "0.1",
+    description: "Функции для работы с массивами",
+}
@@ -0,0 +1,59 @@
+//! Calls the exports by name the way Keeper does: null-terminated UTF-16 in and out
+
+extern crate array;
+
+fn wide(s: &str) -> Vec<u16> {
+    s.encode_utf16().chain(Some(0)).collect()
+}
+
+unsafe fn from_wide(ptr: *const u16) -> String {
+    let len = (0..).take_while(|&i| *ptr.add(i) != 0).count();
+    String::from_utf16(std::slice::from_raw_parts(ptr, len)).unwrap()
+}
+
+macro_rules! exports {
+    ($($name:ident($($arg:ident),*);)+) => {
+        #[allow(non_snake_case)]
+        mod ffi {
+            extern "system" {
+                $(pub fn $name($($arg: *const u16),*) -> *const u16;)+
+            }
+        }
+
+        $(
+            #[allow(non_snake_case)]
+            fn $name($($arg: &str),*) -> String {
+                $(let $arg = wide($arg);)*
+                unsafe { from_wide(ffi::$name($($arg.as_ptr()),*)) }
+            }
+        )+
+    };
+}
+
+exports! {
+    from_list(list);
+    to_list(array);
+    info_getAuthor();
+    info_getVersion();
+    info_getDescription();
+}
+
+#[test]
+fn list_to_array() {
+    assert_eq!(from_list("a\r\nб\r\nc"), r#"["a","б","c"]"#);
+    assert_eq!(from_list(r#"say "hi""#), r#"["say \"hi\""]"#);
+}
+
+#[test]
+fn array_to_list() {
+    assert_eq!(to_list(r#"["a","б","c"]"#), "a\r\nб\r\nc");
+    assert_eq!(to_list("{}"), "");
+    assert!(to_list("[").starts_with("ERR|"));
+}
+
+#[test]
+fn info() {
+    assert_eq!(info_getAuthor(), "_Skill_");
+    assert_eq!(info_getVersion(), "0.1");
+    assert_eq!(info_getDescription(), "Функции для работы с массивами");
+}
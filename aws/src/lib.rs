@@ -1,16 +1,15 @@
 mod aws;
-mod dllmain;
 
 mod utils {
     pub mod aws;
-    pub mod cstring;
-    pub mod macros;
 }
 
-pub use crate::utils::*;
-
-pub const ERR: &str = "ERR|";
-pub const DEBUG: bool = false;
+pk_ffi::dll_main!();
+pk_ffi::info! {
+    author: "_Skill_",
+    version: "0.1",
+    description: "Подпись запросов AWS v4",
+}
 
 #[global_allocator]
 static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
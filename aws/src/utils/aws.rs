@@ -13,10 +13,7 @@ pub fn create_request(
 ) -> SignedRequest {
     let mut signed_request = SignedRequest::new(method, service, &region, url.path());
 
-    let host = match url.host_str() {
-        Some(host) => Some(host.to_string()),
-        None => None,
-    };
+    let host = url.host_str().map(|host| host.to_string());
 
     signed_request.set_hostname(host);
 
@@ -0,0 +1,104 @@
+use pk_ffi::{cstring, export, LPCWSTR};
+
+use crate::{imp::ecc, utils::encoding};
+
+/// curve: p256, p384, secp256k1, ed25519, x25519.
+/// format: pem (default), der, raw, jwk.
+/// Returns `private|public`
+#[no_mangle]
+pub unsafe extern "system" fn ec_generate(curve_ptr: LPCWSTR, format_ptr: LPCWSTR) -> LPCWSTR {
+    export(|| {
+        let curve = cstring::from_widechar_ptr(curve_ptr)?;
+        let format = cstring::from_widechar_ptr(format_ptr)?;
+
+        let (priv_key, pub_key) = ecc::ec_generate(&curve, &format)?;
+        Ok(format!("{}|{}", priv_key.trim_end(), pub_key.trim_end()))
+    })
+}
+
+/// Public key from the private key in any supported format
+#[no_mangle]
+pub unsafe extern "system" fn ec_public_key(
+    key_ptr: LPCWSTR,
+    curve_ptr: LPCWSTR,
+    format_ptr: LPCWSTR,
+) -> LPCWSTR {
+    export(|| {
+        let key = cstring::from_widechar_ptr(key_ptr)?;
+        let curve = cstring::from_widechar_ptr(curve_ptr)?;
+        let format = cstring::from_widechar_ptr(format_ptr)?;
+
+        let pub_key = ecc::ec_public_key(&key, &curve, &format)?;
+        Ok(pub_key.trim_end().to_string())
+    })
+}
+
+/// Empty hash_type means sha256 (sha384 for p384), ignored for ed25519.
+/// signature_format: der (default) or raw (r || s)
+#[no_mangle]
+pub unsafe extern "system" fn ec_sign(
+    data_ptr: LPCWSTR,
+    key_ptr: LPCWSTR,
+    curve_ptr: LPCWSTR,
+    hash_type_ptr: LPCWSTR,
+    signature_format_ptr: LPCWSTR,
+) -> LPCWSTR {
+    export(|| {
+        let (output, data) = encoding::output_prefix(cstring::from_widechar_ptr(data_ptr)?)?;
+        let data = encoding::decode(data)?;
+
+        let key = cstring::from_widechar_ptr(key_ptr)?;
+        let curve = cstring::from_widechar_ptr(curve_ptr)?;
+        let hash_type = cstring::from_widechar_ptr(hash_type_ptr)?;
+        let signature_format = cstring::from_widechar_ptr(signature_format_ptr)?;
+
+        let signed = ecc::ec_sign(&data, &key, &curve, &hash_type, &signature_format)?;
+
+        Ok(encoding::encode(signed, output)?)
+    })
+}
+
+/// Signature can be DER or raw (r || s). Returns true or false
+#[no_mangle]
+pub unsafe extern "system" fn ec_verify(
+    data_ptr: LPCWSTR,
+    signature_ptr: LPCWSTR,
+    key_ptr: LPCWSTR,
+    curve_ptr: LPCWSTR,
+    hash_type_ptr: LPCWSTR,
+) -> LPCWSTR {
+    export(|| {
+        let data = cstring::from_widechar_ptr(data_ptr)?;
+        let data = encoding::decode(data)?;
+
+        let signature = cstring::from_widechar_ptr(signature_ptr)?;
+        let signature = encoding::decode(signature)?;
+
+        let key = cstring::from_widechar_ptr(key_ptr)?;
+        let curve = cstring::from_widechar_ptr(curve_ptr)?;
+        let hash_type = cstring::from_widechar_ptr(hash_type_ptr)?;
+
+        let verified = ecc::ec_verify(&data, &signature, &key, &curve, &hash_type)?;
+
+        Ok(verified.to_string())
+    })
+}
+
+/// curve: p256, p384, secp256k1, x25519. Returns raw shared secret in base64
+#[no_mangle]
+pub unsafe extern "system" fn ecdh(
+    private_key_ptr: LPCWSTR,
+    public_key_ptr: LPCWSTR,
+    curve_ptr: LPCWSTR,
+) -> LPCWSTR {
+    export(|| {
+        let (output, private_key) =
+            encoding::output_prefix(cstring::from_widechar_ptr(private_key_ptr)?)?;
+        let public_key = cstring::from_widechar_ptr(public_key_ptr)?;
+        let curve = cstring::from_widechar_ptr(curve_ptr)?;
+
+        let shared_secret = ecc::ecdh(&private_key, &public_key, &curve)?;
+
+        Ok(encoding::encode(shared_secret, output)?)
+    })
+}
@@ -0,0 +1,74 @@
+use pk_ffi::{cstring, export, LPCWSTR};
+
+use crate::{
+    imp::encryption::{self, DEFAULT_TAG_LEN},
+    utils::encoding,
+};
+
+/// inputs, outputs in base64, tag is appended to the ciphertext.
+/// Empty tag_len means 16 bytes
+#[no_mangle]
+pub unsafe extern "system" fn aead_encrypt(
+    data_ptr: LPCWSTR,
+    key_ptr: LPCWSTR,
+    nonce_ptr: LPCWSTR,
+    aad_ptr: LPCWSTR,
+    mode_ptr: LPCWSTR,
+    tag_len_ptr: LPCWSTR,
+) -> LPCWSTR {
+    export(|| {
+        let (output, data) = encoding::output_prefix(cstring::from_widechar_ptr(data_ptr)?)?;
+        let key = cstring::from_widechar_ptr(key_ptr)?;
+        let nonce = cstring::from_widechar_ptr(nonce_ptr)?;
+        let aad = cstring::from_widechar_ptr(aad_ptr)?;
+        let mode = cstring::from_widechar_ptr(mode_ptr)?;
+        let tag_len = cstring::from_widechar_ptr(tag_len_ptr)?;
+
+        let data = encoding::decode(data)?;
+        let key = encoding::decode(key)?;
+        let nonce = encoding::decode(nonce)?;
+        let aad = encoding::decode(aad)?;
+        let tag_len = match tag_len.is_empty() {
+            true => DEFAULT_TAG_LEN,
+            false => tag_len.parse::<usize>()?,
+        };
+
+        let encrypted = encryption::aead_encrypt(data, &key, &nonce, &aad, &mode, tag_len)?;
+
+        Ok(encoding::encode(encrypted, output)?)
+    })
+}
+
+/// inputs, outputs in base64, tag must be appended to the ciphertext.
+/// Empty tag_len means 16 bytes
+#[no_mangle]
+pub unsafe extern "system" fn aead_decrypt(
+    data_ptr: LPCWSTR,
+    key_ptr: LPCWSTR,
+    nonce_ptr: LPCWSTR,
+    aad_ptr: LPCWSTR,
+    mode_ptr: LPCWSTR,
+    tag_len_ptr: LPCWSTR,
+) -> LPCWSTR {
+    export(|| {
+        let (output, data) = encoding::output_prefix(cstring::from_widechar_ptr(data_ptr)?)?;
+        let key = cstring::from_widechar_ptr(key_ptr)?;
+        let nonce = cstring::from_widechar_ptr(nonce_ptr)?;
+        let aad = cstring::from_widechar_ptr(aad_ptr)?;
+        let mode = cstring::from_widechar_ptr(mode_ptr)?;
+        let tag_len = cstring::from_widechar_ptr(tag_len_ptr)?;
+
+        let data = encoding::decode(data)?;
+        let key = encoding::decode(key)?;
+        let nonce = encoding::decode(nonce)?;
+        let aad = encoding::decode(aad)?;
+        let tag_len = match tag_len.is_empty() {
+            true => DEFAULT_TAG_LEN,
+            false => tag_len.parse::<usize>()?,
+        };
+
+        let decrypted = encryption::aead_decrypt(data, &key, &nonce, &aad, &mode, tag_len)?;
+
+        Ok(encoding::encode(decrypted, output)?)
+    })
+}
@@ -1,51 +1,93 @@
-use crate::{
-    imp::encryption,
-    utils::base64,
-    wstring::{FromWidechar, ToWidechar, LPCWSTR},
-};
+use pk_ffi::{cstring, export, LPCWSTR};
+
+use crate::{imp::encryption, utils::encoding};
 
 /// inputs, outputs in base64
 #[no_mangle]
-pub unsafe extern "stdcall" fn aes_encrypt(
+pub unsafe extern "system" fn aes_encrypt(
     data_ptr: LPCWSTR,
     key_ptr: LPCWSTR,
     iv_ptr: LPCWSTR,
     mode_ptr: LPCWSTR,
     padding_ptr: LPCWSTR,
 ) -> LPCWSTR {
-    let data = String::from_widechar_ptr(data_ptr);
-    let key = String::from_widechar_ptr(key_ptr);
-    let iv = String::from_widechar_ptr(iv_ptr);
-    let mode = String::from_widechar_ptr(mode_ptr);
-    let padding = String::from_widechar_ptr(padding_ptr);
-
-    let mut data = base64::decode(data)?;
-    let key = base64::decode(key)?;
-    let iv = base64::decode(iv)?;
-    let encrypted = encryption::aes_encrypt(data.as_mut_slice(), &key, &iv, &mode, &padding)?;
-
-    base64::encode(encrypted).as_widechar_ptr()
+    export(|| {
+        let (output, data) = encoding::output_prefix(cstring::from_widechar_ptr(data_ptr)?)?;
+        let key = cstring::from_widechar_ptr(key_ptr)?;
+        let iv = cstring::from_widechar_ptr(iv_ptr)?;
+        let mode = cstring::from_widechar_ptr(mode_ptr)?;
+        let padding = cstring::from_widechar_ptr(padding_ptr)?;
+
+        let mut data = encoding::decode(data)?;
+        let key = encoding::decode(key)?;
+        let iv = encoding::decode(iv)?;
+        let encrypted = encryption::aes_encrypt(data.as_mut_slice(), &key, &iv, &mode, &padding)?;
+
+        Ok(encoding::encode(encrypted, output)?)
+    })
 }
 
 /// inputs, outputs in base64
 #[no_mangle]
-pub unsafe extern "stdcall" fn aes_decrypt(
+pub unsafe extern "system" fn aes_decrypt(
     data_ptr: LPCWSTR,
     key_ptr: LPCWSTR,
     iv_ptr: LPCWSTR,
     mode_ptr: LPCWSTR,
     padding_ptr: LPCWSTR,
 ) -> LPCWSTR {
-    let data = String::from_widechar_ptr(data_ptr);
-    let key = String::from_widechar_ptr(key_ptr);
-    let iv = String::from_widechar_ptr(iv_ptr);
-    let mode = String::from_widechar_ptr(mode_ptr);
-    let padding = String::from_widechar_ptr(padding_ptr);
-
-    let mut data = base64::decode(data)?;
-    let key = base64::decode(key)?;
-    let iv = base64::decode(iv)?;
-    let decrypted = encryption::aes_decrypt(data.as_mut_slice(), &key, &iv, &mode, &padding)?;
-
-    base64::encode(decrypted).as_widechar_ptr()
+    export(|| {
+        let (output, data) = encoding::output_prefix(cstring::from_widechar_ptr(data_ptr)?)?;
+        let key = cstring::from_widechar_ptr(key_ptr)?;
+        let iv = cstring::from_widechar_ptr(iv_ptr)?;
+        let mode = cstring::from_widechar_ptr(mode_ptr)?;
+        let padding = cstring::from_widechar_ptr(padding_ptr)?;
+
+        let mut data = encoding::decode(data)?;
+        let key = encoding::decode(key)?;
+        let iv = encoding::decode(iv)?;
+        let decrypted = encryption::aes_decrypt(data.as_mut_slice(), &key, &iv, &mode, &padding)?;
+
+        Ok(encoding::encode(decrypted, output)?)
+    })
+}
+
+/// inputs, outputs in base64. Empty mode means kw (RFC 3394), kwp is RFC 5649
+#[no_mangle]
+pub unsafe extern "system" fn aes_key_wrap(
+    data_ptr: LPCWSTR,
+    kek_ptr: LPCWSTR,
+    mode_ptr: LPCWSTR,
+) -> LPCWSTR {
+    export(|| {
+        let (output, data) = encoding::output_prefix(cstring::from_widechar_ptr(data_ptr)?)?;
+        let kek = cstring::from_widechar_ptr(kek_ptr)?;
+        let mode = cstring::from_widechar_ptr(mode_ptr)?;
+
+        let data = encoding::decode(data)?;
+        let kek = encoding::decode(kek)?;
+        let wrapped = encryption::aes_key_wrap(&data, &kek, &mode)?;
+
+        Ok(encoding::encode(wrapped, output)?)
+    })
+}
+
+/// inputs, outputs in base64
+#[no_mangle]
+pub unsafe extern "system" fn aes_key_unwrap(
+    data_ptr: LPCWSTR,
+    kek_ptr: LPCWSTR,
+    mode_ptr: LPCWSTR,
+) -> LPCWSTR {
+    export(|| {
+        let (output, data) = encoding::output_prefix(cstring::from_widechar_ptr(data_ptr)?)?;
+        let kek = cstring::from_widechar_ptr(kek_ptr)?;
+        let mode = cstring::from_widechar_ptr(mode_ptr)?;
+
+        let data = encoding::decode(data)?;
+        let kek = encoding::decode(kek)?;
+        let unwrapped = encryption::aes_key_unwrap(&data, &kek, &mode)?;
+
+        Ok(encoding::encode(unwrapped, output)?)
+    })
 }
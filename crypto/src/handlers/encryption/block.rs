@@ -0,0 +1,61 @@
+use pk_ffi::{cstring, export, LPCWSTR};
+
+use crate::{imp::encryption, utils::encoding};
+
+/// cipher: aes, blowfish, des, 3des, camellia, sm4, twofish, serpent.
+/// inputs, outputs in base64
+#[no_mangle]
+pub unsafe extern "system" fn block_encrypt(
+    cipher_ptr: LPCWSTR,
+    data_ptr: LPCWSTR,
+    key_ptr: LPCWSTR,
+    iv_ptr: LPCWSTR,
+    mode_ptr: LPCWSTR,
+    padding_ptr: LPCWSTR,
+) -> LPCWSTR {
+    export(|| {
+        let (output, cipher) = encoding::output_prefix(cstring::from_widechar_ptr(cipher_ptr)?)?;
+        let data = cstring::from_widechar_ptr(data_ptr)?;
+        let key = cstring::from_widechar_ptr(key_ptr)?;
+        let iv = cstring::from_widechar_ptr(iv_ptr)?;
+        let mode = cstring::from_widechar_ptr(mode_ptr)?;
+        let padding = cstring::from_widechar_ptr(padding_ptr)?;
+
+        let mut data = encoding::decode(data)?;
+        let key = encoding::decode(key)?;
+        let iv = encoding::decode(iv)?;
+        let encrypted =
+            encryption::block_encrypt(&cipher, data.as_mut_slice(), &key, &iv, &mode, &padding)?;
+
+        Ok(encoding::encode(encrypted, output)?)
+    })
+}
+
+/// cipher: aes, blowfish, des, 3des, camellia, sm4, twofish, serpent.
+/// inputs, outputs in base64
+#[no_mangle]
+pub unsafe extern "system" fn block_decrypt(
+    cipher_ptr: LPCWSTR,
+    data_ptr: LPCWSTR,
+    key_ptr: LPCWSTR,
+    iv_ptr: LPCWSTR,
+    mode_ptr: LPCWSTR,
+    padding_ptr: LPCWSTR,
+) -> LPCWSTR {
+    export(|| {
+        let (output, cipher) = encoding::output_prefix(cstring::from_widechar_ptr(cipher_ptr)?)?;
+        let data = cstring::from_widechar_ptr(data_ptr)?;
+        let key = cstring::from_widechar_ptr(key_ptr)?;
+        let iv = cstring::from_widechar_ptr(iv_ptr)?;
+        let mode = cstring::from_widechar_ptr(mode_ptr)?;
+        let padding = cstring::from_widechar_ptr(padding_ptr)?;
+
+        let mut data = encoding::decode(data)?;
+        let key = encoding::decode(key)?;
+        let iv = encoding::decode(iv)?;
+        let decrypted =
+            encryption::block_decrypt(&cipher, data.as_mut_slice(), &key, &iv, &mode, &padding)?;
+
+        Ok(encoding::encode(decrypted, output)?)
+    })
+}
@@ -0,0 +1,42 @@
+use pk_ffi::{cstring, export, LPCWSTR};
+
+use crate::{imp::encryption, utils::encoding};
+
+/// data in base64, key is a 32-byte urlsafe base64 fernet key.
+/// Returns a fernet token
+#[no_mangle]
+pub unsafe extern "system" fn fernet_encrypt(data_ptr: LPCWSTR, key_ptr: LPCWSTR) -> LPCWSTR {
+    export(|| {
+        let data = cstring::from_widechar_ptr(data_ptr)?;
+        let key = cstring::from_widechar_ptr(key_ptr)?;
+
+        let data = encoding::decode(data)?;
+
+        let token = encryption::fernet_encrypt(&data, &key)?;
+
+        Ok(token)
+    })
+}
+
+/// Returns data in base64. ttl is the maximum token age in seconds, empty means no age check
+#[no_mangle]
+pub unsafe extern "system" fn fernet_decrypt(
+    token_ptr: LPCWSTR,
+    key_ptr: LPCWSTR,
+    ttl_ptr: LPCWSTR,
+) -> LPCWSTR {
+    export(|| {
+        let (output, token) = encoding::output_prefix(cstring::from_widechar_ptr(token_ptr)?)?;
+        let key = cstring::from_widechar_ptr(key_ptr)?;
+        let ttl = cstring::from_widechar_ptr(ttl_ptr)?;
+
+        let ttl = match ttl.trim().is_empty() {
+            true => None,
+            false => Some(ttl.trim().parse::<u64>()?),
+        };
+
+        let decrypted = encryption::fernet_decrypt(&token, &key, ttl)?;
+
+        Ok(encoding::encode(decrypted, output)?)
+    })
+}
@@ -1,5 +1,11 @@
+mod aead;
 mod aes;
+mod block;
 mod blowfish;
+mod fernet;
+mod nacl;
+mod openssl;
 mod rc4;
 mod rsa;
+mod rsa_keys;
 mod xor;
@@ -0,0 +1,130 @@
+use pk_ffi::{cstring, export, LPCWSTR};
+
+use crate::{imp::encryption, utils::encoding};
+
+/// inputs, outputs in base64. Key is 32 bytes, nonce is 24 bytes.
+/// Output is compatible with nacl.secretbox / crypto_secretbox_easy (tag || ciphertext)
+#[no_mangle]
+pub unsafe extern "system" fn secretbox_encrypt(
+    data_ptr: LPCWSTR,
+    key_ptr: LPCWSTR,
+    nonce_ptr: LPCWSTR,
+) -> LPCWSTR {
+    export(|| {
+        let (output, data) = encoding::output_prefix(cstring::from_widechar_ptr(data_ptr)?)?;
+        let key = cstring::from_widechar_ptr(key_ptr)?;
+        let nonce = cstring::from_widechar_ptr(nonce_ptr)?;
+
+        let data = encoding::decode(data)?;
+        let key = encoding::decode(key)?;
+        let nonce = encoding::decode(nonce)?;
+
+        let encrypted = encryption::secretbox_encrypt(&data, &key, &nonce)?;
+
+        Ok(encoding::encode(encrypted, output)?)
+    })
+}
+
+/// inputs, outputs in base64
+#[no_mangle]
+pub unsafe extern "system" fn secretbox_decrypt(
+    data_ptr: LPCWSTR,
+    key_ptr: LPCWSTR,
+    nonce_ptr: LPCWSTR,
+) -> LPCWSTR {
+    export(|| {
+        let (output, data) = encoding::output_prefix(cstring::from_widechar_ptr(data_ptr)?)?;
+        let key = cstring::from_widechar_ptr(key_ptr)?;
+        let nonce = cstring::from_widechar_ptr(nonce_ptr)?;
+
+        let data = encoding::decode(data)?;
+        let key = encoding::decode(key)?;
+        let nonce = encoding::decode(nonce)?;
+
+        let decrypted = encryption::secretbox_decrypt(&data, &key, &nonce)?;
+
+        Ok(encoding::encode(decrypted, output)?)
+    })
+}
+
+/// data, nonce, output in base64. Keys are x25519 keys (raw base64, PEM or JWK):
+/// recipient's public key and sender's secret key
+#[no_mangle]
+pub unsafe extern "system" fn box_encrypt(
+    data_ptr: LPCWSTR,
+    public_key_ptr: LPCWSTR,
+    secret_key_ptr: LPCWSTR,
+    nonce_ptr: LPCWSTR,
+) -> LPCWSTR {
+    export(|| {
+        let (output, data) = encoding::output_prefix(cstring::from_widechar_ptr(data_ptr)?)?;
+        let public_key = cstring::from_widechar_ptr(public_key_ptr)?;
+        let secret_key = cstring::from_widechar_ptr(secret_key_ptr)?;
+        let nonce = cstring::from_widechar_ptr(nonce_ptr)?;
+
+        let data = encoding::decode(data)?;
+        let nonce = encoding::decode(nonce)?;
+
+        let encrypted = encryption::box_encrypt(&data, &public_key, &secret_key, &nonce)?;
+
+        Ok(encoding::encode(encrypted, output)?)
+    })
+}
+
+/// sender's public key and recipient's secret key
+#[no_mangle]
+pub unsafe extern "system" fn box_decrypt(
+    data_ptr: LPCWSTR,
+    public_key_ptr: LPCWSTR,
+    secret_key_ptr: LPCWSTR,
+    nonce_ptr: LPCWSTR,
+) -> LPCWSTR {
+    export(|| {
+        let (output, data) = encoding::output_prefix(cstring::from_widechar_ptr(data_ptr)?)?;
+        let public_key = cstring::from_widechar_ptr(public_key_ptr)?;
+        let secret_key = cstring::from_widechar_ptr(secret_key_ptr)?;
+        let nonce = cstring::from_widechar_ptr(nonce_ptr)?;
+
+        let data = encoding::decode(data)?;
+        let nonce = encoding::decode(nonce)?;
+
+        let decrypted = encryption::box_decrypt(&data, &public_key, &secret_key, &nonce)?;
+
+        Ok(encoding::encode(decrypted, output)?)
+    })
+}
+
+/// Anonymous encryption to a public key, compatible with libsodium crypto_box_seal
+#[no_mangle]
+pub unsafe extern "system" fn sealed_box_encrypt(
+    data_ptr: LPCWSTR,
+    public_key_ptr: LPCWSTR,
+) -> LPCWSTR {
+    export(|| {
+        let (output, data) = encoding::output_prefix(cstring::from_widechar_ptr(data_ptr)?)?;
+        let public_key = cstring::from_widechar_ptr(public_key_ptr)?;
+
+        let data = encoding::decode(data)?;
+
+        let encrypted = encryption::sealed_box_encrypt(&data, &public_key)?;
+
+        Ok(encoding::encode(encrypted, output)?)
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn sealed_box_decrypt(
+    data_ptr: LPCWSTR,
+    secret_key_ptr: LPCWSTR,
+) -> LPCWSTR {
+    export(|| {
+        let (output, data) = encoding::output_prefix(cstring::from_widechar_ptr(data_ptr)?)?;
+        let secret_key = cstring::from_widechar_ptr(secret_key_ptr)?;
+
+        let data = encoding::decode(data)?;
+
+        let decrypted = encryption::sealed_box_decrypt(&data, &secret_key)?;
+
+        Ok(encoding::encode(decrypted, output)?)
+    })
+}
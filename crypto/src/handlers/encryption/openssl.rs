@@ -0,0 +1,85 @@
+use pk_ffi::{cstring, export, LPCWSTR};
+
+use crate::{imp::encryption, utils::encoding};
+
+/// cipher: aes-256-cbc (default), aes-128-ctr, des-ede3-cbc, bf-cbc...
+/// kdf: evpkdf (default, md5 and 1 round) or pbkdf2 (sha256 and 10000 rounds).
+/// Returns base64 of `Salted__` || salt || ciphertext, the same as `openssl enc -a` and CryptoJS
+#[no_mangle]
+pub unsafe extern "system" fn openssl_encrypt(
+    data_ptr: LPCWSTR,
+    passphrase_ptr: LPCWSTR,
+    cipher_ptr: LPCWSTR,
+    kdf_ptr: LPCWSTR,
+    hash_type_ptr: LPCWSTR,
+    rounds_ptr: LPCWSTR,
+    salt_ptr: LPCWSTR,
+) -> LPCWSTR {
+    export(|| {
+        let (output, data) = encoding::output_prefix(cstring::from_widechar_ptr(data_ptr)?)?;
+        let mut data = encoding::decode(data)?;
+
+        let passphrase = cstring::from_widechar_ptr(passphrase_ptr)?;
+        let passphrase = encoding::decode(passphrase)?;
+
+        let cipher = cstring::from_widechar_ptr(cipher_ptr)?;
+        let kdf = cstring::from_widechar_ptr(kdf_ptr)?;
+        let hash_type = cstring::from_widechar_ptr(hash_type_ptr)?;
+
+        let rounds = cstring::from_widechar_ptr(rounds_ptr)?;
+        let rounds = match rounds.is_empty() {
+            true => None,
+            false => Some(rounds.parse::<u32>()?),
+        };
+
+        let salt = cstring::from_widechar_ptr(salt_ptr)?;
+        let salt = encoding::decode(salt)?;
+
+        let encrypted = encryption::openssl_encrypt(
+            &mut data,
+            &passphrase,
+            &cipher,
+            &kdf,
+            &hash_type,
+            rounds,
+            &salt,
+        )?;
+
+        Ok(encoding::encode(encrypted, output)?)
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn openssl_decrypt(
+    data_ptr: LPCWSTR,
+    passphrase_ptr: LPCWSTR,
+    cipher_ptr: LPCWSTR,
+    kdf_ptr: LPCWSTR,
+    hash_type_ptr: LPCWSTR,
+    rounds_ptr: LPCWSTR,
+) -> LPCWSTR {
+    export(|| {
+        // `openssl enc -a` wraps lines at 64 characters
+        let (output, data) = encoding::output_prefix(cstring::from_widechar_ptr(data_ptr)?)?;
+        let data: String = data.split_whitespace().collect();
+        let mut data = encoding::decode(data)?;
+
+        let passphrase = cstring::from_widechar_ptr(passphrase_ptr)?;
+        let passphrase = encoding::decode(passphrase)?;
+
+        let cipher = cstring::from_widechar_ptr(cipher_ptr)?;
+        let kdf = cstring::from_widechar_ptr(kdf_ptr)?;
+        let hash_type = cstring::from_widechar_ptr(hash_type_ptr)?;
+
+        let rounds = cstring::from_widechar_ptr(rounds_ptr)?;
+        let rounds = match rounds.is_empty() {
+            true => None,
+            false => Some(rounds.parse::<u32>()?),
+        };
+
+        let decrypted =
+            encryption::openssl_decrypt(&mut data, &passphrase, &cipher, &kdf, &hash_type, rounds)?;
+
+        Ok(encoding::encode(decrypted, output)?)
+    })
+}
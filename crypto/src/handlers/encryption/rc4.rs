@@ -1,19 +1,18 @@
-use crate::{
-    utils::base64,
-    wstring::{FromWidechar, ToWidechar, LPCWSTR},
-};
-use arc4::Arc4;
+use pk_ffi::{cstring, export, LPCWSTR};
+
+use crate::{imp::encryption, utils::encoding};
 
 #[no_mangle]
-pub unsafe extern "stdcall" fn rc4(data_ptr: LPCWSTR, key_ptr: LPCWSTR) -> LPCWSTR {
-    let data = String::from_widechar_ptr(data_ptr);
-    let mut data = base64::decode(data)?;
+pub unsafe extern "system" fn rc4(data_ptr: LPCWSTR, key_ptr: LPCWSTR) -> LPCWSTR {
+    export(|| {
+        let (output, data) = encoding::output_prefix(cstring::from_widechar_ptr(data_ptr)?)?;
+        let mut data = encoding::decode(data)?;
 
-    let key = String::from_widechar_ptr(key_ptr);
-    let key = base64::decode(key)?;
+        let key = cstring::from_widechar_ptr(key_ptr)?;
+        let key = encoding::decode(key)?;
 
-    let mut cipher = Arc4::with_key(&key);
-    cipher.encrypt(&mut data);
+        encryption::rc4(&mut data, &key);
 
-    base64::encode(data).as_widechar_ptr()
+        Ok(encoding::encode(data, output)?)
+    })
 }
@@ -1,72 +1,131 @@
-use crate::{
-    imp::encryption,
-    utils::base64,
-    wstring::{FromWidechar, ToWidechar, LPCWSTR},
-};
+use pk_ffi::{cstring, export, LPCWSTR};
+
+use crate::{imp::encryption, utils::encoding};
 
 #[no_mangle]
-pub unsafe extern "stdcall" fn rsa_pem_from_modulus(n_ptr: LPCWSTR, e_ptr: LPCWSTR) -> LPCWSTR {
-    let n = String::from_widechar_ptr(n_ptr);
-    let n = base64::decode(n)?;
+pub unsafe extern "system" fn rsa_pem_from_modulus(n_ptr: LPCWSTR, e_ptr: LPCWSTR) -> LPCWSTR {
+    export(|| {
+        let n = cstring::from_widechar_ptr(n_ptr)?;
+        let n = encoding::decode(n)?;
 
-    let e = String::from_widechar_ptr(e_ptr);
-    let e = base64::decode(e)?;
+        let e = cstring::from_widechar_ptr(e_ptr)?;
+        let e = encoding::decode(e)?;
 
-    let pem_encoded = encryption::modulus_to_pem(&n, &e)?;
-    pem_encoded.trim_end().as_widechar_ptr()
+        let pem_encoded = encryption::modulus_to_pem(&n, &e)?;
+        Ok(pem_encoded.trim_end().to_string())
+    })
 }
 
-/// hash_type needed if you want to use oaep mode
+/// hash_type needed if you want to use oaep mode, `nopadding` for raw rsa
 #[no_mangle]
-pub unsafe extern "stdcall" fn rsa_encrypt(
+pub unsafe extern "system" fn rsa_encrypt(
     data_ptr: LPCWSTR,
     key_ptr: LPCWSTR,
     hash_type_ptr: LPCWSTR,
 ) -> LPCWSTR {
-    let data = String::from_widechar_ptr(data_ptr);
-    let data = base64::decode(data)?;
+    export(|| {
+        let (output, data) = encoding::output_prefix(cstring::from_widechar_ptr(data_ptr)?)?;
+        let data = encoding::decode(data)?;
 
-    let key = String::from_widechar_ptr(key_ptr);
-    let hash_type = String::from_widechar_ptr(hash_type_ptr);
+        let key = cstring::from_widechar_ptr(key_ptr)?;
+        let hash_type = cstring::from_widechar_ptr(hash_type_ptr)?;
 
-    let encrypted = encryption::rsa_encrypt(&data, &key, &hash_type)?;
-    base64::encode(encrypted).as_widechar_ptr()
+        let encrypted = encryption::rsa_encrypt(&data, &key, &hash_type)?;
+        Ok(encoding::encode(encrypted, output)?)
+    })
 }
 
-/// hash_type needed if you want to use oaep mode
+/// hash_type needed if you want to use oaep mode, `nopadding` for raw rsa
 #[no_mangle]
-pub unsafe extern "stdcall" fn rsa_decrypt(
+pub unsafe extern "system" fn rsa_decrypt(
     data_ptr: LPCWSTR,
     key_ptr: LPCWSTR,
     hash_type_ptr: LPCWSTR,
 ) -> LPCWSTR {
-    let data = String::from_widechar_ptr(data_ptr);
-    let data = base64::decode(data)?;
+    export(|| {
+        let (output, data) = encoding::output_prefix(cstring::from_widechar_ptr(data_ptr)?)?;
+        let data = encoding::decode(data)?;
 
-    let key = String::from_widechar_ptr(key_ptr);
-    let hash_type = String::from_widechar_ptr(hash_type_ptr);
+        let key = cstring::from_widechar_ptr(key_ptr)?;
+        let hash_type = cstring::from_widechar_ptr(hash_type_ptr)?;
 
-    let decrypted = encryption::rsa_decrypt(&data, &key, &hash_type)?;
-    base64::encode(decrypted).as_widechar_ptr()
+        let decrypted = encryption::rsa_decrypt(&data, &key, &hash_type)?;
+        Ok(encoding::encode(decrypted, output)?)
+    })
 }
 
 #[no_mangle]
-pub unsafe extern "stdcall" fn rsa_sign(
+pub unsafe extern "system" fn rsa_sign(
     data_ptr: LPCWSTR,
     key_ptr: LPCWSTR,
     hash_type_ptr: LPCWSTR,
     mode_ptr: LPCWSTR,
 ) -> LPCWSTR {
-    let data = String::from_widechar_ptr(data_ptr);
-    let data = base64::decode(data)?;
+    export(|| {
+        let (output, data) = encoding::output_prefix(cstring::from_widechar_ptr(data_ptr)?)?;
+        let data = encoding::decode(data)?;
+
+        let key = cstring::from_widechar_ptr(key_ptr)?;
+
+        let hash_type = cstring::from_widechar_ptr(hash_type_ptr)?;
+
+        let mode = cstring::from_widechar_ptr(mode_ptr)?;
+
+        let signed = encryption::rsa_sign(&data, &key, &hash_type, &mode)?;
+
+        Ok(encoding::encode(signed, output)?)
+    })
+}
+
+/// data is a digest made with hash_type, in base64
+#[no_mangle]
+pub unsafe extern "system" fn rsa_sign_prehashed(
+    hashed_data_ptr: LPCWSTR,
+    key_ptr: LPCWSTR,
+    hash_type_ptr: LPCWSTR,
+    mode_ptr: LPCWSTR,
+) -> LPCWSTR {
+    export(|| {
+        let (output, hashed_data) =
+            encoding::output_prefix(cstring::from_widechar_ptr(hashed_data_ptr)?)?;
+        let hashed_data = encoding::decode(hashed_data)?;
+
+        let key = cstring::from_widechar_ptr(key_ptr)?;
+
+        let hash_type = cstring::from_widechar_ptr(hash_type_ptr)?;
+
+        let mode = cstring::from_widechar_ptr(mode_ptr)?;
+
+        let signed = encryption::rsa_sign_prehashed(&hashed_data, &key, &hash_type, &mode)?;
+
+        Ok(encoding::encode(signed, output)?)
+    })
+}
+
+/// Returns true or false
+#[no_mangle]
+pub unsafe extern "system" fn rsa_verify(
+    data_ptr: LPCWSTR,
+    signature_ptr: LPCWSTR,
+    key_ptr: LPCWSTR,
+    hash_type_ptr: LPCWSTR,
+    mode_ptr: LPCWSTR,
+) -> LPCWSTR {
+    export(|| {
+        let data = cstring::from_widechar_ptr(data_ptr)?;
+        let data = encoding::decode(data)?;
+
+        let signature = cstring::from_widechar_ptr(signature_ptr)?;
+        let signature = encoding::decode(signature)?;
 
-    let key = String::from_widechar_ptr(key_ptr);
+        let key = cstring::from_widechar_ptr(key_ptr)?;
 
-    let hash_type = String::from_widechar_ptr(hash_type_ptr);
+        let hash_type = cstring::from_widechar_ptr(hash_type_ptr)?;
 
-    let mode = String::from_widechar_ptr(mode_ptr);
+        let mode = cstring::from_widechar_ptr(mode_ptr)?;
 
-    let signed = encryption::rsa_sign(&data, &key, &hash_type, &mode)?;
+        let verified = encryption::rsa_verify(&data, &signature, &key, &hash_type, &mode)?;
 
-    base64::encode(signed).as_widechar_ptr()
+        Ok(verified.to_string())
+    })
 }
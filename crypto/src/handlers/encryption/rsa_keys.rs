@@ -0,0 +1,64 @@
+use pk_ffi::{cstring, export, LPCWSTR};
+
+use crate::{
+    imp::encryption::{self, DEFAULT_RSA_BITS},
+    utils::encoding,
+};
+
+/// format: pkcs1, pkcs8 (spki for public key), pkcs1-der, pkcs8-der, jwk.
+/// Empty bits means 2048, empty format means pkcs8.
+/// Returns `private|public`
+#[no_mangle]
+pub unsafe extern "system" fn rsa_generate(bits_ptr: LPCWSTR, format_ptr: LPCWSTR) -> LPCWSTR {
+    export(|| {
+        let bits = cstring::from_widechar_ptr(bits_ptr)?;
+        let format = cstring::from_widechar_ptr(format_ptr)?;
+
+        let bits = match bits.is_empty() {
+            true => DEFAULT_RSA_BITS,
+            false => bits.parse::<usize>()?,
+        };
+
+        let (priv_key, pub_key) = encryption::rsa_generate(bits, &format)?;
+        Ok(format!("{}|{}", priv_key.trim_end(), pub_key.trim_end()))
+    })
+}
+
+/// key can be PEM, base64 DER or JWK, private key stays private
+#[no_mangle]
+pub unsafe extern "system" fn rsa_convert_key(key_ptr: LPCWSTR, format_ptr: LPCWSTR) -> LPCWSTR {
+    export(|| {
+        let key = cstring::from_widechar_ptr(key_ptr)?;
+        let format = cstring::from_widechar_ptr(format_ptr)?;
+
+        let converted = encryption::rsa_convert_key(&key, &format)?;
+        Ok(converted.trim_end().to_string())
+    })
+}
+
+/// key can be PEM, base64 DER or JWK, both private and public
+#[no_mangle]
+pub unsafe extern "system" fn rsa_public_key(key_ptr: LPCWSTR, format_ptr: LPCWSTR) -> LPCWSTR {
+    export(|| {
+        let key = cstring::from_widechar_ptr(key_ptr)?;
+        let format = cstring::from_widechar_ptr(format_ptr)?;
+
+        let pub_key = encryption::rsa_public_key(&key, &format)?;
+        Ok(pub_key.trim_end().to_string())
+    })
+}
+
+/// Reverse of rsa_pem_from_modulus. Returns `n|e` in base64, or as set by an output prefix on key (`hex>`)
+#[no_mangle]
+pub unsafe extern "system" fn rsa_modulus_from_pem(key_ptr: LPCWSTR) -> LPCWSTR {
+    export(|| {
+        let (output, key) = encoding::output_prefix(cstring::from_widechar_ptr(key_ptr)?)?;
+
+        let (n, e) = encryption::pem_to_modulus(&key)?;
+        Ok(format!(
+            "{}|{}",
+            encoding::encode(n, output)?,
+            encoding::encode(e, output)?
+        ))
+    })
+}
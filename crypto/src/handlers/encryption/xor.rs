@@ -1,23 +1,23 @@
-use crate::{
-    imp::encryption,
-    utils::base64,
-    wstring::{FromWidechar, ToWidechar, LPCWSTR},
-};
+use pk_ffi::{cstring, export, LPCWSTR};
+
+use crate::{imp::encryption, utils::encoding};
 
 #[no_mangle]
-pub unsafe extern "stdcall" fn xor(data_ptr: LPCWSTR, key_ptr: LPCWSTR) -> LPCWSTR {
-    let data = String::from_widechar_ptr(data_ptr);
-    let mut data = base64::decode(data)?;
+pub unsafe extern "system" fn xor(data_ptr: LPCWSTR, key_ptr: LPCWSTR) -> LPCWSTR {
+    export(|| {
+        let (output, data) = encoding::output_prefix(cstring::from_widechar_ptr(data_ptr)?)?;
+        let mut data = encoding::decode(data)?;
 
-    let key = String::from_widechar_ptr(key_ptr);
+        let key = cstring::from_widechar_ptr(key_ptr)?;
 
-    match key.parse::<u32>() {
-        Ok(key) => encryption::xor_simple(&mut data, key),
-        Err(_) => {
-            let key = base64::decode(key)?;
-            encryption::xor(&mut data, &key)
-        }
-    };
+        match key.parse::<u32>() {
+            Ok(key) => encryption::xor_simple(&mut data, key),
+            Err(_) => {
+                let key = encoding::decode(key)?;
+                encryption::xor(&mut data, &key)
+            }
+        };
 
-    base64::encode(data).as_widechar_ptr()
+        Ok(encoding::encode(data, output)?)
+    })
 }
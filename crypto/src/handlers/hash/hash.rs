@@ -1,16 +1,16 @@
-use crate::{
-    imp::hashing,
-    utils::base64,
-    wstring::{FromWidechar, ToWidechar, LPCWSTR},
-};
+use pk_ffi::{cstring, export, LPCWSTR};
+
+use crate::{imp::hashing, utils::encoding};
 
 #[no_mangle]
-pub unsafe extern "stdcall" fn hash(hash_type: LPCWSTR, data_ptr: LPCWSTR) -> LPCWSTR {
-    let data = String::from_widechar_ptr(data_ptr);
-    let data = base64::decode(data)?;
-    let hash_type = String::from_widechar_ptr(hash_type);
+pub unsafe extern "system" fn hash(hash_type: LPCWSTR, data_ptr: LPCWSTR) -> LPCWSTR {
+    export(|| {
+        let data = cstring::from_widechar_ptr(data_ptr)?;
+        let data = encoding::decode(data)?;
+        let (output, hash_type) = encoding::output_prefix(cstring::from_widechar_ptr(hash_type)?)?;
 
-    let hashed = hashing::make_hash(data, &hash_type)?;
+        let hashed = hashing::make_hash(data, &hash_type)?;
 
-    base64::encode(hashed).as_widechar_ptr()
+        Ok(encoding::encode(hashed, output)?)
+    })
 }
@@ -1,24 +1,24 @@
-use crate::{
-    imp::hashing,
-    utils::base64,
-    wstring::{FromWidechar, ToWidechar, LPCWSTR},
-};
+use pk_ffi::{cstring, export, LPCWSTR};
+
+use crate::{imp::hashing, utils::encoding};
 
 #[no_mangle]
-pub unsafe extern "stdcall" fn hmac(
+pub unsafe extern "system" fn hmac(
     hash_type: LPCWSTR,
     data_ptr: LPCWSTR,
     key_ptr: LPCWSTR,
 ) -> LPCWSTR {
-    let data = String::from_widechar_ptr(data_ptr);
-    let data = base64::decode(data)?;
+    export(|| {
+        let data = cstring::from_widechar_ptr(data_ptr)?;
+        let data = encoding::decode(data)?;
 
-    let key = String::from_widechar_ptr(key_ptr);
-    let key = base64::decode(key)?;
+        let key = cstring::from_widechar_ptr(key_ptr)?;
+        let key = encoding::decode(key)?;
 
-    let hash_type = String::from_widechar_ptr(hash_type);
+        let (output, hash_type) = encoding::output_prefix(cstring::from_widechar_ptr(hash_type)?)?;
 
-    let hashed = hashing::make_hmac(&data, &key, &hash_type)?;
+        let hashed = hashing::make_hmac(&data, &key, &hash_type)?;
 
-    base64::encode(hashed).as_widechar_ptr()
+        Ok(encoding::encode(hashed, output)?)
+    })
 }
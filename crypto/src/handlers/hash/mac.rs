@@ -0,0 +1,30 @@
+use pk_ffi::{cstring, export, LPCWSTR};
+
+use crate::{imp::hashing, utils::encoding};
+
+/// mac_type: cmac, cmac-3des, gmac, poly1305, siphash, siphash-1-3, siphash128.
+/// nonce is needed only for gmac, all in base64
+#[no_mangle]
+pub unsafe extern "system" fn mac(
+    mac_type: LPCWSTR,
+    data_ptr: LPCWSTR,
+    key_ptr: LPCWSTR,
+    nonce_ptr: LPCWSTR,
+) -> LPCWSTR {
+    export(|| {
+        let data = cstring::from_widechar_ptr(data_ptr)?;
+        let data = encoding::decode(data)?;
+
+        let key = cstring::from_widechar_ptr(key_ptr)?;
+        let key = encoding::decode(key)?;
+
+        let nonce = cstring::from_widechar_ptr(nonce_ptr)?;
+        let nonce = encoding::decode(nonce)?;
+
+        let (output, mac_type) = encoding::output_prefix(cstring::from_widechar_ptr(mac_type)?)?;
+
+        let mac = hashing::make_mac(&data, &key, &mac_type, &nonce)?;
+
+        Ok(encoding::encode(mac, output)?)
+    })
+}
@@ -1,2 +1,5 @@
 mod hash;
 mod hmac;
+mod mac;
+mod stream;
+mod xof;
@@ -0,0 +1,67 @@
+use pk_ffi::{cstring, export, LPCWSTR};
+
+use crate::{imp::hashing, utils::encoding};
+
+/// Returns session id, use it with hash_update and hash_final
+#[no_mangle]
+pub unsafe extern "system" fn hash_init(hash_type: LPCWSTR) -> LPCWSTR {
+    export(|| {
+        let hash_type = cstring::from_widechar_ptr(hash_type)?;
+
+        let id = hashing::hash_init(&hash_type)?;
+
+        Ok(id)
+    })
+}
+
+/// data in base64
+#[no_mangle]
+pub unsafe extern "system" fn hash_update(id_ptr: LPCWSTR, data_ptr: LPCWSTR) -> LPCWSTR {
+    export(|| {
+        let id = cstring::from_widechar_ptr(id_ptr)?;
+
+        let data = cstring::from_widechar_ptr(data_ptr)?;
+        let data = encoding::decode(data)?;
+
+        hashing::hash_update(&id, &data)?;
+
+        Ok("OK")
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn hash_final(id_ptr: LPCWSTR) -> LPCWSTR {
+    export(|| {
+        let (output, id) = encoding::output_prefix(cstring::from_widechar_ptr(id_ptr)?)?;
+
+        let hashed = hashing::hash_final(&id)?;
+
+        Ok(encoding::encode(hashed, output)?)
+    })
+}
+
+/// key in base64. Returns session id, use it with hmac_update and hmac_final
+#[no_mangle]
+pub unsafe extern "system" fn hmac_init(hash_type: LPCWSTR, key_ptr: LPCWSTR) -> LPCWSTR {
+    export(|| {
+        let hash_type = cstring::from_widechar_ptr(hash_type)?;
+
+        let key = cstring::from_widechar_ptr(key_ptr)?;
+        let key = encoding::decode(key)?;
+
+        let id = hashing::hmac_init(&hash_type, &key)?;
+
+        Ok(id)
+    })
+}
+
+/// data in base64
+#[no_mangle]
+pub unsafe extern "system" fn hmac_update(id_ptr: LPCWSTR, data_ptr: LPCWSTR) -> LPCWSTR {
+    hash_update(id_ptr, data_ptr)
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn hmac_final(id_ptr: LPCWSTR) -> LPCWSTR {
+    hash_final(id_ptr)
+}
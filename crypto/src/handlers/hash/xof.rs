@@ -0,0 +1,35 @@
+use pk_ffi::{cstring, export, LPCWSTR};
+
+use crate::{imp::hashing, utils::encoding};
+
+/// hash_type: shake128, shake256, cshake128, cshake256, kmac128, kmac256, blake3.
+/// customization and key are optional, all in base64
+#[no_mangle]
+pub unsafe extern "system" fn hash_xof(
+    hash_type: LPCWSTR,
+    data_ptr: LPCWSTR,
+    output_length_ptr: LPCWSTR,
+    customization_ptr: LPCWSTR,
+    key_ptr: LPCWSTR,
+) -> LPCWSTR {
+    export(|| {
+        let data = cstring::from_widechar_ptr(data_ptr)?;
+        let data = encoding::decode(data)?;
+
+        let (output, hash_type) = encoding::output_prefix(cstring::from_widechar_ptr(hash_type)?)?;
+
+        let output_length = cstring::from_widechar_ptr(output_length_ptr)?;
+        let output_length = output_length.parse::<usize>()?;
+
+        let customization = cstring::from_widechar_ptr(customization_ptr)?;
+        let customization = encoding::decode(customization)?;
+
+        let key = cstring::from_widechar_ptr(key_ptr)?;
+        let key = encoding::decode(key)?;
+
+        let hashed =
+            hashing::make_hash_xof(&data, &hash_type, output_length, &customization, &key)?;
+
+        Ok(encoding::encode(hashed, output)?)
+    })
+}
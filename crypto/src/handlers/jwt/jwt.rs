@@ -0,0 +1,87 @@
+use pk_ffi::{cstring, export, LPCWSTR};
+
+use crate::imp::jwt;
+
+/// header and payload are JSON, header may be empty.
+/// key: base64 secret for HS*, PEM/DER/JWK for RS*, PS*, ES*, EdDSA. ES512 is not supported
+#[no_mangle]
+pub unsafe extern "system" fn jwt_sign(
+    header_ptr: LPCWSTR,
+    payload_ptr: LPCWSTR,
+    key_ptr: LPCWSTR,
+    alg_ptr: LPCWSTR,
+) -> LPCWSTR {
+    export(|| {
+        let header = cstring::from_widechar_ptr(header_ptr)?;
+        let payload = cstring::from_widechar_ptr(payload_ptr)?;
+        let key = cstring::from_widechar_ptr(key_ptr)?;
+        let alg = cstring::from_widechar_ptr(alg_ptr)?;
+
+        let token = jwt::jwt_sign(&header, &payload, &key, &alg)?;
+
+        Ok(token)
+    })
+}
+
+/// Returns true or false, alg is required and pins the expected algorithm. ES512 is not supported
+#[no_mangle]
+pub unsafe extern "system" fn jwt_verify(
+    token_ptr: LPCWSTR,
+    key_ptr: LPCWSTR,
+    alg_ptr: LPCWSTR,
+) -> LPCWSTR {
+    export(|| {
+        let token = cstring::from_widechar_ptr(token_ptr)?;
+        let key = cstring::from_widechar_ptr(key_ptr)?;
+        let alg = cstring::from_widechar_ptr(alg_ptr)?;
+
+        let verified = jwt::jwt_verify(&token, &key, &alg)?;
+
+        Ok(verified.to_string())
+    })
+}
+
+/// Returns {"header": {...}, "payload": {...}} without checking the signature
+#[no_mangle]
+pub unsafe extern "system" fn jwt_decode(token_ptr: LPCWSTR) -> LPCWSTR {
+    export(|| {
+        let token = cstring::from_widechar_ptr(token_ptr)?;
+
+        let decoded = jwt::jwt_decode(&token)?;
+
+        Ok(decoded)
+    })
+}
+
+/// plaintext is a string (usually JSON or a signed JWT), key is RSA public key
+#[no_mangle]
+pub unsafe extern "system" fn jwe_encrypt(
+    plaintext_ptr: LPCWSTR,
+    key_ptr: LPCWSTR,
+    alg_ptr: LPCWSTR,
+    enc_ptr: LPCWSTR,
+) -> LPCWSTR {
+    export(|| {
+        let plaintext = cstring::from_widechar_ptr(plaintext_ptr)?;
+        let key = cstring::from_widechar_ptr(key_ptr)?;
+        let alg = cstring::from_widechar_ptr(alg_ptr)?;
+        let enc = cstring::from_widechar_ptr(enc_ptr)?;
+
+        let token = jwt::jwe_encrypt(plaintext.as_bytes(), &key, &alg, &enc)?;
+
+        Ok(token)
+    })
+}
+
+/// key is RSA private key, returns the plaintext string
+#[no_mangle]
+pub unsafe extern "system" fn jwe_decrypt(token_ptr: LPCWSTR, key_ptr: LPCWSTR) -> LPCWSTR {
+    export(|| {
+        let token = cstring::from_widechar_ptr(token_ptr)?;
+        let key = cstring::from_widechar_ptr(key_ptr)?;
+
+        let plaintext = jwt::jwe_decrypt(&token, &key)?;
+
+        Ok(String::from_utf8(plaintext)?)
+    })
+}
@@ -1,37 +1,40 @@
+use argon2::Params;
 use bcrypt::BcryptError;
+use pk_ffi::{cstring, export, Error, LPCWSTR};
 
 use crate::{
     imp::kdf::{self, error::KdfError},
-    utils::base64,
-    wstring::{FromWidechar, ToWidechar, LPCWSTR},
+    utils::{base64, encoding},
 };
 
 #[no_mangle]
-pub unsafe extern "stdcall" fn bcrypt(
+pub unsafe extern "system" fn bcrypt(
     data_ptr: LPCWSTR,
     cost_ptr: LPCWSTR,
     salt_ptr: LPCWSTR,
 ) -> LPCWSTR {
-    let data = String::from_widechar_ptr(data_ptr);
-    let data = base64::decode(data)?;
+    export(|| {
+        let data = cstring::from_widechar_ptr(data_ptr)?;
+        let data = encoding::decode(data)?;
 
-    let cost = String::from_widechar_ptr(cost_ptr);
-    let cost = cost.parse::<u32>()?;
+        let cost = cstring::from_widechar_ptr(cost_ptr)?;
+        let cost = cost.parse::<u32>()?;
 
-    let salt = String::from_widechar_ptr(salt_ptr);
-    let salt = match base64::decode(&salt) {
-        Ok(salt) => salt,
-        Err(_) => base64::decode_bcrypt(salt)?,
-    };
+        let salt = cstring::from_widechar_ptr(salt_ptr)?;
+        let salt = match encoding::decode(&salt) {
+            Ok(salt) => salt,
+            Err(_) => base64::decode_bcrypt(salt)?,
+        };
 
-    let salt_len = salt.len();
+        let salt_len = salt.len();
 
-    let salt: [u8; 16] = salt
-        .try_into()
-        .or(Err(KdfError::Bcrypt(BcryptError::InvalidSaltLen(salt_len))))?;
+        let salt: [u8; 16] = salt
+            .try_into()
+            .or(Err(KdfError::Bcrypt(BcryptError::InvalidSaltLen(salt_len))))?;
 
-    let hashed = kdf::bcrypt(data, cost, salt)?;
-    hashed.as_widechar_ptr()
+        let hashed = kdf::bcrypt(data, cost, salt)?;
+        Ok(hashed)
+    })
 }
 
 /// Recommended values sufficient for most use-cases
@@ -41,7 +44,7 @@ pub unsafe extern "stdcall" fn bcrypt(
 /// - `len = 32`
 /// - `len(salt) = 16 bytes (max - 63)`
 #[no_mangle]
-pub unsafe extern "stdcall" fn scrypt(
+pub unsafe extern "system" fn scrypt(
     data_ptr: LPCWSTR,
     log_n_ptr: LPCWSTR,
     r_ptr: LPCWSTR,
@@ -49,79 +52,281 @@ pub unsafe extern "stdcall" fn scrypt(
     len_ptr: LPCWSTR,
     salt_ptr: LPCWSTR,
 ) -> LPCWSTR {
-    let data = String::from_widechar_ptr(data_ptr);
-    let data = base64::decode(data)?;
+    export(|| {
+        let (output, data) = encoding::output_prefix(cstring::from_widechar_ptr(data_ptr)?)?;
+        let data = encoding::decode(data)?;
 
-    let log_n = String::from_widechar_ptr(log_n_ptr);
-    let log_n = log_n.parse::<u8>()?;
+        let log_n = cstring::from_widechar_ptr(log_n_ptr)?;
+        let log_n = log_n.parse::<u8>()?;
 
-    let r = String::from_widechar_ptr(r_ptr);
-    let r = r.parse::<u32>()?;
+        let r = cstring::from_widechar_ptr(r_ptr)?;
+        let r = r.parse::<u32>()?;
 
-    let p = String::from_widechar_ptr(p_ptr);
-    let p = p.parse::<u32>()?;
+        let p = cstring::from_widechar_ptr(p_ptr)?;
+        let p = p.parse::<u32>()?;
 
-    let len = String::from_widechar_ptr(len_ptr);
-    let len = len.parse::<usize>()?;
+        let len = cstring::from_widechar_ptr(len_ptr)?;
+        let len = len.parse::<usize>()?;
 
-    let salt = String::from_widechar_ptr(salt_ptr);
-    let salt = base64::decode(salt)?;
+        let salt = cstring::from_widechar_ptr(salt_ptr)?;
+        let salt = encoding::decode(salt)?;
 
-    let hashed = kdf::scrypt(&data, log_n, r, p, len, &salt)?;
+        let hashed = kdf::scrypt(&data, log_n, r, p, len, &salt)?;
 
-    hashed.as_widechar_ptr()
+        Ok(encoding::encode(hashed, output)?)
+    })
 }
 
 #[no_mangle]
-pub unsafe extern "stdcall" fn pbkdf2(
+pub unsafe extern "system" fn pbkdf2(
     data_ptr: LPCWSTR,
     salt_ptr: LPCWSTR,
     rounds_ptr: LPCWSTR,
     len_ptr: LPCWSTR,
     hash_type_ptr: LPCWSTR,
 ) -> LPCWSTR {
-    let data = String::from_widechar_ptr(data_ptr);
-    let data = base64::decode(data)?;
+    export(|| {
+        let (output, data) = encoding::output_prefix(cstring::from_widechar_ptr(data_ptr)?)?;
+        let data = encoding::decode(data)?;
 
-    let salt = String::from_widechar_ptr(salt_ptr);
-    let salt = base64::decode(salt)?;
+        let salt = cstring::from_widechar_ptr(salt_ptr)?;
+        let salt = encoding::decode(salt)?;
 
-    let rounds = String::from_widechar_ptr(rounds_ptr);
-    let rounds = rounds.parse::<u32>()?;
+        let rounds = cstring::from_widechar_ptr(rounds_ptr)?;
+        let rounds = rounds.parse::<u32>()?;
 
-    let len = String::from_widechar_ptr(len_ptr);
-    let len = len.parse::<usize>()?;
+        let len = cstring::from_widechar_ptr(len_ptr)?;
+        let len = len.parse::<usize>()?;
 
-    let hash_type = String::from_widechar_ptr(hash_type_ptr);
+        let hash_type = cstring::from_widechar_ptr(hash_type_ptr)?;
 
-    let hashed = kdf::pbkdf2(&data, &salt, rounds, len, &hash_type)?;
+        let hashed = kdf::pbkdf2(&data, &salt, rounds, len, &hash_type)?;
 
-    base64::encode(hashed).as_widechar_ptr()
+        Ok(encoding::encode(hashed, output)?)
+    })
 }
 
 #[no_mangle]
-pub unsafe extern "stdcall" fn evpkdf(
+pub unsafe extern "system" fn evpkdf(
     data_ptr: LPCWSTR,
     salt_ptr: LPCWSTR,
     rounds_ptr: LPCWSTR,
     output_len_ptr: LPCWSTR,
     hash_type_ptr: LPCWSTR,
 ) -> LPCWSTR {
-    let data = String::from_widechar_ptr(data_ptr);
-    let data = base64::decode(data)?;
+    export(|| {
+        let (output, data) = encoding::output_prefix(cstring::from_widechar_ptr(data_ptr)?)?;
+        let data = encoding::decode(data)?;
+
+        let salt = cstring::from_widechar_ptr(salt_ptr)?;
+        let salt = encoding::decode(salt)?;
+
+        let rounds = cstring::from_widechar_ptr(rounds_ptr)?;
+        let rounds = rounds.parse::<usize>()?;
+
+        let len = cstring::from_widechar_ptr(output_len_ptr)?;
+        let len = len.parse::<usize>()?;
+
+        let hash_type = cstring::from_widechar_ptr(hash_type_ptr)?;
+
+        let hashed = kdf::evpkdf(&data, &salt, rounds, len, &hash_type)?;
+
+        Ok(encoding::encode(hashed, output)?)
+    })
+}
+
+/// Empty m_cost, t_cost and p_cost mean defaults (19456 KiB, 2, 1)
+unsafe fn argon2_params(
+    m_cost_ptr: LPCWSTR,
+    t_cost_ptr: LPCWSTR,
+    p_cost_ptr: LPCWSTR,
+) -> Result<(u32, u32, u32), Error> {
+    let parse = |ptr, default: u32| -> Result<u32, Error> {
+        let value = cstring::from_widechar_ptr(ptr)?;
+        match value.is_empty() {
+            true => Ok(default),
+            false => Ok(value.parse::<u32>()?),
+        }
+    };
+
+    Ok((
+        parse(m_cost_ptr, Params::DEFAULT_M_COST)?,
+        parse(t_cost_ptr, Params::DEFAULT_T_COST)?,
+        parse(p_cost_ptr, Params::DEFAULT_P_COST)?,
+    ))
+}
+
+/// Returns raw key in base64. Variant: argon2i, argon2d, argon2id (default if empty)
+#[no_mangle]
+pub unsafe extern "system" fn argon2(
+    data_ptr: LPCWSTR,
+    salt_ptr: LPCWSTR,
+    variant_ptr: LPCWSTR,
+    m_cost_ptr: LPCWSTR,
+    t_cost_ptr: LPCWSTR,
+    p_cost_ptr: LPCWSTR,
+    len_ptr: LPCWSTR,
+) -> LPCWSTR {
+    export(|| {
+        let (output, data) = encoding::output_prefix(cstring::from_widechar_ptr(data_ptr)?)?;
+        let data = encoding::decode(data)?;
+
+        let salt = cstring::from_widechar_ptr(salt_ptr)?;
+        let salt = encoding::decode(salt)?;
+
+        let variant = cstring::from_widechar_ptr(variant_ptr)?;
+
+        let (m_cost, t_cost, p_cost) = argon2_params(m_cost_ptr, t_cost_ptr, p_cost_ptr)?;
+
+        let len = cstring::from_widechar_ptr(len_ptr)?;
+        let len = len.parse::<usize>()?;
+
+        let hashed = kdf::argon2(&data, &salt, &variant, m_cost, t_cost, p_cost, len)?;
+
+        Ok(encoding::encode(hashed, output)?)
+    })
+}
+
+/// Returns PHC string, random salt is generated if salt is empty
+#[no_mangle]
+pub unsafe extern "system" fn argon2_hash(
+    data_ptr: LPCWSTR,
+    salt_ptr: LPCWSTR,
+    variant_ptr: LPCWSTR,
+    m_cost_ptr: LPCWSTR,
+    t_cost_ptr: LPCWSTR,
+    p_cost_ptr: LPCWSTR,
+) -> LPCWSTR {
+    export(|| {
+        let data = cstring::from_widechar_ptr(data_ptr)?;
+        let data = encoding::decode(data)?;
+
+        let salt = cstring::from_widechar_ptr(salt_ptr)?;
+        let salt = encoding::decode(salt)?;
+
+        let variant = cstring::from_widechar_ptr(variant_ptr)?;
+
+        let (m_cost, t_cost, p_cost) = argon2_params(m_cost_ptr, t_cost_ptr, p_cost_ptr)?;
+
+        let hashed = kdf::argon2_hash(&data, &salt, &variant, m_cost, t_cost, p_cost)?;
+
+        Ok(hashed)
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn hkdf(
+    data_ptr: LPCWSTR,
+    salt_ptr: LPCWSTR,
+    info_ptr: LPCWSTR,
+    len_ptr: LPCWSTR,
+    hash_type_ptr: LPCWSTR,
+) -> LPCWSTR {
+    export(|| {
+        let (output, data) = encoding::output_prefix(cstring::from_widechar_ptr(data_ptr)?)?;
+        let data = encoding::decode(data)?;
+
+        let salt = cstring::from_widechar_ptr(salt_ptr)?;
+        let salt = encoding::decode(salt)?;
+
+        let info = cstring::from_widechar_ptr(info_ptr)?;
+        let info = encoding::decode(info)?;
+
+        let len = cstring::from_widechar_ptr(len_ptr)?;
+        let len = len.parse::<usize>()?;
+
+        let hash_type = cstring::from_widechar_ptr(hash_type_ptr)?;
+
+        let hashed = kdf::hkdf(&data, &salt, &info, len, &hash_type)?;
+
+        Ok(encoding::encode(hashed, output)?)
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn hkdf_extract(
+    data_ptr: LPCWSTR,
+    salt_ptr: LPCWSTR,
+    hash_type_ptr: LPCWSTR,
+) -> LPCWSTR {
+    export(|| {
+        let (output, data) = encoding::output_prefix(cstring::from_widechar_ptr(data_ptr)?)?;
+        let data = encoding::decode(data)?;
+
+        let salt = cstring::from_widechar_ptr(salt_ptr)?;
+        let salt = encoding::decode(salt)?;
+
+        let hash_type = cstring::from_widechar_ptr(hash_type_ptr)?;
+
+        let prk = kdf::hkdf_extract(&data, &salt, &hash_type)?;
+
+        Ok(encoding::encode(prk, output)?)
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn hkdf_expand(
+    prk_ptr: LPCWSTR,
+    info_ptr: LPCWSTR,
+    len_ptr: LPCWSTR,
+    hash_type_ptr: LPCWSTR,
+) -> LPCWSTR {
+    export(|| {
+        let (output, prk) = encoding::output_prefix(cstring::from_widechar_ptr(prk_ptr)?)?;
+        let prk = encoding::decode(prk)?;
 
-    let salt = String::from_widechar_ptr(salt_ptr);
-    let salt = base64::decode(salt)?;
+        let info = cstring::from_widechar_ptr(info_ptr)?;
+        let info = encoding::decode(info)?;
 
-    let rounds = String::from_widechar_ptr(rounds_ptr);
-    let rounds = rounds.parse::<usize>()?;
+        let len = cstring::from_widechar_ptr(len_ptr)?;
+        let len = len.parse::<usize>()?;
 
-    let len = String::from_widechar_ptr(output_len_ptr);
-    let len = len.parse::<usize>()?;
+        let hash_type = cstring::from_widechar_ptr(hash_type_ptr)?;
+
+        let hashed = kdf::hkdf_expand(&prk, &info, len, &hash_type)?;
+
+        Ok(encoding::encode(hashed, output)?)
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "system" fn bcrypt_pbkdf(
+    data_ptr: LPCWSTR,
+    salt_ptr: LPCWSTR,
+    rounds_ptr: LPCWSTR,
+    len_ptr: LPCWSTR,
+) -> LPCWSTR {
+    export(|| {
+        let (output, data) = encoding::output_prefix(cstring::from_widechar_ptr(data_ptr)?)?;
+        let data = encoding::decode(data)?;
+
+        let salt = cstring::from_widechar_ptr(salt_ptr)?;
+        let salt = encoding::decode(salt)?;
+
+        let rounds = cstring::from_widechar_ptr(rounds_ptr)?;
+        let rounds = rounds.parse::<u32>()?;
+
+        let len = cstring::from_widechar_ptr(len_ptr)?;
+        let len = len.parse::<usize>()?;
+
+        let hashed = kdf::bcrypt_pbkdf(&data, &salt, rounds, len)?;
+
+        Ok(encoding::encode(hashed, output)?)
+    })
+}
+
+/// Hash is bcrypt ($2a$...) or PHC string ($argon2id$..., $scrypt$..., $pbkdf2-sha256$...).
+/// Returns true or false
+#[no_mangle]
+pub unsafe extern "system" fn password_verify(data_ptr: LPCWSTR, hash_ptr: LPCWSTR) -> LPCWSTR {
+    export(|| {
+        let data = cstring::from_widechar_ptr(data_ptr)?;
+        let data = encoding::decode(data)?;
 
-    let hash_type = String::from_widechar_ptr(hash_type_ptr);
+        let hash = cstring::from_widechar_ptr(hash_ptr)?;
 
-    let hashed = kdf::evpkdf(&data, &salt, rounds, len, &hash_type)?;
+        let verified = kdf::password_verify(&data, &hash)?;
 
-    base64::encode(hashed).as_widechar_ptr()
+        Ok(verified.to_string())
+    })
 }
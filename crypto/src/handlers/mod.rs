@@ -1,4 +1,9 @@
+mod ecc;
 mod encryption;
 mod hash;
+mod jwt;
 mod kdf;
+mod otp;
+mod pipeline;
 mod tool;
+mod x509;
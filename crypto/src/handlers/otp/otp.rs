@@ -0,0 +1,115 @@
+use std::str::FromStr;
+
+use pk_ffi::{cstring, export, Error, LPCWSTR};
+
+use crate::imp::otp;
+
+/// Empty parameter means the default value
+unsafe fn parse_or<T>(ptr: LPCWSTR, default: T) -> Result<T, Error>
+where
+    T: FromStr,
+    T::Err: std::error::Error,
+{
+    let value = cstring::from_widechar_ptr(ptr)?;
+    match value.trim().is_empty() {
+        true => Ok(default),
+        false => Ok(value.trim().parse::<T>()?),
+    }
+}
+
+/// secret in base32 or prefixed (`hex:`), digits 6, period 30, hash_type sha1 and timestamp now by default
+#[no_mangle]
+pub unsafe extern "system" fn totp(
+    secret_ptr: LPCWSTR,
+    digits_ptr: LPCWSTR,
+    period_ptr: LPCWSTR,
+    hash_type_ptr: LPCWSTR,
+    timestamp_ptr: LPCWSTR,
+) -> LPCWSTR {
+    export(|| {
+        let secret = cstring::from_widechar_ptr(secret_ptr)?;
+        let digits = parse_or(digits_ptr, otp::DEFAULT_DIGITS)?;
+        let period = parse_or(period_ptr, otp::DEFAULT_PERIOD)?;
+        let hash_type = cstring::from_widechar_ptr(hash_type_ptr)?;
+        let timestamp = parse_or(timestamp_ptr, otp::unix_time())?;
+
+        let code = otp::totp(&secret, digits, period, &hash_type, timestamp)?;
+
+        Ok(code)
+    })
+}
+
+/// secret in base32 or prefixed (`hex:`), digits 6 and hash_type sha1 by default
+#[no_mangle]
+pub unsafe extern "system" fn hotp(
+    secret_ptr: LPCWSTR,
+    counter_ptr: LPCWSTR,
+    digits_ptr: LPCWSTR,
+    hash_type_ptr: LPCWSTR,
+) -> LPCWSTR {
+    export(|| {
+        let secret = cstring::from_widechar_ptr(secret_ptr)?;
+        let counter = cstring::from_widechar_ptr(counter_ptr)?;
+        let counter = counter.parse::<u64>()?;
+        let digits = parse_or(digits_ptr, otp::DEFAULT_DIGITS)?;
+        let hash_type = cstring::from_widechar_ptr(hash_type_ptr)?;
+
+        let code = otp::hotp(&secret, counter, digits, &hash_type)?;
+
+        Ok(code)
+    })
+}
+
+/// Returns true or false. window is the number of periods accepted before and after
+/// (1 by default, at most 10)
+#[no_mangle]
+pub unsafe extern "system" fn totp_verify(
+    secret_ptr: LPCWSTR,
+    code_ptr: LPCWSTR,
+    digits_ptr: LPCWSTR,
+    period_ptr: LPCWSTR,
+    hash_type_ptr: LPCWSTR,
+    window_ptr: LPCWSTR,
+    timestamp_ptr: LPCWSTR,
+) -> LPCWSTR {
+    export(|| {
+        let secret = cstring::from_widechar_ptr(secret_ptr)?;
+        let code = cstring::from_widechar_ptr(code_ptr)?;
+        let digits = parse_or(digits_ptr, otp::DEFAULT_DIGITS)?;
+        let period = parse_or(period_ptr, otp::DEFAULT_PERIOD)?;
+        let hash_type = cstring::from_widechar_ptr(hash_type_ptr)?;
+        let window = parse_or(window_ptr, otp::DEFAULT_WINDOW)?;
+        let timestamp = parse_or(timestamp_ptr, otp::unix_time())?;
+
+        let verified = otp::totp_verify(
+            &secret, &code, digits, period, &hash_type, window, timestamp,
+        )?;
+
+        Ok(verified.to_string())
+    })
+}
+
+/// Returns uri parameters as JSON
+#[no_mangle]
+pub unsafe extern "system" fn otpauth_parse(uri_ptr: LPCWSTR) -> LPCWSTR {
+    export(|| {
+        let uri = cstring::from_widechar_ptr(uri_ptr)?;
+
+        let parsed = otp::otpauth_parse(&uri)?;
+
+        Ok(parsed)
+    })
+}
+
+/// Code for the otpauth:// uri, timestamp is now by default
+#[no_mangle]
+pub unsafe extern "system" fn otpauth_code(uri_ptr: LPCWSTR, timestamp_ptr: LPCWSTR) -> LPCWSTR {
+    export(|| {
+        let uri = cstring::from_widechar_ptr(uri_ptr)?;
+        let timestamp = parse_or(timestamp_ptr, otp::unix_time())?;
+
+        let code = otp::otpauth_code(&uri, timestamp)?;
+
+        Ok(code)
+    })
+}
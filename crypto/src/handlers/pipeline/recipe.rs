@@ -0,0 +1,19 @@
+use pk_ffi::{cstring, export, LPCWSTR};
+
+use crate::{imp::pipeline, utils::encoding};
+
+/// recipe is a JSON array of steps (see imp::pipeline::pipeline), input is in base64
+/// or with an encoding prefix. Errors name the failed step: `ERR|step 2 (hmac): ...`
+#[no_mangle]
+pub unsafe extern "system" fn pipeline(recipe_ptr: LPCWSTR, input_ptr: LPCWSTR) -> LPCWSTR {
+    export(|| {
+        let recipe = cstring::from_widechar_ptr(recipe_ptr)?;
+        let input = cstring::from_widechar_ptr(input_ptr)?;
+
+        let input = encoding::decode(input)?;
+
+        let result = pipeline::pipeline(&recipe, &input)?;
+
+        Ok(result)
+    })
+}
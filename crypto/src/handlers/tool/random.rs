@@ -1,15 +1,53 @@
-use crate::{
-    imp::tool,
-    utils::base64,
-    wstring::{FromWidechar, ToWidechar, LPCWSTR},
-};
+use pk_ffi::{cstring, export, LPCWSTR};
 
+use crate::{imp::tool, utils::encoding};
+
+#[no_mangle]
+pub unsafe extern "system" fn random_bytes(len_ptr: LPCWSTR) -> LPCWSTR {
+    export(|| {
+        let (output, len) = encoding::output_prefix(cstring::from_widechar_ptr(len_ptr)?)?;
+        let len = len.parse::<usize>()?;
+
+        let len = tool::random::check_length(len)?;
+
+        let hashed = tool::random::random_bytes(len);
+
+        Ok(encoding::encode(hashed, output)?)
+    })
+}
+
+/// alphabet: hex, base62 (default), urlsafe, digits or custom characters
 #[no_mangle]
-pub unsafe extern "stdcall" fn random_bytes(len_ptr: LPCWSTR) -> LPCWSTR {
-    let len = String::from_widechar_ptr(len_ptr);
-    let len = len.parse::<usize>()?;
+pub unsafe extern "system" fn random_token(len_ptr: LPCWSTR, alphabet_ptr: LPCWSTR) -> LPCWSTR {
+    export(|| {
+        let len = cstring::from_widechar_ptr(len_ptr)?;
+        let len = len.parse::<usize>()?;
+
+        let alphabet = cstring::from_widechar_ptr(alphabet_ptr)?;
+
+        let token = tool::random::random_token(len, &alphabet)?;
+
+        Ok(token)
+    })
+}
+
+/// classes: comma separated lower, upper, digits, symbols (all if empty),
+/// symbols: custom special characters (optional)
+#[no_mangle]
+pub unsafe extern "system" fn random_password(
+    len_ptr: LPCWSTR,
+    classes_ptr: LPCWSTR,
+    symbols_ptr: LPCWSTR,
+) -> LPCWSTR {
+    export(|| {
+        let len = cstring::from_widechar_ptr(len_ptr)?;
+        let len = len.parse::<usize>()?;
+
+        let classes = cstring::from_widechar_ptr(classes_ptr)?;
+        let symbols = cstring::from_widechar_ptr(symbols_ptr)?;
 
-    let hashed = tool::random::random_bytes(len);
+        let password = tool::random::random_password(len, &classes, &symbols)?;
 
-    base64::encode(hashed).as_widechar_ptr()
+        Ok(password)
+    })
 }
@@ -0,0 +1,2 @@
+mod pkcs12;
+mod x509;
@@ -0,0 +1,38 @@
+use pk_ffi::{cstring, export, LPCWSTR};
+
+use crate::{imp::x509, utils::encoding};
+
+/// data is the .p12/.pfx file in base64. Returns JSON with key, certs and friendly_name
+#[no_mangle]
+pub unsafe extern "system" fn pkcs12_parse(data_ptr: LPCWSTR, password_ptr: LPCWSTR) -> LPCWSTR {
+    export(|| {
+        let data = cstring::from_widechar_ptr(data_ptr)?;
+        let data = encoding::decode(data)?;
+        let password = cstring::from_widechar_ptr(password_ptr)?;
+
+        let parsed = x509::pkcs12_parse(&data, &password)?;
+
+        Ok(parsed)
+    })
+}
+
+/// key is RSA or EC private key, certs is PEM chain starting with the key certificate.
+/// friendly_name is optional. Returns .p12 in base64
+#[no_mangle]
+pub unsafe extern "system" fn pkcs12_build(
+    key_ptr: LPCWSTR,
+    certs_ptr: LPCWSTR,
+    password_ptr: LPCWSTR,
+    friendly_name_ptr: LPCWSTR,
+) -> LPCWSTR {
+    export(|| {
+        let (output, key) = encoding::output_prefix(cstring::from_widechar_ptr(key_ptr)?)?;
+        let certs = cstring::from_widechar_ptr(certs_ptr)?;
+        let password = cstring::from_widechar_ptr(password_ptr)?;
+        let friendly_name = cstring::from_widechar_ptr(friendly_name_ptr)?;
+
+        let pkcs12 = x509::pkcs12_build(&key, &certs, &password, &friendly_name)?;
+
+        Ok(encoding::encode(pkcs12, output)?)
+    })
+}
@@ -0,0 +1,62 @@
+use pk_ffi::{cstring, export, LPCWSTR};
+
+use crate::imp::x509;
+
+/// cert is PEM or base64 (or prefixed) DER, returns JSON
+#[no_mangle]
+pub unsafe extern "system" fn x509_parse(cert_ptr: LPCWSTR) -> LPCWSTR {
+    export(|| {
+        let cert = cstring::from_widechar_ptr(cert_ptr)?;
+
+        let parsed = x509::x509_parse(&cert)?;
+
+        Ok(parsed)
+    })
+}
+
+/// key is RSA or EC private key, subject like "CN=example.com,O=Org",
+/// sans are comma separated, hash_type is sha256 by default. Returns PEM
+#[no_mangle]
+pub unsafe extern "system" fn x509_csr(
+    key_ptr: LPCWSTR,
+    subject_ptr: LPCWSTR,
+    sans_ptr: LPCWSTR,
+    hash_type_ptr: LPCWSTR,
+) -> LPCWSTR {
+    export(|| {
+        let key = cstring::from_widechar_ptr(key_ptr)?;
+        let subject = cstring::from_widechar_ptr(subject_ptr)?;
+        let sans = cstring::from_widechar_ptr(sans_ptr)?;
+        let hash_type = cstring::from_widechar_ptr(hash_type_ptr)?;
+
+        let csr = x509::x509_csr(&key, &subject, &sans, &hash_type)?;
+
+        Ok(csr)
+    })
+}
+
+/// Same parameters as x509_csr, days is 365 by default. Returns PEM
+#[no_mangle]
+pub unsafe extern "system" fn x509_self_signed(
+    key_ptr: LPCWSTR,
+    subject_ptr: LPCWSTR,
+    sans_ptr: LPCWSTR,
+    days_ptr: LPCWSTR,
+    hash_type_ptr: LPCWSTR,
+) -> LPCWSTR {
+    export(|| {
+        let key = cstring::from_widechar_ptr(key_ptr)?;
+        let subject = cstring::from_widechar_ptr(subject_ptr)?;
+        let sans = cstring::from_widechar_ptr(sans_ptr)?;
+        let days = cstring::from_widechar_ptr(days_ptr)?;
+        let days = match days.trim().is_empty() {
+            true => x509::DEFAULT_DAYS,
+            false => days.trim().parse::<u32>()?,
+        };
+        let hash_type = cstring::from_widechar_ptr(hash_type_ptr)?;
+
+        let cert = x509::x509_self_signed(&key, &subject, &sans, days, &hash_type)?;
+
+        Ok(cert)
+    })
+}
@@ -0,0 +1,80 @@
+use std::str::FromStr;
+
+use super::error::EccError;
+
+pub enum Curve {
+    P256,
+    P384,
+    Secp256k1,
+    Ed25519,
+    X25519,
+}
+
+impl FromStr for Curve {
+    type Err = EccError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let curve = match s {
+            "p256" | "p-256" | "secp256r1" | "prime256v1" => Curve::P256,
+            "p384" | "p-384" | "secp384r1" => Curve::P384,
+            "secp256k1" | "k256" => Curve::Secp256k1,
+            "ed25519" => Curve::Ed25519,
+            "x25519" => Curve::X25519,
+            _ => return Err(EccError::InvalidCurve(s.to_owned())),
+        };
+        Ok(curve)
+    }
+}
+
+impl Curve {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Curve::P256 => "p256",
+            Curve::P384 => "p384",
+            Curve::Secp256k1 => "secp256k1",
+            Curve::Ed25519 => "ed25519",
+            Curve::X25519 => "x25519",
+        }
+    }
+}
+
+pub enum KeyFormat {
+    Pem,
+    Der,
+    Raw,
+    Jwk,
+}
+
+impl FromStr for KeyFormat {
+    type Err = EccError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let format = match s {
+            "pem" | "" => KeyFormat::Pem,
+            "der" => KeyFormat::Der,
+            "raw" => KeyFormat::Raw,
+            "jwk" => KeyFormat::Jwk,
+            _ => return Err(EccError::InvalidKeyFormat(s.to_owned())),
+        };
+        Ok(format)
+    }
+}
+
+pub enum SignatureFormat {
+    Der,
+    /// r || s, as used in JWS
+    Raw,
+}
+
+impl FromStr for SignatureFormat {
+    type Err = EccError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let format = match s {
+            "der" | "" => SignatureFormat::Der,
+            "raw" => SignatureFormat::Raw,
+            _ => return Err(EccError::InvalidSignatureFormat(s.to_owned())),
+        };
+        Ok(format)
+    }
+}
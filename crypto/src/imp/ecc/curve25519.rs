@@ -0,0 +1,212 @@
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+use serde_json::{json, Value};
+use x25519_dalek::{PublicKey, StaticSecret};
+
+use crate::utils::base64;
+
+use super::{curve::KeyFormat, error::EccError};
+
+pub const KEY_LEN: usize = 32;
+
+/// Keys on curve25519 have fixed size, so PKCS#8 and SPKI encodings are just a constant prefix
+/// (RFC 8410) before the raw key
+pub enum Okp {
+    Ed25519,
+    X25519,
+}
+
+impl Okp {
+    fn jwk_curve(&self) -> &'static str {
+        match self {
+            Okp::Ed25519 => "Ed25519",
+            Okp::X25519 => "X25519",
+        }
+    }
+
+    fn pkcs8_prefix(&self) -> [u8; 16] {
+        match self {
+            Okp::Ed25519 => [
+                0x30, 0x2e, 0x02, 0x01, 0x00, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x04, 0x22,
+                0x04, 0x20,
+            ],
+            Okp::X25519 => [
+                0x30, 0x2e, 0x02, 0x01, 0x00, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x6e, 0x04, 0x22,
+                0x04, 0x20,
+            ],
+        }
+    }
+
+    fn spki_prefix(&self) -> [u8; 12] {
+        match self {
+            Okp::Ed25519 => [
+                0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00,
+            ],
+            Okp::X25519 => [
+                0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x6e, 0x03, 0x21, 0x00,
+            ],
+        }
+    }
+
+    pub fn generate(&self) -> [u8; KEY_LEN] {
+        let mut secret = [0u8; KEY_LEN];
+        StdRng::from_entropy().fill_bytes(&mut secret);
+        secret
+    }
+
+    pub fn public_from_private(&self, secret: &[u8; KEY_LEN]) -> [u8; KEY_LEN] {
+        match self {
+            Okp::Ed25519 => SigningKey::from_bytes(secret).verifying_key().to_bytes(),
+            Okp::X25519 => PublicKey::from(&StaticSecret::from(*secret)).to_bytes(),
+        }
+    }
+
+    /// Accepts PKCS#8 PEM, base64 DER, raw 32 bytes or JWK
+    pub fn parse_private(&self, key: &str) -> Result<[u8; KEY_LEN], EccError> {
+        let key = key.trim();
+
+        if key.starts_with('{') {
+            let jwk = self.parse_jwk(key).ok_or(EccError::InvalidPrivateKey)?;
+            return jwk_component(&jwk, "d").ok_or(EccError::InvalidPrivateKey);
+        }
+
+        let der = match key.starts_with("-----") {
+            true => pem_decode(key, "PRIVATE KEY"),
+            false => base64::decode(key).ok(),
+        }
+        .ok_or(EccError::InvalidPrivateKey)?;
+
+        let raw = match der.len() {
+            KEY_LEN => &der[..],
+            _ => der
+                .strip_prefix(&self.pkcs8_prefix()[..])
+                .ok_or(EccError::InvalidPrivateKey)?,
+        };
+        raw.try_into().or(Err(EccError::InvalidPrivateKey))
+    }
+
+    /// Accepts SPKI PEM, base64 DER, raw 32 bytes or JWK.
+    /// Private key is accepted too (except raw), its public part is used
+    pub fn parse_public(&self, key: &str) -> Result<[u8; KEY_LEN], EccError> {
+        let trimmed = key.trim();
+
+        if trimmed.starts_with('{') {
+            let jwk = self.parse_jwk(trimmed).ok_or(EccError::InvalidPublicKey)?;
+            return jwk_component(&jwk, "x").ok_or(EccError::InvalidPublicKey);
+        }
+
+        let der = match trimmed.starts_with("-----") {
+            true => pem_decode(trimmed, "PUBLIC KEY"),
+            false => base64::decode(trimmed).ok(),
+        };
+
+        let raw = der.as_deref().and_then(|der| match der.len() {
+            KEY_LEN => Some(der),
+            _ => der.strip_prefix(&self.spki_prefix()[..]),
+        });
+
+        match raw.and_then(|raw| raw.try_into().ok()) {
+            Some(raw) => Ok(raw),
+            None => match self.parse_private(key) {
+                Ok(secret) => Ok(self.public_from_private(&secret)),
+                Err(_) => Err(EccError::InvalidPublicKey),
+            },
+        }
+    }
+
+    pub fn encode_private(&self, secret: &[u8; KEY_LEN], format: &KeyFormat) -> String {
+        let der = [&self.pkcs8_prefix()[..], secret].concat();
+        match format {
+            KeyFormat::Pem => pem_encode(&der, "PRIVATE KEY"),
+            KeyFormat::Der => base64::encode(der),
+            KeyFormat::Raw => base64::encode(secret),
+            KeyFormat::Jwk => json!({
+                "kty": "OKP",
+                "crv": self.jwk_curve(),
+                "x": base64::encode_url(self.public_from_private(secret)),
+                "d": base64::encode_url(secret),
+            })
+            .to_string(),
+        }
+    }
+
+    pub fn encode_public(&self, public: &[u8; KEY_LEN], format: &KeyFormat) -> String {
+        let der = [&self.spki_prefix()[..], public].concat();
+        match format {
+            KeyFormat::Pem => pem_encode(&der, "PUBLIC KEY"),
+            KeyFormat::Der => base64::encode(der),
+            KeyFormat::Raw => base64::encode(public),
+            KeyFormat::Jwk => json!({
+                "kty": "OKP",
+                "crv": self.jwk_curve(),
+                "x": base64::encode_url(public),
+            })
+            .to_string(),
+        }
+    }
+
+    fn parse_jwk(&self, jwk: &str) -> Option<Value> {
+        let jwk: Value = serde_json::from_str(jwk).ok()?;
+        match jwk["kty"] == "OKP" && jwk["crv"] == self.jwk_curve() {
+            true => Some(jwk),
+            false => None,
+        }
+    }
+}
+
+pub fn ed25519_sign(secret: &[u8; KEY_LEN], data: &[u8]) -> Vec<u8> {
+    SigningKey::from_bytes(secret).sign(data).to_vec()
+}
+
+pub fn ed25519_verify(public: &[u8; KEY_LEN], data: &[u8], signature: &[u8]) -> bool {
+    let verifying_key = match VerifyingKey::from_bytes(public) {
+        Ok(verifying_key) => verifying_key,
+        Err(_) => return false,
+    };
+    let signature = match Signature::from_slice(signature) {
+        Ok(signature) => signature,
+        Err(_) => return false,
+    };
+
+    verifying_key.verify(data, &signature).is_ok()
+}
+
+pub fn x25519(secret: &[u8; KEY_LEN], public: &[u8; KEY_LEN]) -> Result<Vec<u8>, EccError> {
+    let shared = StaticSecret::from(*secret).diffie_hellman(&PublicKey::from(*public));
+    match shared.was_contributory() {
+        true => Ok(shared.as_bytes().to_vec()),
+        false => Err(EccError::NonContributory),
+    }
+}
+
+fn jwk_component(jwk: &Value, name: &str) -> Option<[u8; KEY_LEN]> {
+    let value = jwk[name].as_str()?;
+    let bytes = base64::decode_url(value.trim_end_matches('=')).ok()?;
+    bytes.try_into().ok()
+}
+
+fn pem_decode(pem: &str, label: &str) -> Option<Vec<u8>> {
+    if !pem.starts_with(&format!("-----BEGIN {}-----", label)) {
+        return None;
+    }
+
+    let body: String = pem
+        .lines()
+        .filter(|line| !line.starts_with("-----"))
+        .map(str::trim)
+        .collect();
+    base64::decode(body).ok()
+}
+
+fn pem_encode(der: &[u8], label: &str) -> String {
+    let body = base64::encode(der);
+    let lines: Vec<&str> = body
+        .as_bytes()
+        .chunks(64)
+        .map(|chunk| std::str::from_utf8(chunk).unwrap())
+        .collect();
+    format!(
+        "-----BEGIN {label}-----\n{}\n-----END {label}-----\n",
+        lines.join("\n")
+    )
+}
@@ -0,0 +1,157 @@
+use std::str::FromStr;
+
+use crate::{imp::hashing::make_hash, utils::helpers::or_default};
+
+use super::{
+    curve::{Curve, KeyFormat, SignatureFormat},
+    curve25519::{self, Okp},
+    ecdsa_curves::{k256_curve, p256_curve, p384_curve},
+    error::EccError,
+};
+
+/// Evaluates `$func` with `$module` bound to the ECDSA curve module,
+/// the remaining arms handle curve25519
+macro_rules! call_with_ecdsa_curve {
+    ($curve:expr, |$module:ident| $func:expr, $($other:pat => $fallback:expr),+) => {
+        match $curve {
+            Curve::P256 => {
+                use p256_curve as $module;
+                $func
+            }
+            Curve::P384 => {
+                use p384_curve as $module;
+                $func
+            }
+            Curve::Secp256k1 => {
+                use k256_curve as $module;
+                $func
+            }
+            $($other => $fallback,)+
+        }
+    };
+}
+
+/// Returns (private key, public key) encoded in the given format
+pub fn ec_generate(curve: &str, format: &str) -> Result<(String, String), EccError> {
+    let curve = Curve::from_str(curve)?;
+    let format = KeyFormat::from_str(format)?;
+
+    call_with_ecdsa_curve!(curve, |c| {
+        let secret_key = c::generate();
+        Ok((
+            c::encode_private(&secret_key, &format)?,
+            c::encode_public(&secret_key.public_key(), &format)?,
+        ))
+    }, curve => {
+        let okp = okp(&curve)?;
+        let secret = okp.generate();
+        Ok((
+            okp.encode_private(&secret, &format),
+            okp.encode_public(&okp.public_from_private(&secret), &format),
+        ))
+    })
+}
+
+/// Public key from the private key
+pub fn ec_public_key(key: &str, curve: &str, format: &str) -> Result<String, EccError> {
+    let curve = Curve::from_str(curve)?;
+    let format = KeyFormat::from_str(format)?;
+
+    call_with_ecdsa_curve!(curve, |c| {
+        let secret_key = c::parse_private(key)?;
+        c::encode_public(&secret_key.public_key(), &format)
+    }, curve => {
+        let okp = okp(&curve)?;
+        let secret = okp.parse_private(key)?;
+        Ok(okp.encode_public(&okp.public_from_private(&secret), &format))
+    })
+}
+
+/// Private key re-encoded in the given format
+pub fn ec_private_key(key: &str, curve: &str, format: &str) -> Result<String, EccError> {
+    let curve = Curve::from_str(curve)?;
+    let format = KeyFormat::from_str(format)?;
+
+    call_with_ecdsa_curve!(curve, |c| {
+        let secret_key = c::parse_private(key)?;
+        c::encode_private(&secret_key, &format)
+    }, curve => {
+        let okp = okp(&curve)?;
+        let secret = okp.parse_private(key)?;
+        Ok(okp.encode_private(&secret, &format))
+    })
+}
+
+/// Empty hash_type means the curve default (sha256 or sha384), ignored for ed25519.
+/// Empty signature format means DER, ignored for ed25519
+pub fn ec_sign(
+    data: &[u8],
+    key: &str,
+    curve: &str,
+    hash_type: &str,
+    signature_format: &str,
+) -> Result<Vec<u8>, EccError> {
+    let curve = Curve::from_str(curve)?;
+    let signature_format = SignatureFormat::from_str(signature_format)?;
+
+    call_with_ecdsa_curve!(curve, |c| {
+        let secret_key = c::parse_private(key)?;
+        let hashed_data = make_hash(data, or_default(hash_type, c::DEFAULT_HASH))?;
+        c::sign(&secret_key, &hashed_data, &signature_format)
+    }, Curve::Ed25519 => {
+        let secret = Okp::Ed25519.parse_private(key)?;
+        Ok(curve25519::ed25519_sign(&secret, data))
+    }, Curve::X25519 => Err(EccError::Unsupported("signing", curve.name())))
+}
+
+/// Returns false if the signature doesn't match, errors are returned only for bad input
+pub fn ec_verify(
+    data: &[u8],
+    signature: &[u8],
+    key: &str,
+    curve: &str,
+    hash_type: &str,
+) -> Result<bool, EccError> {
+    let curve = Curve::from_str(curve)?;
+
+    call_with_ecdsa_curve!(curve, |c| {
+        let public_key = c::parse_public(key)?;
+        let hashed_data = make_hash(data, or_default(hash_type, c::DEFAULT_HASH))?;
+        Ok(c::verify(&public_key, &hashed_data, signature))
+    }, Curve::Ed25519 => {
+        let public = Okp::Ed25519.parse_public(key)?;
+        Ok(curve25519::ed25519_verify(&public, data, signature))
+    }, Curve::X25519 => Err(EccError::Unsupported("signing", curve.name())))
+}
+
+/// Raw shared secret (x coordinate for ECDH), hash it before using as a key
+pub fn ecdh(private_key: &str, public_key: &str, curve: &str) -> Result<Vec<u8>, EccError> {
+    let curve = Curve::from_str(curve)?;
+
+    call_with_ecdsa_curve!(curve, |c| {
+        let secret_key = c::parse_private(private_key)?;
+        let public_key = c::parse_public(public_key)?;
+        Ok(c::ecdh(&secret_key, &public_key))
+    }, Curve::X25519 => {
+        let secret = Okp::X25519.parse_private(private_key)?;
+        let public = Okp::X25519.parse_public(public_key)?;
+        curve25519::x25519(&secret, &public)
+    }, Curve::Ed25519 => Err(EccError::Unsupported("key agreement", curve.name())))
+}
+
+/// Raw x25519 keys for NaCl box, accepted in any format ecc functions take
+pub fn x25519_private_raw(key: &str) -> Result<[u8; curve25519::KEY_LEN], EccError> {
+    Okp::X25519.parse_private(key)
+}
+
+pub fn x25519_public_raw(key: &str) -> Result<[u8; curve25519::KEY_LEN], EccError> {
+    Okp::X25519.parse_public(key)
+}
+
+fn okp(curve: &Curve) -> Result<Okp, EccError> {
+    match curve {
+        Curve::Ed25519 => Ok(Okp::Ed25519),
+        Curve::X25519 => Ok(Okp::X25519),
+        _ => Err(EccError::InvalidCurve(curve.name().to_owned())),
+    }
+}
@@ -0,0 +1,25 @@
+use thiserror::Error;
+
+use crate::imp::hashing::error::HashError;
+
+#[derive(Error, Debug)]
+pub enum EccError {
+    #[error("invalid curve: {0}")]
+    InvalidCurve(String),
+    #[error("invalid key format: {0}")]
+    InvalidKeyFormat(String),
+    #[error("invalid signature format: {0}")]
+    InvalidSignatureFormat(String),
+    #[error("invalid private key")]
+    InvalidPrivateKey,
+    #[error("invalid public key")]
+    InvalidPublicKey,
+    #[error("{0} is not supported for {1}")]
+    Unsupported(&'static str, &'static str),
+    #[error("shared secret is all zeros (low order public key)")]
+    NonContributory,
+    #[error("failed to sign: {0}")]
+    Signature(#[from] signature::Error),
+    #[error(transparent)]
+    HashError(#[from] HashError),
+}
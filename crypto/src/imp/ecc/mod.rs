@@ -0,0 +1,7 @@
+mod curve;
+mod curve25519;
+mod ecc;
+mod ecdsa_curves;
+pub mod error;
+
+pub use ecc::*;
@@ -0,0 +1,152 @@
+use std::str::FromStr;
+
+use aes::{Aes128, Aes192, Aes256};
+use aes_gcm::{
+    aead::{consts::*, AeadInPlace, KeyInit, Nonce},
+    AesGcm,
+};
+use ccm::Ccm;
+use chacha20poly1305::{ChaCha20Poly1305, XChaCha20Poly1305};
+
+use super::error::CipherError;
+
+pub const DEFAULT_TAG_LEN: usize = 16;
+
+// Same generic order for both AES based constructions: <cipher, nonce size, tag size>
+type Gcm<C, N, T> = AesGcm<C, N, T>;
+type CcmNonceTag<C, N, T> = Ccm<C, T, N>;
+
+pub enum AeadMode {
+    Gcm,
+    Ccm,
+    ChaCha20Poly1305,
+}
+
+impl FromStr for AeadMode {
+    type Err = CipherError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mode = match s {
+            "gcm" => AeadMode::Gcm,
+            "ccm" => AeadMode::Ccm,
+            "chacha20-poly1305" | "chacha20poly1305" => AeadMode::ChaCha20Poly1305,
+            _ => return Err(CipherError::InvalidMode(s.to_owned())),
+        };
+        Ok(mode)
+    }
+}
+
+macro_rules! call_with_aead_generic {
+    ($func:ident $args:tt, $mode:expr, $key_len:expr, $nonce_len:expr, $tag_len:expr) => {
+        match ($mode, $key_len) {
+            (AeadMode::Gcm, 16) => call_with_aead_generic!(@gcm, $func $args, Aes128, $nonce_len, $tag_len),
+            (AeadMode::Gcm, 24) => call_with_aead_generic!(@gcm, $func $args, Aes192, $nonce_len, $tag_len),
+            (AeadMode::Gcm, 32) => call_with_aead_generic!(@gcm, $func $args, Aes256, $nonce_len, $tag_len),
+            (AeadMode::Ccm, 16) => call_with_aead_generic!(@ccm, $func $args, Aes128, $nonce_len, $tag_len),
+            (AeadMode::Ccm, 24) => call_with_aead_generic!(@ccm, $func $args, Aes192, $nonce_len, $tag_len),
+            (AeadMode::Ccm, 32) => call_with_aead_generic!(@ccm, $func $args, Aes256, $nonce_len, $tag_len),
+            (AeadMode::ChaCha20Poly1305, 32) => match ($nonce_len, $tag_len) {
+                (12, 16) => $func::<ChaCha20Poly1305> $args,
+                (24, 16) => $func::<XChaCha20Poly1305> $args,
+                (12 | 24, tag_len) => Err(CipherError::InvalidTagLen(tag_len)),
+                (nonce_len, _) => Err(CipherError::InvalidNonceLen(nonce_len)),
+            },
+            (AeadMode::ChaCha20Poly1305, key_len) => Err(CipherError::InvalidChaChaKeyLen(key_len)),
+            (_, key_len) => Err(CipherError::InvalidKeyLen(key_len)),
+        }
+    };
+
+    (@gcm, $func:ident $args:tt, $aes:ty, $nonce_len:expr, $tag_len:expr) => {
+        call_with_aead_generic!(
+            @nonce, $func $args, Gcm<$aes>, $nonce_len, $tag_len,
+            [12 => U12, 16 => U16],
+            [12 => U12, 13 => U13, 14 => U14, 15 => U15, 16 => U16]
+        )
+    };
+
+    (@ccm, $func:ident $args:tt, $aes:ty, $nonce_len:expr, $tag_len:expr) => {
+        call_with_aead_generic!(
+            @nonce, $func $args, CcmNonceTag<$aes>, $nonce_len, $tag_len,
+            [7 => U7, 8 => U8, 9 => U9, 10 => U10, 11 => U11, 12 => U12, 13 => U13],
+            [4 => U4, 6 => U6, 8 => U8, 10 => U10, 12 => U12, 14 => U14, 16 => U16]
+        )
+    };
+
+    (@nonce, $func:ident $args:tt, $cipher:ident<$aes:ty>, $nonce_len:expr, $tag_len:expr, [$($nl:literal => $nt:ty),+], $tags:tt) => {
+        match $nonce_len {
+            $($nl => call_with_aead_generic!(@tag, $func $args, $cipher<$aes, $nt>, $tag_len, $tags),)+
+            nonce_len => Err(CipherError::InvalidNonceLen(nonce_len)),
+        }
+    };
+
+    (@tag, $func:ident $args:tt, $cipher:ident<$aes:ty, $nonce:ty>, $tag_len:expr, [$($tl:literal => $tt:ty),+]) => {
+        match $tag_len {
+            $($tl => $func::<$cipher<$aes, $nonce, $tt>> $args,)+
+            tag_len => Err(CipherError::InvalidTagLen(tag_len)),
+        }
+    };
+}
+
+fn _seal<C: KeyInit + AeadInPlace>(
+    key: &[u8],
+    nonce: &[u8],
+    aad: &[u8],
+    mut data: Vec<u8>,
+) -> Result<Vec<u8>, CipherError> {
+    let cipher = C::new_from_slice(key)?;
+    cipher
+        .encrypt_in_place(Nonce::<C>::from_slice(nonce), aad, &mut data)
+        .or(Err(CipherError::Aead))?;
+    Ok(data)
+}
+
+fn _open<C: KeyInit + AeadInPlace>(
+    key: &[u8],
+    nonce: &[u8],
+    aad: &[u8],
+    mut data: Vec<u8>,
+) -> Result<Vec<u8>, CipherError> {
+    let cipher = C::new_from_slice(key)?;
+    cipher
+        .decrypt_in_place(Nonce::<C>::from_slice(nonce), aad, &mut data)
+        .or(Err(CipherError::TagMismatch))?;
+    Ok(data)
+}
+
+/// Returns ciphertext with the tag appended
+pub fn aead_encrypt(
+    data: Vec<u8>,
+    key: &[u8],
+    nonce: &[u8],
+    aad: &[u8],
+    mode: &str,
+    tag_len: usize,
+) -> Result<Vec<u8>, CipherError> {
+    let mode = AeadMode::from_str(mode)?;
+    call_with_aead_generic!(
+        _seal(key, nonce, aad, data),
+        mode,
+        key.len(),
+        nonce.len(),
+        tag_len
+    )
+}
+
+/// Expects ciphertext with the tag appended
+pub fn aead_decrypt(
+    data: Vec<u8>,
+    key: &[u8],
+    nonce: &[u8],
+    aad: &[u8],
+    mode: &str,
+    tag_len: usize,
+) -> Result<Vec<u8>, CipherError> {
+    let mode = AeadMode::from_str(mode)?;
+    call_with_aead_generic!(
+        _open(key, nonce, aad, data),
+        mode,
+        key.len(),
+        nonce.len(),
+        tag_len
+    )
+}
@@ -9,9 +9,9 @@ pub fn aes_encrypt<'a>(
     mode: &str,
     padding: &str,
 ) -> Result<Cow<'a, [u8]>, CipherError> {
-    let aes_type = BlockCipher::new_from_key_length(key.len())?;
     let mode = Mode::from_str(mode)?;
-    let padding = Padding::from_str(padding)?;
+    let aes_type = BlockCipher::new_from_key_length(mode.cipher_key_len(key.len()))?;
+    let padding = Padding::for_mode(&mode, padding)?;
 
     let cipher = Cipher::new(aes_type, mode, padding);
     let encrypted = cipher.encrypt(key, iv, data)?;
@@ -26,12 +26,27 @@ pub fn aes_decrypt<'a>(
     mode: &str,
     padding: &str,
 ) -> Result<Cow<'a, [u8]>, CipherError> {
-    let aes_mode = BlockCipher::new_from_key_length(key.len())?;
     let mode = Mode::from_str(mode)?;
-    let padding = Padding::from_str(padding)?;
+    let aes_mode = BlockCipher::new_from_key_length(mode.cipher_key_len(key.len()))?;
+    let padding = Padding::for_mode(&mode, padding)?;
 
     let cipher = Cipher::new(aes_mode, mode, padding);
     let decrypted = cipher.decrypt(key, iv, data)?;
 
     Ok(decrypted)
 }
+
+/// Empty mode means kw (RFC 3394), kwp is RFC 5649 with padding
+pub fn aes_key_wrap(data: &[u8], kek: &[u8], mode: &str) -> Result<Vec<u8>, CipherError> {
+    let aes_type = BlockCipher::new_from_key_length(kek.len())?;
+    let key_wrap = KeyWrap::from_str(mode)?;
+
+    key_wrap.wrap_key(aes_type, kek, data)
+}
+
+pub fn aes_key_unwrap(wrapped: &[u8], kek: &[u8], mode: &str) -> Result<Vec<u8>, CipherError> {
+    let aes_type = BlockCipher::new_from_key_length(kek.len())?;
+    let key_wrap = KeyWrap::from_str(mode)?;
+
+    key_wrap.unwrap_key(aes_type, kek, wrapped)
+}
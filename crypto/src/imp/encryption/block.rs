@@ -0,0 +1,39 @@
+use std::{borrow::Cow, str::FromStr};
+
+use super::{cipher::*, error::CipherError};
+
+pub fn block_encrypt<'a>(
+    cipher: &str,
+    data: &'a mut [u8],
+    key: &[u8],
+    iv: &[u8],
+    mode: &str,
+    padding: &str,
+) -> Result<Cow<'a, [u8]>, CipherError> {
+    let mode = Mode::from_str(mode)?;
+    let block_cipher = BlockCipher::new_from_name(cipher, mode.cipher_key_len(key.len()))?;
+    let padding = Padding::for_mode(&mode, padding)?;
+
+    let cipher = Cipher::new(block_cipher, mode, padding);
+    let encrypted = cipher.encrypt(key, iv, data)?;
+
+    Ok(encrypted)
+}
+
+pub fn block_decrypt<'a>(
+    cipher: &str,
+    data: &'a mut [u8],
+    key: &[u8],
+    iv: &[u8],
+    mode: &str,
+    padding: &str,
+) -> Result<Cow<'a, [u8]>, CipherError> {
+    let mode = Mode::from_str(mode)?;
+    let block_cipher = BlockCipher::new_from_name(cipher, mode.cipher_key_len(key.len()))?;
+    let padding = Padding::for_mode(&mode, padding)?;
+
+    let cipher = Cipher::new(block_cipher, mode, padding);
+    let decrypted = cipher.decrypt(key, iv, data)?;
+
+    Ok(decrypted)
+}
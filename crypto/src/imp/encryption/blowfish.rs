@@ -10,7 +10,7 @@ pub fn blowfish_encrypt<'a>(
     padding: &str,
 ) -> Result<Cow<'a, [u8]>, CipherError> {
     let mode = Mode::from_str(mode)?;
-    let padding = Padding::from_str(padding)?;
+    let padding = Padding::for_mode(&mode, padding)?;
 
     let cipher = Cipher::new(BlockCipher::Blowfish, mode, padding);
     let encrypted = cipher.encrypt(key, iv, data)?;
@@ -26,7 +26,7 @@ pub fn blowfish_decrypt<'a>(
     padding: &str,
 ) -> Result<Cow<'a, [u8]>, CipherError> {
     let mode = Mode::from_str(mode)?;
-    let padding = Padding::from_str(padding)?;
+    let padding = Padding::for_mode(&mode, padding)?;
 
     let cipher = Cipher::new(BlockCipher::Blowfish, mode, padding);
     let decrypted = cipher.decrypt(key, iv, data)?;
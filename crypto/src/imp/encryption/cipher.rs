@@ -3,19 +3,34 @@ use std::{borrow::Cow, str::FromStr};
 use aes::{
     cipher::{
         block_padding::{AnsiX923, Iso10126, Iso7816, NoPadding, Pkcs7, ZeroPadding},
-        BlockDecryptMut, BlockEncryptMut, KeyIvInit, StreamCipher,
+        AsyncStreamCipher, Block, BlockCipher as _BlockCipher, BlockDecryptMut, BlockEncryptMut,
+        BlockSizeUser, InnerIvInit, StreamCipher, StreamCipherCoreWrapper,
     },
     Aes128, Aes192, Aes256,
 };
 use cbc;
-use ctr::Ctr128BE;
-use digest::KeyInit;
+use cfb8;
+use cfb_mode;
+use ctr::{
+    flavors::{Ctr128BE, Ctr64BE},
+    CtrCore,
+};
+use digest::{InnerInit, InvalidLength, KeyInit};
 use ecb;
+use ofb::OfbCore;
+use pcbc;
 
 use blowfish::Blowfish;
+use camellia::{Camellia128, Camellia192, Camellia256};
+use des::{Des, TdesEde2, TdesEde3};
+use rc2::Rc2;
+use serpent::Serpent;
+use sm4::Sm4;
+use twofish::Twofish;
 
 use super::error::CipherError;
 
+#[allow(clippy::enum_variant_names)]
 pub enum Padding {
     NoPadding,
     Pkcs7,
@@ -42,10 +57,53 @@ impl FromStr for Padding {
     }
 }
 
+impl Padding {
+    /// Padding only applies to ecb, cbc and pcbc, the other modes may leave it empty
+    pub fn for_mode(mode: &Mode, s: &str) -> Result<Self, CipherError> {
+        match (mode, s) {
+            (Mode::Ecb | Mode::Cbc | Mode::Pcbc, _) => s.parse(),
+            (_, "") => Ok(Padding::NoPadding),
+            _ => s.parse(),
+        }
+    }
+
+    fn encrypt<M: BlockEncryptMut>(&self, encryptor: M, data: &[u8]) -> Vec<u8> {
+        match self {
+            Padding::NoPadding => encryptor.encrypt_padded_vec_mut::<NoPadding>(data),
+            Padding::Pkcs7 => encryptor.encrypt_padded_vec_mut::<Pkcs7>(data),
+            Padding::ZeroPadding => encryptor.encrypt_padded_vec_mut::<ZeroPadding>(data),
+            Padding::AnsiX923 => encryptor.encrypt_padded_vec_mut::<AnsiX923>(data),
+            Padding::Iso7816 => encryptor.encrypt_padded_vec_mut::<Iso7816>(data),
+            Padding::Iso10126 => encryptor.encrypt_padded_vec_mut::<Iso10126>(data),
+        }
+    }
+
+    fn decrypt<M: BlockDecryptMut>(
+        &self,
+        decryptor: M,
+        data: &[u8],
+    ) -> Result<Vec<u8>, CipherError> {
+        let decrypted = match self {
+            Padding::NoPadding => decryptor.decrypt_padded_vec_mut::<NoPadding>(data),
+            Padding::Pkcs7 => decryptor.decrypt_padded_vec_mut::<Pkcs7>(data),
+            Padding::ZeroPadding => decryptor.decrypt_padded_vec_mut::<ZeroPadding>(data),
+            Padding::AnsiX923 => decryptor.decrypt_padded_vec_mut::<AnsiX923>(data),
+            Padding::Iso7816 => decryptor.decrypt_padded_vec_mut::<Iso7816>(data),
+            Padding::Iso10126 => decryptor.decrypt_padded_vec_mut::<Iso10126>(data),
+        }?;
+        Ok(decrypted)
+    }
+}
+
 pub enum Mode {
     Ecb,
     Cbc,
     Ctr,
+    Cfb,
+    Cfb8,
+    Ofb,
+    Pcbc,
+    Xts,
 }
 
 impl FromStr for Mode {
@@ -56,18 +114,134 @@ impl FromStr for Mode {
             "ecb" => Mode::Ecb,
             "cbc" => Mode::Cbc,
             "ctr" => Mode::Ctr,
+            "cfb" => Mode::Cfb,
+            "cfb8" => Mode::Cfb8,
+            "ofb" => Mode::Ofb,
+            "pcbc" => Mode::Pcbc,
+            "xts" => Mode::Xts,
             _ => return Err(CipherError::InvalidMode(s.to_owned())),
         };
         Ok(mode)
     }
 }
 
-#[derive(Clone, Copy)]
-pub enum BlockCipher {
-    Aes128,
-    Aes192,
-    Aes256,
-    Blowfish,
+impl Mode {
+    /// XTS takes two keys of the cipher's size concatenated: data key || tweak key
+    pub fn cipher_key_len(&self, key_len: usize) -> usize {
+        match self {
+            Mode::Xts if key_len.is_multiple_of(2) => key_len / 2,
+            _ => key_len,
+        }
+    }
+
+    fn validate_key(&self, cipher: BlockCipher, key: &[u8]) -> Result<(), CipherError> {
+        let valid = match self {
+            Mode::Xts => key.len().is_multiple_of(2) && cipher.validate_key(&key[..key.len() / 2]),
+            _ => cipher.validate_key(key),
+        };
+        if !valid {
+            return Err(CipherError::InvalidKeyLen(key.len()));
+        }
+
+        // IEEE 1619 requires the tweak key to be independent from the data key
+        if let Mode::Xts = self {
+            let (key, tweak_key) = key.split_at(key.len() / 2);
+            if key == tweak_key {
+                return Err(CipherError::XtsSameKeys);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Everything a block cipher needs to work with every [`Mode`] and [`Padding`]
+pub trait BlockCipherImpl: _BlockCipher + BlockEncryptMut + BlockDecryptMut + KeyInit {
+    /// CTR with the counter over the whole block
+    fn apply_ctr(self, iv: &[u8], data: &mut [u8]) -> Result<(), InvalidLength>;
+    fn apply_ofb(self, iv: &[u8], data: &mut [u8]) -> Result<(), InvalidLength>;
+}
+
+/// Called with the concrete cipher type picked from [`BlockCipher`]
+trait CipherVisitor {
+    type Output;
+
+    fn visit<C: BlockCipherImpl>(self) -> Self::Output;
+}
+
+/// One line per cipher: variant(names, accepted key lengths) => type, CTR counter flavor
+macro_rules! block_ciphers {
+    ($($variant:ident($name:pat, $key_len:pat) => $cipher:ty, $ctr:ident;)+) => {
+        #[derive(Clone, Copy)]
+        pub enum BlockCipher {
+            $($variant,)+
+        }
+
+        $(
+            impl BlockCipherImpl for $cipher {
+                fn apply_ctr(self, iv: &[u8], data: &mut [u8]) -> Result<(), InvalidLength> {
+                    let core = CtrCore::<$cipher, $ctr>::inner_iv_slice_init(self, iv)?;
+                    StreamCipherCoreWrapper::from_core(core).apply_keystream(data);
+                    Ok(())
+                }
+
+                fn apply_ofb(self, iv: &[u8], data: &mut [u8]) -> Result<(), InvalidLength> {
+                    let core = OfbCore::<$cipher>::inner_iv_slice_init(self, iv)?;
+                    StreamCipherCoreWrapper::from_core(core).apply_keystream(data);
+                    Ok(())
+                }
+            }
+        )+
+
+        impl BlockCipher {
+            /// Ciphers with several key sizes are picked by the key length
+            pub fn new_from_name(name: &str, key_length: usize) -> Result<BlockCipher, CipherError> {
+                // the same name is listed once per key size
+                #[allow(unreachable_patterns)]
+                match (name, key_length) {
+                    $(($name, $key_len) => Ok(BlockCipher::$variant),)+
+                    $(($name, _))|+ => Err(CipherError::InvalidKeyLenFor(name.to_owned(), key_length)),
+                    _ => Err(CipherError::InvalidCipher(name.to_owned())),
+                }
+            }
+
+            fn visit<V: CipherVisitor>(self, visitor: V) -> V::Output {
+                match self {
+                    $(BlockCipher::$variant => visitor.visit::<$cipher>(),)+
+                }
+            }
+
+            fn validate_key(self, key: &[u8]) -> bool {
+                match self {
+                    $(BlockCipher::$variant => matches!(key.len(), $key_len),)+
+                }
+            }
+
+            pub fn block_size(self) -> usize {
+                match self {
+                    $(BlockCipher::$variant => <$cipher as BlockSizeUser>::block_size(),)+
+                }
+            }
+        }
+    };
+}
+
+block_ciphers! {
+    Aes128("aes", 16) => Aes128, Ctr128BE;
+    Aes192("aes", 24) => Aes192, Ctr128BE;
+    Aes256("aes", 32) => Aes256, Ctr128BE;
+    Blowfish("blowfish", 4..=56) => Blowfish, Ctr64BE;
+    Des("des", 8) => Des, Ctr64BE;
+    TdesEde2("3des" | "tdes", 16) => TdesEde2, Ctr64BE;
+    TdesEde3("3des" | "tdes", 24) => TdesEde3, Ctr64BE;
+    Camellia128("camellia", 16) => Camellia128, Ctr128BE;
+    Camellia192("camellia", 24) => Camellia192, Ctr128BE;
+    Camellia256("camellia", 32) => Camellia256, Ctr128BE;
+    Sm4("sm4", 16) => Sm4, Ctr128BE;
+    Twofish("twofish", 16 | 24 | 32) => Twofish, Ctr128BE;
+    Serpent("serpent", 16 | 24 | 32) => Serpent, Ctr128BE;
+    // effective key length is the key length, e.g. 5 bytes for RC2-40
+    Rc2("rc2", 1..=128) => Rc2, Ctr64BE;
 }
 
 impl BlockCipher {
@@ -103,580 +277,433 @@ impl Cipher {
         iv: &[u8],
         data: &'a mut [u8],
     ) -> Result<Cow<'a, [u8]>, CipherError> {
-        if !Self::validate_key(self.cipher, key) {
-            return Err(CipherError::InvalidKeyLen(key.len()));
-        }
+        self.mode.validate_key(self.cipher, key)?;
+
+        self.cipher.visit(Encrypt {
+            mode: &self.mode,
+            padding: &self.padding,
+            key,
+            iv,
+            data,
+        })
+    }
 
-        match (&self.cipher, &self.mode, &self.padding) {
-            // AES128 CBC
-            (BlockCipher::Aes128, Mode::Cbc, Padding::Pkcs7) => {
-                Ok(cbc::Encryptor::<Aes128>::new_from_slices(key, iv)?
-                    .encrypt_padded_vec_mut::<Pkcs7>(data)
-                    .into())
-            }
-            (BlockCipher::Aes128, Mode::Cbc, Padding::ZeroPadding) => {
-                Ok(cbc::Encryptor::<Aes128>::new_from_slices(key, iv)?
-                    .encrypt_padded_vec_mut::<ZeroPadding>(data)
-                    .into())
-            }
-            (BlockCipher::Aes128, Mode::Cbc, Padding::NoPadding) => {
-                Ok(cbc::Encryptor::<Aes128>::new_from_slices(key, iv)?
-                    .encrypt_padded_vec_mut::<NoPadding>(data)
-                    .into())
-            }
-            (BlockCipher::Aes128, Mode::Cbc, Padding::Iso7816) => {
-                Ok(cbc::Encryptor::<Aes128>::new_from_slices(key, iv)?
-                    .encrypt_padded_vec_mut::<Iso7816>(data)
-                    .into())
-            }
-            (BlockCipher::Aes128, Mode::Cbc, Padding::Iso10126) => {
-                Ok(cbc::Encryptor::<Aes128>::new_from_slices(key, iv)?
-                    .encrypt_padded_vec_mut::<Iso10126>(data)
-                    .into())
-            }
-            (BlockCipher::Aes128, Mode::Cbc, Padding::AnsiX923) => {
-                Ok(cbc::Encryptor::<Aes128>::new_from_slices(key, iv)?
-                    .encrypt_padded_vec_mut::<AnsiX923>(data)
-                    .into())
-            }
+    pub fn decrypt<'a>(
+        &self,
+        key: &[u8],
+        iv: &[u8],
+        encrypted_data: &'a mut [u8],
+    ) -> Result<Cow<'a, [u8]>, CipherError> {
+        self.mode.validate_key(self.cipher, key)?;
+
+        self.cipher.visit(Decrypt {
+            mode: &self.mode,
+            padding: &self.padding,
+            key,
+            iv,
+            encrypted_data,
+        })
+    }
+}
 
-            // AES192 CBC
-            (BlockCipher::Aes192, Mode::Cbc, Padding::Pkcs7) => {
-                Ok(cbc::Encryptor::<Aes192>::new_from_slices(key, iv)?
-                    .encrypt_padded_vec_mut::<Pkcs7>(data)
-                    .into())
-            }
-            (BlockCipher::Aes192, Mode::Cbc, Padding::ZeroPadding) => {
-                Ok(cbc::Encryptor::<Aes192>::new_from_slices(key, iv)?
-                    .encrypt_padded_vec_mut::<ZeroPadding>(data)
-                    .into())
-            }
-            (BlockCipher::Aes192, Mode::Cbc, Padding::NoPadding) => {
-                Ok(cbc::Encryptor::<Aes192>::new_from_slices(key, iv)?
-                    .encrypt_padded_vec_mut::<NoPadding>(data)
-                    .into())
-            }
-            (BlockCipher::Aes192, Mode::Cbc, Padding::Iso7816) => {
-                Ok(cbc::Encryptor::<Aes192>::new_from_slices(key, iv)?
-                    .encrypt_padded_vec_mut::<Iso7816>(data)
-                    .into())
-            }
-            (BlockCipher::Aes192, Mode::Cbc, Padding::Iso10126) => {
-                Ok(cbc::Encryptor::<Aes192>::new_from_slices(key, iv)?
-                    .encrypt_padded_vec_mut::<Iso10126>(data)
-                    .into())
-            }
-            (BlockCipher::Aes192, Mode::Cbc, Padding::AnsiX923) => {
-                Ok(cbc::Encryptor::<Aes192>::new_from_slices(key, iv)?
-                    .encrypt_padded_vec_mut::<AnsiX923>(data)
-                    .into())
-            }
+struct Encrypt<'a, 'b> {
+    mode: &'b Mode,
+    padding: &'b Padding,
+    key: &'b [u8],
+    iv: &'b [u8],
+    data: &'a mut [u8],
+}
 
-            // AES256 CBC
-            (BlockCipher::Aes256, Mode::Cbc, Padding::ZeroPadding) => {
-                Ok(cbc::Encryptor::<Aes256>::new_from_slices(key, iv)?
-                    .encrypt_padded_vec_mut::<ZeroPadding>(data)
-                    .into())
-            }
-            (BlockCipher::Aes256, Mode::Cbc, Padding::NoPadding) => {
-                Ok(cbc::Encryptor::<Aes256>::new_from_slices(key, iv)?
-                    .encrypt_padded_vec_mut::<NoPadding>(data)
-                    .into())
-            }
-            (BlockCipher::Aes256, Mode::Cbc, Padding::Iso7816) => {
-                Ok(cbc::Encryptor::<Aes256>::new_from_slices(key, iv)?
-                    .encrypt_padded_vec_mut::<Iso7816>(data)
-                    .into())
-            }
-            (BlockCipher::Aes256, Mode::Cbc, Padding::Iso10126) => {
-                Ok(cbc::Encryptor::<Aes256>::new_from_slices(key, iv)?
-                    .encrypt_padded_vec_mut::<Iso10126>(data)
-                    .into())
-            }
-            (BlockCipher::Aes256, Mode::Cbc, Padding::AnsiX923) => {
-                Ok(cbc::Encryptor::<Aes256>::new_from_slices(key, iv)?
-                    .encrypt_padded_vec_mut::<AnsiX923>(data)
-                    .into())
-            }
-            (BlockCipher::Aes256, Mode::Cbc, Padding::Pkcs7) => {
-                Ok(cbc::Encryptor::<Aes256>::new_from_slices(key, iv)?
-                    .encrypt_padded_vec_mut::<Pkcs7>(data)
-                    .into())
-            }
+impl<'a, 'b> CipherVisitor for Encrypt<'a, 'b> {
+    type Output = Result<Cow<'a, [u8]>, CipherError>;
 
-            // AES128 ECB
-            (BlockCipher::Aes128, Mode::Ecb, Padding::Pkcs7) => {
-                Ok(ecb::Encryptor::<Aes128>::new_from_slice(key)?
-                    .encrypt_padded_vec_mut::<Pkcs7>(data)
-                    .into())
-            }
-            (BlockCipher::Aes128, Mode::Ecb, Padding::ZeroPadding) => {
-                Ok(ecb::Encryptor::<Aes128>::new_from_slice(key)?
-                    .encrypt_padded_vec_mut::<ZeroPadding>(data)
-                    .into())
-            }
-            (BlockCipher::Aes128, Mode::Ecb, Padding::NoPadding) => {
-                Ok(ecb::Encryptor::<Aes128>::new_from_slice(key)?
-                    .encrypt_padded_vec_mut::<NoPadding>(data)
-                    .into())
-            }
-            (BlockCipher::Aes128, Mode::Ecb, Padding::Iso7816) => {
-                Ok(ecb::Encryptor::<Aes128>::new_from_slice(key)?
-                    .encrypt_padded_vec_mut::<Iso7816>(data)
-                    .into())
-            }
-            (BlockCipher::Aes128, Mode::Ecb, Padding::Iso10126) => {
-                Ok(ecb::Encryptor::<Aes128>::new_from_slice(key)?
-                    .encrypt_padded_vec_mut::<Iso10126>(data)
-                    .into())
-            }
-            (BlockCipher::Aes128, Mode::Ecb, Padding::AnsiX923) => {
-                Ok(ecb::Encryptor::<Aes128>::new_from_slice(key)?
-                    .encrypt_padded_vec_mut::<AnsiX923>(data)
-                    .into())
-            }
-            (BlockCipher::Aes192, Mode::Ecb, Padding::Pkcs7) => {
-                Ok(ecb::Encryptor::<Aes192>::new_from_slice(key)?
-                    .encrypt_padded_vec_mut::<Pkcs7>(data)
-                    .into())
+    fn visit<C: BlockCipherImpl>(self) -> Self::Output {
+        let Self {
+            mode,
+            padding,
+            key,
+            iv,
+            data,
+        } = self;
+
+        let (key, tweak_key) = key.split_at(mode.cipher_key_len(key.len()));
+        let cipher = C::new_from_slice(key)?;
+
+        let encrypted = match mode {
+            Mode::Ecb => padding.encrypt(ecb::Encryptor::inner_init(cipher), data),
+            Mode::Cbc => padding.encrypt(cbc::Encryptor::inner_iv_slice_init(cipher, iv)?, data),
+            Mode::Pcbc => padding.encrypt(pcbc::Encryptor::inner_iv_slice_init(cipher, iv)?, data),
+            Mode::Ctr => {
+                cipher.apply_ctr(iv, data)?;
+                return Ok(Cow::Borrowed(data));
+            }
+            Mode::Cfb => {
+                cfb_mode::Encryptor::inner_iv_slice_init(cipher, iv)?.encrypt(data);
+                return Ok(Cow::Borrowed(data));
+            }
+            Mode::Cfb8 => {
+                cfb8::Encryptor::inner_iv_slice_init(cipher, iv)?.encrypt(data);
+                return Ok(Cow::Borrowed(data));
+            }
+            Mode::Ofb => {
+                cipher.apply_ofb(iv, data)?;
+                return Ok(Cow::Borrowed(data));
+            }
+            Mode::Xts => {
+                xts(cipher, C::new_from_slice(tweak_key)?, iv, data, true)?;
+                return Ok(Cow::Borrowed(data));
             }
+        };
 
-            // AES192 ECB
-            (BlockCipher::Aes192, Mode::Ecb, Padding::ZeroPadding) => {
-                Ok(ecb::Encryptor::<Aes192>::new_from_slice(key)?
-                    .encrypt_padded_vec_mut::<ZeroPadding>(data)
-                    .into())
-            }
-            (BlockCipher::Aes192, Mode::Ecb, Padding::NoPadding) => {
-                Ok(ecb::Encryptor::<Aes192>::new_from_slice(key)?
-                    .encrypt_padded_vec_mut::<NoPadding>(data)
-                    .into())
-            }
-            (BlockCipher::Aes192, Mode::Ecb, Padding::Iso7816) => {
-                Ok(ecb::Encryptor::<Aes192>::new_from_slice(key)?
-                    .encrypt_padded_vec_mut::<Iso7816>(data)
-                    .into())
-            }
-            (BlockCipher::Aes192, Mode::Ecb, Padding::Iso10126) => {
-                Ok(ecb::Encryptor::<Aes192>::new_from_slice(key)?
-                    .encrypt_padded_vec_mut::<Iso7816>(data)
-                    .into())
-            }
-            (BlockCipher::Aes192, Mode::Ecb, Padding::AnsiX923) => {
-                Ok(ecb::Encryptor::<Aes192>::new_from_slice(key)?
-                    .encrypt_padded_vec_mut::<AnsiX923>(data)
-                    .into())
-            }
+        Ok(encrypted.into())
+    }
+}
 
-            // AES256 ECB
-            (BlockCipher::Aes256, Mode::Ecb, Padding::Pkcs7) => {
-                Ok(ecb::Encryptor::<Aes256>::new_from_slice(key)?
-                    .encrypt_padded_vec_mut::<Pkcs7>(data)
-                    .into())
-            }
-            (BlockCipher::Aes256, Mode::Ecb, Padding::ZeroPadding) => {
-                Ok(ecb::Encryptor::<Aes256>::new_from_slice(key)?
-                    .encrypt_padded_vec_mut::<ZeroPadding>(data)
-                    .into())
-            }
-            (BlockCipher::Aes256, Mode::Ecb, Padding::NoPadding) => {
-                Ok(ecb::Encryptor::<Aes256>::new_from_slice(key)?
-                    .encrypt_padded_vec_mut::<NoPadding>(data)
-                    .into())
-            }
-            (BlockCipher::Aes256, Mode::Ecb, Padding::Iso7816) => {
-                Ok(ecb::Encryptor::<Aes256>::new_from_slice(key)?
-                    .encrypt_padded_vec_mut::<Iso7816>(data)
-                    .into())
-            }
-            (BlockCipher::Aes256, Mode::Ecb, Padding::Iso10126) => {
-                Ok(ecb::Encryptor::<Aes256>::new_from_slice(key)?
-                    .encrypt_padded_vec_mut::<Iso10126>(data)
-                    .into())
-            }
-            (BlockCipher::Aes256, Mode::Ecb, Padding::AnsiX923) => {
-                Ok(ecb::Encryptor::<Aes256>::new_from_slice(key)?
-                    .encrypt_padded_vec_mut::<AnsiX923>(data)
-                    .into())
-            }
+struct Decrypt<'a, 'b> {
+    mode: &'b Mode,
+    padding: &'b Padding,
+    key: &'b [u8],
+    iv: &'b [u8],
+    encrypted_data: &'a mut [u8],
+}
 
-            // AES CTR
-            (BlockCipher::Aes128, Mode::Ctr, _) => {
-                Ctr128BE::<Aes128>::new_from_slices(key, iv)?.apply_keystream(data);
-                Ok(Cow::Borrowed(data))
-            }
+impl<'a, 'b> CipherVisitor for Decrypt<'a, 'b> {
+    type Output = Result<Cow<'a, [u8]>, CipherError>;
 
-            (BlockCipher::Aes192, Mode::Ctr, _) => {
-                Ctr128BE::<Aes192>::new_from_slices(key, iv)?.apply_keystream(data);
-                Ok(std::borrow::Cow::Borrowed(data))
+    fn visit<C: BlockCipherImpl>(self) -> Self::Output {
+        let Self {
+            mode,
+            padding,
+            key,
+            iv,
+            encrypted_data,
+        } = self;
+
+        let (key, tweak_key) = key.split_at(mode.cipher_key_len(key.len()));
+        let cipher = C::new_from_slice(key)?;
+
+        let decrypted = match mode {
+            Mode::Ecb => padding.decrypt(ecb::Decryptor::inner_init(cipher), encrypted_data)?,
+            Mode::Cbc => padding.decrypt(
+                cbc::Decryptor::inner_iv_slice_init(cipher, iv)?,
+                encrypted_data,
+            )?,
+            Mode::Pcbc => padding.decrypt(
+                pcbc::Decryptor::inner_iv_slice_init(cipher, iv)?,
+                encrypted_data,
+            )?,
+            Mode::Ctr => {
+                cipher.apply_ctr(iv, encrypted_data)?;
+                return Ok(Cow::Borrowed(encrypted_data));
+            }
+            Mode::Cfb => {
+                cfb_mode::Decryptor::inner_iv_slice_init(cipher, iv)?.decrypt(encrypted_data);
+                return Ok(Cow::Borrowed(encrypted_data));
+            }
+            Mode::Cfb8 => {
+                cfb8::Decryptor::inner_iv_slice_init(cipher, iv)?.decrypt(encrypted_data);
+                return Ok(Cow::Borrowed(encrypted_data));
+            }
+            Mode::Ofb => {
+                cipher.apply_ofb(iv, encrypted_data)?;
+                return Ok(Cow::Borrowed(encrypted_data));
+            }
+            Mode::Xts => {
+                xts(
+                    cipher,
+                    C::new_from_slice(tweak_key)?,
+                    iv,
+                    encrypted_data,
+                    false,
+                )?;
+                return Ok(Cow::Borrowed(encrypted_data));
             }
+        };
 
-            (BlockCipher::Aes256, Mode::Ctr, _) => {
-                Ctr128BE::<Aes256>::new_from_slices(key, iv)?.apply_keystream(data);
-                Ok(std::borrow::Cow::Borrowed(data))
-            }
+        Ok(decrypted.into())
+    }
+}
 
-            // Blowfish CBC
-            (BlockCipher::Blowfish, Mode::Cbc, Padding::Pkcs7) => {
-                Ok(cbc::Encryptor::<Blowfish>::new_from_slices(key, iv)?
-                    .encrypt_padded_vec_mut::<Pkcs7>(data)
-                    .into())
-            }
-            (BlockCipher::Blowfish, Mode::Cbc, Padding::ZeroPadding) => {
-                Ok(cbc::Encryptor::<Blowfish>::new_from_slices(key, iv)?
-                    .encrypt_padded_vec_mut::<ZeroPadding>(data)
-                    .into())
-            }
-            (BlockCipher::Blowfish, Mode::Cbc, Padding::NoPadding) => {
-                Ok(cbc::Encryptor::<Blowfish>::new_from_slices(key, iv)?
-                    .encrypt_padded_vec_mut::<NoPadding>(data)
-                    .into())
-            }
-            (BlockCipher::Blowfish, Mode::Cbc, Padding::Iso7816) => {
-                Ok(cbc::Encryptor::<Blowfish>::new_from_slices(key, iv)?
-                    .encrypt_padded_vec_mut::<Iso7816>(data)
-                    .into())
-            }
-            (BlockCipher::Blowfish, Mode::Cbc, Padding::Iso10126) => {
-                Ok(cbc::Encryptor::<Blowfish>::new_from_slices(key, iv)?
-                    .encrypt_padded_vec_mut::<Iso10126>(data)
-                    .into())
-            }
-            (BlockCipher::Blowfish, Mode::Cbc, Padding::AnsiX923) => {
-                Ok(cbc::Encryptor::<Blowfish>::new_from_slices(key, iv)?
-                    .encrypt_padded_vec_mut::<AnsiX923>(data)
-                    .into())
-            }
+/// XTS (IEEE 1619) with ciphertext stealing, iv is the 16-byte tweak
+/// (usually the sector number, little-endian)
+fn xts<C: BlockCipherImpl>(
+    mut cipher: C,
+    mut tweak_cipher: C,
+    iv: &[u8],
+    data: &mut [u8],
+    encrypt: bool,
+) -> Result<(), CipherError> {
+    check_block_size::<C>("xts")?;
+    if iv.len() != 16 {
+        return Err(CipherError::InvalidTweakLen(iv.len()));
+    }
+    if data.len() < 16 {
+        return Err(CipherError::InvalidDataLen(data.len()));
+    }
 
-            // Blowfish ECB
-            (BlockCipher::Blowfish, Mode::Ecb, Padding::Pkcs7) => {
-                Ok(ecb::Encryptor::<Blowfish>::new_from_slice(key)?
-                    .encrypt_padded_vec_mut::<Pkcs7>(data)
-                    .into())
-            }
-            (BlockCipher::Blowfish, Mode::Ecb, Padding::ZeroPadding) => {
-                Ok(ecb::Encryptor::<Blowfish>::new_from_slice(key)?
-                    .encrypt_padded_vec_mut::<ZeroPadding>(data)
-                    .into())
-            }
-            (BlockCipher::Blowfish, Mode::Ecb, Padding::NoPadding) => {
-                Ok(ecb::Encryptor::<Blowfish>::new_from_slice(key)?
-                    .encrypt_padded_vec_mut::<NoPadding>(data)
-                    .into())
-            }
-            (BlockCipher::Blowfish, Mode::Ecb, Padding::Iso7816) => {
-                Ok(ecb::Encryptor::<Blowfish>::new_from_slice(key)?
-                    .encrypt_padded_vec_mut::<Iso7816>(data)
-                    .into())
-            }
-            (BlockCipher::Blowfish, Mode::Ecb, Padding::Iso10126) => {
-                Ok(ecb::Encryptor::<Blowfish>::new_from_slice(key)?
-                    .encrypt_padded_vec_mut::<Iso10126>(data)
-                    .into())
-            }
-            (BlockCipher::Blowfish, Mode::Ecb, Padding::AnsiX923) => {
-                Ok(ecb::Encryptor::<Blowfish>::new_from_slice(key)?
-                    .encrypt_padded_vec_mut::<AnsiX923>(data)
-                    .into())
-            }
+    let mut tweak = Block::<C>::default();
+    tweak.copy_from_slice(iv);
+    tweak_cipher.encrypt_block_mut(&mut tweak);
+    let mut tweak = u128::from_le_bytes(tweak[..].try_into().unwrap_or_default());
+
+    let mut process = |block: &mut [u8], tweak: u128| {
+        let tweak = tweak.to_le_bytes();
+        let mut buf = Block::<C>::default();
+        buf.iter_mut()
+            .zip(block.iter().zip(tweak))
+            .for_each(|(b, (x, t))| *b = x ^ t);
+        match encrypt {
+            true => cipher.encrypt_block_mut(&mut buf),
+            false => cipher.decrypt_block_mut(&mut buf),
+        }
+        block
+            .iter_mut()
+            .zip(buf.iter().zip(tweak))
+            .for_each(|(x, (b, t))| *x = b ^ t);
+    };
+    // multiplication by x in GF(2^128)
+    let next = |tweak: u128| (tweak << 1) ^ ((tweak >> 127) * 0x87);
+
+    let tail = data.len() % 16;
+    // with a partial last block the last full block is stolen from
+    let full_blocks = match tail {
+        0 => data.len() / 16,
+        _ => data.len() / 16 - 1,
+    };
+    for block in data.chunks_exact_mut(16).take(full_blocks) {
+        process(block, tweak);
+        tweak = next(tweak);
+    }
+
+    if tail != 0 {
+        let (last, partial) = data[full_blocks * 16..].split_at_mut(16);
+        let (first, second) = match encrypt {
+            true => (tweak, next(tweak)),
+            false => (next(tweak), tweak),
+        };
+        process(last, first);
+        last[..tail].swap_with_slice(partial);
+        process(last, second);
+    }
+
+    Ok(())
+}
+
+fn check_block_size<C: BlockCipherImpl>(name: &'static str) -> Result<(), CipherError> {
+    match C::block_size() {
+        16 => Ok(()),
+        block_size => Err(CipherError::UnsupportedBlockSize(name, block_size * 8)),
+    }
+}
+
+const KW_IV: [u8; 8] = [0xa6; 8];
+const KWP_IV: [u8; 4] = [0xa6, 0x59, 0x59, 0xa6];
+
+/// RFC 3394 (kw, data is a multiple of 8 bytes, at least 16) or RFC 5649 (kwp, any length)
+pub enum KeyWrap {
+    Kw,
+    Kwp,
+}
+
+impl FromStr for KeyWrap {
+    type Err = CipherError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let key_wrap = match s {
+            "kw" | "" => KeyWrap::Kw,
+            "kwp" => KeyWrap::Kwp,
+            _ => return Err(CipherError::InvalidMode(s.to_owned())),
+        };
+        Ok(key_wrap)
+    }
+}
 
-            (BlockCipher::Blowfish, Mode::Ctr, _) => Err(CipherError::CtrBlowfish),
+impl KeyWrap {
+    pub fn wrap_key(
+        &self,
+        cipher: BlockCipher,
+        kek: &[u8],
+        data: &[u8],
+    ) -> Result<Vec<u8>, CipherError> {
+        if !cipher.validate_key(kek) {
+            return Err(CipherError::InvalidKeyLen(kek.len()));
         }
+
+        cipher.visit(Wrap {
+            key_wrap: self,
+            kek,
+            data,
+        })
     }
 
-    pub fn decrypt<'a>(
+    pub fn unwrap_key(
         &self,
-        key: &[u8],
-        iv: &[u8],
-        encrypted_data: &'a mut [u8],
-    ) -> Result<Cow<'a, [u8]>, CipherError> {
-        if !Self::validate_key(self.cipher, key) {
-            return Err(CipherError::InvalidKeyLen(key.len()));
+        cipher: BlockCipher,
+        kek: &[u8],
+        wrapped: &[u8],
+    ) -> Result<Vec<u8>, CipherError> {
+        if !cipher.validate_key(kek) {
+            return Err(CipherError::InvalidKeyLen(kek.len()));
         }
 
-        match (&self.cipher, &self.mode, &self.padding) {
-            // AES128 CBC
-            (BlockCipher::Aes128, Mode::Cbc, Padding::Pkcs7) => {
-                Ok(cbc::Decryptor::<Aes128>::new_from_slices(key, iv)?
-                    .decrypt_padded_vec_mut::<Pkcs7>(encrypted_data)?
-                    .into())
-            }
-            (BlockCipher::Aes128, Mode::Cbc, Padding::ZeroPadding) => {
-                Ok(cbc::Decryptor::<Aes128>::new_from_slices(key, iv)?
-                    .decrypt_padded_vec_mut::<ZeroPadding>(encrypted_data)?
-                    .into())
-            }
-            (BlockCipher::Aes128, Mode::Cbc, Padding::NoPadding) => {
-                Ok(cbc::Decryptor::<Aes128>::new_from_slices(key, iv)?
-                    .decrypt_padded_vec_mut::<NoPadding>(encrypted_data)?
-                    .into())
-            }
-            (BlockCipher::Aes128, Mode::Cbc, Padding::Iso7816) => {
-                Ok(cbc::Decryptor::<Aes128>::new_from_slices(key, iv)?
-                    .decrypt_padded_vec_mut::<Iso7816>(encrypted_data)?
-                    .into())
-            }
-            (BlockCipher::Aes128, Mode::Cbc, Padding::Iso10126) => {
-                Ok(cbc::Decryptor::<Aes128>::new_from_slices(key, iv)?
-                    .decrypt_padded_vec_mut::<Iso10126>(encrypted_data)?
-                    .into())
-            }
-            (BlockCipher::Aes128, Mode::Cbc, Padding::AnsiX923) => {
-                Ok(cbc::Decryptor::<Aes128>::new_from_slices(key, iv)?
-                    .decrypt_padded_vec_mut::<AnsiX923>(encrypted_data)?
-                    .into())
-            }
+        cipher.visit(Unwrap {
+            key_wrap: self,
+            kek,
+            wrapped,
+        })
+    }
+}
 
-            // AES192 CBC
-            (BlockCipher::Aes192, Mode::Cbc, Padding::Pkcs7) => {
-                Ok(cbc::Decryptor::<Aes192>::new_from_slices(key, iv)?
-                    .decrypt_padded_vec_mut::<Pkcs7>(encrypted_data)?
-                    .into())
-            }
-            (BlockCipher::Aes192, Mode::Cbc, Padding::ZeroPadding) => {
-                Ok(cbc::Decryptor::<Aes192>::new_from_slices(key, iv)?
-                    .decrypt_padded_vec_mut::<ZeroPadding>(encrypted_data)?
-                    .into())
-            }
-            (BlockCipher::Aes192, Mode::Cbc, Padding::NoPadding) => {
-                Ok(cbc::Decryptor::<Aes192>::new_from_slices(key, iv)?
-                    .decrypt_padded_vec_mut::<NoPadding>(encrypted_data)?
-                    .into())
-            }
-            (BlockCipher::Aes192, Mode::Cbc, Padding::Iso7816) => {
-                Ok(cbc::Decryptor::<Aes192>::new_from_slices(key, iv)?
-                    .decrypt_padded_vec_mut::<Iso7816>(encrypted_data)?
-                    .into())
-            }
-            (BlockCipher::Aes192, Mode::Cbc, Padding::Iso10126) => {
-                Ok(cbc::Decryptor::<Aes192>::new_from_slices(key, iv)?
-                    .decrypt_padded_vec_mut::<Iso10126>(encrypted_data)?
-                    .into())
-            }
-            (BlockCipher::Aes192, Mode::Cbc, Padding::AnsiX923) => {
-                Ok(cbc::Decryptor::<Aes192>::new_from_slices(key, iv)?
-                    .decrypt_padded_vec_mut::<AnsiX923>(encrypted_data)?
-                    .into())
-            }
+struct Wrap<'a> {
+    key_wrap: &'a KeyWrap,
+    kek: &'a [u8],
+    data: &'a [u8],
+}
 
-            // AES256 CBC
-            (BlockCipher::Aes256, Mode::Cbc, Padding::ZeroPadding) => {
-                Ok(cbc::Decryptor::<Aes256>::new_from_slices(key, iv)?
-                    .decrypt_padded_vec_mut::<ZeroPadding>(encrypted_data)?
-                    .into())
-            }
-            (BlockCipher::Aes256, Mode::Cbc, Padding::NoPadding) => {
-                Ok(cbc::Decryptor::<Aes256>::new_from_slices(key, iv)?
-                    .decrypt_padded_vec_mut::<NoPadding>(encrypted_data)?
-                    .into())
-            }
-            (BlockCipher::Aes256, Mode::Cbc, Padding::Iso7816) => {
-                Ok(cbc::Decryptor::<Aes256>::new_from_slices(key, iv)?
-                    .decrypt_padded_vec_mut::<Iso7816>(encrypted_data)?
-                    .into())
-            }
-            (BlockCipher::Aes256, Mode::Cbc, Padding::Iso10126) => {
-                Ok(cbc::Decryptor::<Aes256>::new_from_slices(key, iv)?
-                    .decrypt_padded_vec_mut::<Iso10126>(encrypted_data)?
-                    .into())
-            }
-            (BlockCipher::Aes256, Mode::Cbc, Padding::AnsiX923) => {
-                Ok(cbc::Decryptor::<Aes256>::new_from_slices(key, iv)?
-                    .decrypt_padded_vec_mut::<AnsiX923>(encrypted_data)?
-                    .into())
-            }
-            (BlockCipher::Aes256, Mode::Cbc, Padding::Pkcs7) => {
-                Ok(cbc::Decryptor::<Aes256>::new_from_slices(key, iv)?
-                    .decrypt_padded_vec_mut::<Pkcs7>(encrypted_data)?
-                    .into())
-            }
+impl<'a> CipherVisitor for Wrap<'a> {
+    type Output = Result<Vec<u8>, CipherError>;
 
-            // AES128 ECB
-            (BlockCipher::Aes128, Mode::Ecb, Padding::Pkcs7) => {
-                Ok(ecb::Decryptor::<Aes128>::new_from_slice(key)?
-                    .decrypt_padded_vec_mut::<Pkcs7>(encrypted_data)?
-                    .into())
-            }
-            (BlockCipher::Aes128, Mode::Ecb, Padding::ZeroPadding) => {
-                Ok(ecb::Decryptor::<Aes128>::new_from_slice(key)?
-                    .decrypt_padded_vec_mut::<ZeroPadding>(encrypted_data)?
-                    .into())
-            }
-            (BlockCipher::Aes128, Mode::Ecb, Padding::NoPadding) => {
-                Ok(ecb::Decryptor::<Aes128>::new_from_slice(key)?
-                    .decrypt_padded_vec_mut::<NoPadding>(encrypted_data)?
-                    .into())
-            }
-            (BlockCipher::Aes128, Mode::Ecb, Padding::Iso7816) => {
-                Ok(ecb::Decryptor::<Aes128>::new_from_slice(key)?
-                    .decrypt_padded_vec_mut::<Iso7816>(encrypted_data)?
-                    .into())
-            }
-            (BlockCipher::Aes128, Mode::Ecb, Padding::Iso10126) => {
-                Ok(ecb::Decryptor::<Aes128>::new_from_slice(key)?
-                    .decrypt_padded_vec_mut::<Iso10126>(encrypted_data)?
-                    .into())
-            }
-            (BlockCipher::Aes128, Mode::Ecb, Padding::AnsiX923) => {
-                Ok(ecb::Decryptor::<Aes128>::new_from_slice(key)?
-                    .decrypt_padded_vec_mut::<AnsiX923>(encrypted_data)?
-                    .into())
-            }
-            (BlockCipher::Aes192, Mode::Ecb, Padding::Pkcs7) => {
-                Ok(ecb::Decryptor::<Aes192>::new_from_slice(key)?
-                    .decrypt_padded_vec_mut::<Pkcs7>(encrypted_data)?
-                    .into())
-            }
+    fn visit<C: BlockCipherImpl>(self) -> Self::Output {
+        let Self {
+            key_wrap,
+            kek,
+            data,
+        } = self;
 
-            // AES192 ECB
-            (BlockCipher::Aes192, Mode::Ecb, Padding::ZeroPadding) => {
-                Ok(ecb::Decryptor::<Aes192>::new_from_slice(key)?
-                    .decrypt_padded_vec_mut::<ZeroPadding>(encrypted_data)?
-                    .into())
-            }
-            (BlockCipher::Aes192, Mode::Ecb, Padding::NoPadding) => {
-                Ok(ecb::Decryptor::<Aes192>::new_from_slice(key)?
-                    .decrypt_padded_vec_mut::<NoPadding>(encrypted_data)?
-                    .into())
-            }
-            (BlockCipher::Aes192, Mode::Ecb, Padding::Iso7816) => {
-                Ok(ecb::Decryptor::<Aes192>::new_from_slice(key)?
-                    .decrypt_padded_vec_mut::<Iso7816>(encrypted_data)?
-                    .into())
-            }
-            (BlockCipher::Aes192, Mode::Ecb, Padding::Iso10126) => {
-                Ok(ecb::Decryptor::<Aes192>::new_from_slice(key)?
-                    .decrypt_padded_vec_mut::<Iso7816>(encrypted_data)?
-                    .into())
-            }
-            (BlockCipher::Aes192, Mode::Ecb, Padding::AnsiX923) => {
-                Ok(ecb::Decryptor::<Aes192>::new_from_slice(key)?
-                    .decrypt_padded_vec_mut::<AnsiX923>(encrypted_data)?
-                    .into())
-            }
+        check_block_size::<C>("key wrap")?;
+        let mut cipher = C::new_from_slice(kek)?;
 
-            // AES256 ECB
-            (BlockCipher::Aes256, Mode::Ecb, Padding::Pkcs7) => {
-                Ok(ecb::Decryptor::<Aes256>::new_from_slice(key)?
-                    .decrypt_padded_vec_mut::<Pkcs7>(encrypted_data)?
-                    .into())
-            }
-            (BlockCipher::Aes256, Mode::Ecb, Padding::ZeroPadding) => {
-                Ok(ecb::Decryptor::<Aes256>::new_from_slice(key)?
-                    .decrypt_padded_vec_mut::<ZeroPadding>(encrypted_data)?
-                    .into())
-            }
-            (BlockCipher::Aes256, Mode::Ecb, Padding::NoPadding) => {
-                Ok(ecb::Decryptor::<Aes256>::new_from_slice(key)?
-                    .decrypt_padded_vec_mut::<NoPadding>(encrypted_data)?
-                    .into())
+        let (iv, padded) = match key_wrap {
+            KeyWrap::Kw if data.len() >= 16 && data.len().is_multiple_of(8) => {
+                (KW_IV, data.to_vec())
             }
-            (BlockCipher::Aes256, Mode::Ecb, Padding::Iso7816) => {
-                Ok(ecb::Decryptor::<Aes256>::new_from_slice(key)?
-                    .decrypt_padded_vec_mut::<Iso7816>(encrypted_data)?
-                    .into())
-            }
-            (BlockCipher::Aes256, Mode::Ecb, Padding::Iso10126) => {
-                Ok(ecb::Decryptor::<Aes256>::new_from_slice(key)?
-                    .decrypt_padded_vec_mut::<Iso10126>(encrypted_data)?
-                    .into())
-            }
-            (BlockCipher::Aes256, Mode::Ecb, Padding::AnsiX923) => {
-                Ok(ecb::Decryptor::<Aes256>::new_from_slice(key)?
-                    .decrypt_padded_vec_mut::<AnsiX923>(encrypted_data)?
-                    .into())
+            KeyWrap::Kwp if !data.is_empty() && data.len() <= u32::MAX as usize => {
+                let mut iv = [0u8; 8];
+                iv[..4].copy_from_slice(&KWP_IV);
+                iv[4..].copy_from_slice(&(data.len() as u32).to_be_bytes());
+                let mut padded = data.to_vec();
+                padded.resize(data.len().div_ceil(8) * 8, 0);
+                (iv, padded)
             }
+            _ => return Err(CipherError::InvalidDataLen(data.len())),
+        };
 
-            // AES CTR
-            (BlockCipher::Aes128, Mode::Ctr, _) => {
-                Ctr128BE::<Aes128>::new_from_slices(key, iv)?.apply_keystream(encrypted_data);
-                Ok(Cow::Borrowed(encrypted_data))
-            }
+        // kwp with a single semiblock is just one block encryption
+        if padded.len() == 8 {
+            let mut block = Block::<C>::default();
+            block[..8].copy_from_slice(&iv);
+            block[8..].copy_from_slice(&padded);
+            cipher.encrypt_block_mut(&mut block);
+            return Ok(block.to_vec());
+        }
 
-            (BlockCipher::Aes192, Mode::Ctr, _) => {
-                Ctr128BE::<Aes192>::new_from_slices(key, iv)?.apply_keystream(encrypted_data);
-                Ok(std::borrow::Cow::Borrowed(encrypted_data))
-            }
+        Ok(kw_wrap(&mut cipher, iv, padded))
+    }
+}
 
-            (BlockCipher::Aes256, Mode::Ctr, _) => {
-                Ctr128BE::<Aes256>::new_from_slices(key, iv)?.apply_keystream(encrypted_data);
-                Ok(std::borrow::Cow::Borrowed(encrypted_data))
-            }
+struct Unwrap<'a> {
+    key_wrap: &'a KeyWrap,
+    kek: &'a [u8],
+    wrapped: &'a [u8],
+}
 
-            // Blowfish CBC
-            (BlockCipher::Blowfish, Mode::Cbc, Padding::Pkcs7) => {
-                Ok(cbc::Decryptor::<Blowfish>::new_from_slices(key, iv)?
-                    .decrypt_padded_vec_mut::<Pkcs7>(encrypted_data)?
-                    .into())
-            }
-            (BlockCipher::Blowfish, Mode::Cbc, Padding::ZeroPadding) => {
-                Ok(cbc::Decryptor::<Blowfish>::new_from_slices(key, iv)?
-                    .decrypt_padded_vec_mut::<ZeroPadding>(encrypted_data)?
-                    .into())
-            }
-            (BlockCipher::Blowfish, Mode::Cbc, Padding::NoPadding) => {
-                Ok(cbc::Decryptor::<Blowfish>::new_from_slices(key, iv)?
-                    .decrypt_padded_vec_mut::<NoPadding>(encrypted_data)?
-                    .into())
-            }
-            (BlockCipher::Blowfish, Mode::Cbc, Padding::Iso7816) => {
-                Ok(cbc::Decryptor::<Blowfish>::new_from_slices(key, iv)?
-                    .decrypt_padded_vec_mut::<Iso7816>(encrypted_data)?
-                    .into())
-            }
-            (BlockCipher::Blowfish, Mode::Cbc, Padding::Iso10126) => {
-                Ok(cbc::Decryptor::<Blowfish>::new_from_slices(key, iv)?
-                    .decrypt_padded_vec_mut::<Iso10126>(encrypted_data)?
-                    .into())
-            }
-            (BlockCipher::Blowfish, Mode::Cbc, Padding::AnsiX923) => {
-                Ok(cbc::Decryptor::<Blowfish>::new_from_slices(key, iv)?
-                    .decrypt_padded_vec_mut::<AnsiX923>(encrypted_data)?
-                    .into())
-            }
+impl<'a> CipherVisitor for Unwrap<'a> {
+    type Output = Result<Vec<u8>, CipherError>;
 
-            // Blowfish ECB
-            (BlockCipher::Blowfish, Mode::Ecb, Padding::Pkcs7) => {
-                Ok(ecb::Decryptor::<Blowfish>::new_from_slice(key)?
-                    .decrypt_padded_vec_mut::<Pkcs7>(encrypted_data)?
-                    .into())
-            }
-            (BlockCipher::Blowfish, Mode::Ecb, Padding::ZeroPadding) => {
-                Ok(ecb::Decryptor::<Blowfish>::new_from_slice(key)?
-                    .decrypt_padded_vec_mut::<ZeroPadding>(encrypted_data)?
-                    .into())
-            }
-            (BlockCipher::Blowfish, Mode::Ecb, Padding::NoPadding) => {
-                Ok(ecb::Decryptor::<Blowfish>::new_from_slice(key)?
-                    .decrypt_padded_vec_mut::<NoPadding>(encrypted_data)?
-                    .into())
-            }
-            (BlockCipher::Blowfish, Mode::Ecb, Padding::Iso7816) => {
-                Ok(ecb::Decryptor::<Blowfish>::new_from_slice(key)?
-                    .decrypt_padded_vec_mut::<Iso7816>(encrypted_data)?
-                    .into())
-            }
-            (BlockCipher::Blowfish, Mode::Ecb, Padding::Iso10126) => {
-                Ok(ecb::Decryptor::<Blowfish>::new_from_slice(key)?
-                    .decrypt_padded_vec_mut::<Iso10126>(encrypted_data)?
-                    .into())
-            }
-            (BlockCipher::Blowfish, Mode::Ecb, Padding::AnsiX923) => {
-                Ok(ecb::Decryptor::<Blowfish>::new_from_slice(key)?
-                    .decrypt_padded_vec_mut::<AnsiX923>(encrypted_data)?
-                    .into())
-            }
+    fn visit<C: BlockCipherImpl>(self) -> Self::Output {
+        let Self {
+            key_wrap,
+            kek,
+            wrapped,
+        } = self;
+
+        check_block_size::<C>("key wrap")?;
+        let mut cipher = C::new_from_slice(kek)?;
 
-            (BlockCipher::Blowfish, Mode::Ctr, _) => Err(CipherError::CtrBlowfish),
+        if !wrapped.len().is_multiple_of(8) || wrapped.len() < 16 {
+            return Err(CipherError::InvalidDataLen(wrapped.len()));
+        }
+
+        match key_wrap {
+            KeyWrap::Kw if wrapped.len() >= 24 => {
+                let (iv, data) = kw_unwrap(&mut cipher, wrapped);
+                match iv == KW_IV {
+                    true => Ok(data),
+                    false => Err(CipherError::KeyUnwrap),
+                }
+            }
+            KeyWrap::Kwp => {
+                let (iv, mut data) = match wrapped.len() {
+                    16 => {
+                        let mut block = Block::<C>::default();
+                        block.copy_from_slice(wrapped);
+                        cipher.decrypt_block_mut(&mut block);
+                        (
+                            block[..8].try_into().unwrap_or_default(),
+                            block[8..].to_vec(),
+                        )
+                    }
+                    _ => kw_unwrap(&mut cipher, wrapped),
+                };
+
+                let len = u32::from_be_bytes(iv[4..].try_into().unwrap_or_default()) as usize;
+                let valid = iv[..4] == KWP_IV
+                    && len <= data.len()
+                    && len + 8 > data.len()
+                    && data[len..].iter().all(|&b| b == 0);
+                match valid {
+                    true => {
+                        data.truncate(len);
+                        Ok(data)
+                    }
+                    false => Err(CipherError::KeyUnwrap),
+                }
+            }
+            _ => Err(CipherError::InvalidDataLen(wrapped.len())),
         }
     }
+}
 
-    fn validate_key(cipher: BlockCipher, key: &[u8]) -> bool {
-        match cipher {
-            BlockCipher::Aes128 => key.len() == 16,
-            BlockCipher::Aes192 => key.len() == 24,
-            BlockCipher::Aes256 => key.len() == 32,
-            BlockCipher::Blowfish => (4..=56).contains(&key.len()),
+/// RFC 3394 wrapping function W, returns iv || wrapped data
+fn kw_wrap<C: BlockCipherImpl>(cipher: &mut C, iv: [u8; 8], mut data: Vec<u8>) -> Vec<u8> {
+    let n = data.len() / 8;
+    let mut a = iv;
+    let mut block = Block::<C>::default();
+
+    for j in 0..6 {
+        for (i, r) in data.chunks_exact_mut(8).enumerate() {
+            block[..8].copy_from_slice(&a);
+            block[8..].copy_from_slice(r);
+            cipher.encrypt_block_mut(&mut block);
+
+            let t = (n * j + i + 1) as u64;
+            a.copy_from_slice(&block[..8]);
+            a = (u64::from_be_bytes(a) ^ t).to_be_bytes();
+            r.copy_from_slice(&block[8..]);
         }
     }
+
+    [&a[..], &data].concat()
+}
+
+/// Inverse of [`kw_wrap`], returns the integrity check value and the data
+fn kw_unwrap<C: BlockCipherImpl>(cipher: &mut C, wrapped: &[u8]) -> ([u8; 8], Vec<u8>) {
+    let mut a: [u8; 8] = wrapped[..8].try_into().unwrap_or_default();
+    let mut data = wrapped[8..].to_vec();
+    let n = data.len() / 8;
+    let mut block = Block::<C>::default();
+
+    for j in (0..6).rev() {
+        for (i, r) in data.chunks_exact_mut(8).enumerate().rev() {
+            let t = (n * j + i + 1) as u64;
+            block[..8].copy_from_slice(&(u64::from_be_bytes(a) ^ t).to_be_bytes());
+            block[8..].copy_from_slice(r);
+            cipher.decrypt_block_mut(&mut block);
+
+            a.copy_from_slice(&block[..8]);
+            r.copy_from_slice(&block[8..]);
+        }
+    }
+
+    (a, data)
 }
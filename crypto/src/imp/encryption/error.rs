@@ -1,9 +1,9 @@
 use aes::cipher::block_padding::UnpadError;
 use digest::InvalidLength;
-use rsa::{errors::Error as _RsaError, pkcs8::spki};
+use rsa::{errors::Error as _RsaError, pkcs1, pkcs8, pkcs8::spki};
 use thiserror::Error;
 
-use crate::imp::hashing::error::HashError;
+use crate::imp::{ecc::error::EccError, hashing::error::HashError, kdf::error::KdfError};
 
 #[derive(Error, Debug)]
 pub enum RsaError {
@@ -11,10 +11,22 @@ pub enum RsaError {
     InvalidPublicKey,
     #[error("invalid private key")]
     InvalidPrivateKey,
+    #[error("invalid key (expected PEM, base64 DER or JWK)")]
+    InvalidKey,
+    #[error("invalid key format: {0}")]
+    InvalidKeyFormat(String),
+    #[error("invalid key size: {0}. Only 512-16384 bits accepted")]
+    InvalidKeySize(usize),
+    #[error("invalid jwk: {0}")]
+    InvalidJwk(String),
     #[error("invalid sign mode: {0}")]
     InvalidSignMode(String),
     #[error("rsa key error: {0}")]
     SpkiError(#[from] spki::Error),
+    #[error("rsa key error: {0}")]
+    Pkcs1Error(#[from] pkcs1::Error),
+    #[error("rsa key error: {0}")]
+    Pkcs8Error(#[from] pkcs8::Error),
     #[error(transparent)]
     HashError(#[from] HashError),
     #[error("rsa error: {0}")]
@@ -27,12 +39,50 @@ pub enum CipherError {
     InvalidPadding(String),
     #[error("invalid mode: {0}")]
     InvalidMode(String),
+    #[error("invalid cipher: {0}")]
+    InvalidCipher(String),
     #[error("invalid key length: {0}. Only 16, 24, 32 accepted")]
     InvalidKeyLen(usize),
-    #[error("CTR mode does not support blowfish")]
-    CtrBlowfish,
+    #[error("invalid key length for {0}: {1}")]
+    InvalidKeyLenFor(String, usize),
+    #[error("invalid key length: {0}. Only 32 accepted")]
+    InvalidChaChaKeyLen(usize),
+    #[error("invalid nonce length: {0}")]
+    InvalidNonceLen(usize),
+    #[error("invalid tag length: {0}")]
+    InvalidTagLen(usize),
+    #[error("invalid tweak (iv) length: {0}. Only 16 accepted")]
+    InvalidTweakLen(usize),
+    #[error("xts data key and tweak key must be different")]
+    XtsSameKeys,
+    #[error("invalid data length: {0}")]
+    InvalidDataLen(usize),
+    #[error("{0} requires a 128-bit block cipher, got {1}-bit")]
+    UnsupportedBlockSize(&'static str, usize),
+    #[error("failed to unwrap key (integrity check failed)")]
+    KeyUnwrap,
     #[error("failed to decrypt (unpad failed or bad input data)")]
     Unpad(#[from] UnpadError),
+    #[error("failed to encrypt (data is too long for this nonce)")]
+    Aead,
+    #[error("failed to decrypt (authentication tag mismatch)")]
+    TagMismatch,
+    #[error("invalid openssl cipher: {0}")]
+    InvalidOpensslCipher(String),
+    #[error("invalid kdf: {0}. Only evpkdf and pbkdf2 accepted")]
+    InvalidKdf(String),
+    #[error("invalid salt length: {0}. Only 8 accepted")]
+    InvalidSaltLen(usize),
+    #[error("data doesn't start with Salted__ header")]
+    NoSaltedHeader,
+    #[error("invalid fernet token: {0}")]
+    InvalidFernetToken(&'static str),
+    #[error(transparent)]
+    HashError(#[from] HashError),
+    #[error(transparent)]
+    EccError(#[from] EccError),
+    #[error(transparent)]
+    KdfError(#[from] KdfError),
 }
 
 impl From<InvalidLength> for CipherError {
@@ -0,0 +1,106 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+
+use crate::{
+    imp::hashing::make_hmac,
+    utils::{base64, helpers::constant_time_eq},
+};
+
+use super::{block_decrypt, block_encrypt, error::CipherError};
+
+const VERSION: u8 = 0x80;
+const HEADER_LEN: usize = 1 + 8 + 16;
+const HMAC_LEN: usize = 32;
+/// Allowed clock skew for tokens from the future, same as in the Python implementation
+const MAX_CLOCK_SKEW: u64 = 60;
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|now| now.as_secs())
+        .unwrap_or_default()
+}
+
+/// Fernet keys are 32 bytes in urlsafe base64: signing key || encryption key
+fn split_key(key: &str) -> Result<([u8; 16], [u8; 16]), CipherError> {
+    let key = key.trim();
+    let raw = base64::decode_url(key.trim_end_matches('='))
+        .or_else(|_| base64::decode(key))
+        .unwrap_or_default();
+    if raw.len() != 32 {
+        return Err(CipherError::InvalidKeyLenFor(
+            "fernet".to_owned(),
+            raw.len(),
+        ));
+    }
+
+    let mut signing_key = [0u8; 16];
+    let mut encryption_key = [0u8; 16];
+    signing_key.copy_from_slice(&raw[..16]);
+    encryption_key.copy_from_slice(&raw[16..]);
+    Ok((signing_key, encryption_key))
+}
+
+/// Returns a token compatible with `cryptography.fernet.Fernet` (urlsafe base64 with padding)
+pub fn fernet_encrypt(data: &[u8], key: &str) -> Result<String, CipherError> {
+    let (signing_key, encryption_key) = split_key(key)?;
+
+    let mut iv = [0u8; 16];
+    StdRng::from_entropy().fill_bytes(&mut iv);
+
+    let mut data = data.to_vec();
+    let ciphertext = block_encrypt("aes", &mut data, &encryption_key, &iv, "cbc", "pkcs7")?;
+
+    let mut token = Vec::with_capacity(HEADER_LEN + ciphertext.len() + HMAC_LEN);
+    token.push(VERSION);
+    token.extend_from_slice(&now().to_be_bytes());
+    token.extend_from_slice(&iv);
+    token.extend_from_slice(&ciphertext);
+    let hmac = make_hmac(&token, &signing_key, "sha256")?;
+    token.extend_from_slice(&hmac);
+
+    Ok(base64::encode_url_padded(token))
+}
+
+/// `ttl` is the maximum token age in seconds, no age check if `None`
+pub fn fernet_decrypt(token: &str, key: &str, ttl: Option<u64>) -> Result<Vec<u8>, CipherError> {
+    let (signing_key, encryption_key) = split_key(key)?;
+
+    let token = base64::decode_url(token.trim().trim_end_matches('='))
+        .or(Err(CipherError::InvalidFernetToken("not urlsafe base64")))?;
+    if token.len() < HEADER_LEN + 16 + HMAC_LEN
+        || !(token.len() - HEADER_LEN - HMAC_LEN).is_multiple_of(16)
+    {
+        return Err(CipherError::InvalidFernetToken("invalid length"));
+    }
+    if token[0] != VERSION {
+        return Err(CipherError::InvalidFernetToken("unsupported version"));
+    }
+
+    let (signed, hmac) = token.split_at(token.len() - HMAC_LEN);
+    if !constant_time_eq(&make_hmac(signed, &signing_key, "sha256")?, hmac) {
+        return Err(CipherError::TagMismatch);
+    }
+
+    let mut timestamp = [0u8; 8];
+    timestamp.copy_from_slice(&signed[1..9]);
+    let timestamp = u64::from_be_bytes(timestamp);
+    let now = now();
+    if let Some(ttl) = ttl {
+        if timestamp.saturating_add(ttl) < now {
+            return Err(CipherError::InvalidFernetToken("token has expired"));
+        }
+    }
+    if now.saturating_add(MAX_CLOCK_SKEW) < timestamp {
+        return Err(CipherError::InvalidFernetToken(
+            "timestamp is in the future",
+        ));
+    }
+
+    let iv = &signed[9..HEADER_LEN];
+    let mut ciphertext = signed[HEADER_LEN..].to_vec();
+    let plaintext = block_decrypt("aes", &mut ciphertext, &encryption_key, iv, "cbc", "pkcs7")?;
+
+    Ok(plaintext.into_owned())
+}
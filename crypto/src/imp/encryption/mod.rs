@@ -1,11 +1,25 @@
+mod aead;
 mod aes;
+mod block;
 mod blowfish;
 mod cipher;
-mod error;
+pub mod error;
+mod fernet;
+mod nacl;
+mod openssl;
+mod rc4;
 mod rsa;
+mod rsa_keys;
 mod xor;
 
+pub use self::aead::*;
 pub use self::aes::*;
+pub use self::block::*;
 pub use self::blowfish::*;
+pub use self::fernet::*;
+pub use self::nacl::*;
+pub use self::openssl::*;
+pub use self::rc4::*;
 pub use self::rsa::*;
+pub use self::rsa_keys::*;
 pub use xor::*;
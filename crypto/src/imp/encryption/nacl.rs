@@ -0,0 +1,95 @@
+use crypto_box::{
+    aead::{Aead, KeyInit},
+    Nonce, PublicKey, SalsaBox, SecretKey,
+};
+use crypto_secretbox::XSalsa20Poly1305;
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::imp::ecc::{x25519_private_raw, x25519_public_raw};
+
+use super::error::CipherError;
+
+const NONCE_LEN: usize = 24;
+
+/// Keys are anything x25519 accepts in ecc functions (raw base64 as in tweetnacl, PEM, JWK)
+fn secret_key(key: &str) -> Result<SecretKey, CipherError> {
+    Ok(SecretKey::from(x25519_private_raw(key)?))
+}
+
+fn public_key(key: &str) -> Result<PublicKey, CipherError> {
+    Ok(PublicKey::from(x25519_public_raw(key)?))
+}
+
+fn check_nonce(nonce: &[u8]) -> Result<(), CipherError> {
+    match nonce.len() {
+        NONCE_LEN => Ok(()),
+        nonce_len => Err(CipherError::InvalidNonceLen(nonce_len)),
+    }
+}
+
+/// nacl.secretbox: XSalsa20-Poly1305, output is tag || ciphertext
+pub fn secretbox_encrypt(data: &[u8], key: &[u8], nonce: &[u8]) -> Result<Vec<u8>, CipherError> {
+    check_nonce(nonce)?;
+    let cipher = XSalsa20Poly1305::new_from_slice(key).or(Err(CipherError::InvalidKeyLenFor(
+        "secretbox".to_owned(),
+        key.len(),
+    )))?;
+
+    cipher
+        .encrypt(Nonce::from_slice(nonce), data)
+        .or(Err(CipherError::Aead))
+}
+
+pub fn secretbox_decrypt(data: &[u8], key: &[u8], nonce: &[u8]) -> Result<Vec<u8>, CipherError> {
+    check_nonce(nonce)?;
+    let cipher = XSalsa20Poly1305::new_from_slice(key).or(Err(CipherError::InvalidKeyLenFor(
+        "secretbox".to_owned(),
+        key.len(),
+    )))?;
+
+    cipher
+        .decrypt(Nonce::from_slice(nonce), data)
+        .or(Err(CipherError::TagMismatch))
+}
+
+/// nacl.box / crypto_box_easy: X25519 + XSalsa20-Poly1305, output is tag || ciphertext
+pub fn box_encrypt(
+    data: &[u8],
+    public_key_str: &str,
+    secret_key_str: &str,
+    nonce: &[u8],
+) -> Result<Vec<u8>, CipherError> {
+    check_nonce(nonce)?;
+    let cipher = SalsaBox::new(&public_key(public_key_str)?, &secret_key(secret_key_str)?);
+
+    cipher
+        .encrypt(Nonce::from_slice(nonce), data)
+        .or(Err(CipherError::Aead))
+}
+
+pub fn box_decrypt(
+    data: &[u8],
+    public_key_str: &str,
+    secret_key_str: &str,
+    nonce: &[u8],
+) -> Result<Vec<u8>, CipherError> {
+    check_nonce(nonce)?;
+    let cipher = SalsaBox::new(&public_key(public_key_str)?, &secret_key(secret_key_str)?);
+
+    cipher
+        .decrypt(Nonce::from_slice(nonce), data)
+        .or(Err(CipherError::TagMismatch))
+}
+
+/// libsodium crypto_box_seal: ephemeral public key || box, nonce is derived from both public keys
+pub fn sealed_box_encrypt(data: &[u8], public_key_str: &str) -> Result<Vec<u8>, CipherError> {
+    public_key(public_key_str)?
+        .seal(&mut StdRng::from_entropy(), data)
+        .or(Err(CipherError::Aead))
+}
+
+pub fn sealed_box_decrypt(data: &[u8], secret_key_str: &str) -> Result<Vec<u8>, CipherError> {
+    secret_key(secret_key_str)?
+        .unseal(data)
+        .or(Err(CipherError::TagMismatch))
+}
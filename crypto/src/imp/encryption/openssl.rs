@@ -0,0 +1,184 @@
+use std::str::FromStr;
+
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+
+use crate::{
+    imp::kdf::{evpkdf, pbkdf2},
+    utils::helpers::or_default,
+};
+
+use super::{cipher::*, error::CipherError};
+
+const SALTED_HEADER: &[u8] = b"Salted__";
+const SALT_LEN: usize = 8;
+
+/// Same as `openssl enc` (and CryptoJS with a passphrase)
+pub const DEFAULT_OPENSSL_CIPHER: &str = "aes-256-cbc";
+const DEFAULT_EVPKDF_HASH: &str = "md5";
+const DEFAULT_PBKDF2_HASH: &str = "sha256";
+const DEFAULT_PBKDF2_ROUNDS: u32 = 10000;
+
+pub enum OpensslKdf {
+    /// EVP_BytesToKey, the only one CryptoJS and old OpenSSL versions know
+    Evpkdf,
+    /// `openssl enc -pbkdf2`
+    Pbkdf2,
+}
+
+impl FromStr for OpensslKdf {
+    type Err = CipherError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let kdf = match s {
+            "evpkdf" | "" => OpensslKdf::Evpkdf,
+            "pbkdf2" => OpensslKdf::Pbkdf2,
+            _ => return Err(CipherError::InvalidKdf(s.to_owned())),
+        };
+        Ok(kdf)
+    }
+}
+
+/// Cipher names as in `openssl enc -list`: aes-256-cbc, des-ede3-cbc, bf-cbc, camellia-128-ctr...
+struct OpensslCipher {
+    cipher: BlockCipher,
+    key_len: usize,
+    iv_len: usize,
+    mode: Mode,
+    padding: Padding,
+}
+
+impl FromStr for OpensslCipher {
+    type Err = CipherError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = match s {
+            "" => DEFAULT_OPENSSL_CIPHER,
+            _ => s,
+        };
+        let (name, mode) = s
+            .rsplit_once('-')
+            .ok_or_else(|| CipherError::InvalidOpensslCipher(s.to_owned()))?;
+
+        let (cipher_name, key_len) = match name {
+            "aes-128" => ("aes", 16),
+            "aes-192" => ("aes", 24),
+            "aes-256" => ("aes", 32),
+            "camellia-128" => ("camellia", 16),
+            "camellia-192" => ("camellia", 24),
+            "camellia-256" => ("camellia", 32),
+            "des" => ("des", 8),
+            "des-ede" => ("3des", 16),
+            "des-ede3" => ("3des", 24),
+            "bf" => ("blowfish", 16),
+            "sm4" => ("sm4", 16),
+            _ => return Err(CipherError::InvalidOpensslCipher(s.to_owned())),
+        };
+
+        let cipher = BlockCipher::new_from_name(cipher_name, key_len)?;
+        let mode = Mode::from_str(mode)?;
+        let (iv_len, padding) = match mode {
+            Mode::Ecb => (0, Padding::Pkcs7),
+            Mode::Cbc | Mode::Pcbc => (cipher.block_size(), Padding::Pkcs7),
+            _ => (cipher.block_size(), Padding::NoPadding),
+        };
+
+        Ok(OpensslCipher {
+            cipher,
+            key_len,
+            iv_len,
+            mode,
+            padding,
+        })
+    }
+}
+
+impl OpensslCipher {
+    /// Returns (key, iv)
+    fn derive_key(
+        &self,
+        passphrase: &[u8],
+        salt: &[u8],
+        kdf: &OpensslKdf,
+        hash_type: &str,
+        rounds: Option<u32>,
+    ) -> Result<(Vec<u8>, Vec<u8>), CipherError> {
+        let length = self.key_len + self.iv_len;
+        let mut key = match kdf {
+            OpensslKdf::Evpkdf => evpkdf(
+                passphrase,
+                salt,
+                rounds.unwrap_or(1) as usize,
+                length,
+                or_default(hash_type, DEFAULT_EVPKDF_HASH),
+            )?,
+            OpensslKdf::Pbkdf2 => pbkdf2(
+                passphrase,
+                salt,
+                rounds.unwrap_or(DEFAULT_PBKDF2_ROUNDS),
+                length,
+                or_default(hash_type, DEFAULT_PBKDF2_HASH),
+            )?,
+        };
+
+        let iv = key.split_off(self.key_len);
+        Ok((key, iv))
+    }
+
+    fn into_cipher(self) -> Cipher {
+        Cipher::new(self.cipher, self.mode, self.padding)
+    }
+}
+
+/// Returns `Salted__` || salt || ciphertext. Random salt is used if salt is empty.
+/// Empty hash_type means md5 for evpkdf and sha256 for pbkdf2,
+/// rounds are 1 for evpkdf and 10000 for pbkdf2 if not set
+pub fn openssl_encrypt(
+    data: &mut [u8],
+    passphrase: &[u8],
+    cipher: &str,
+    kdf: &str,
+    hash_type: &str,
+    rounds: Option<u32>,
+    salt: &[u8],
+) -> Result<Vec<u8>, CipherError> {
+    let cipher = OpensslCipher::from_str(cipher)?;
+    let kdf = OpensslKdf::from_str(kdf)?;
+
+    let salt = match salt.len() {
+        0 => {
+            let mut salt = [0u8; SALT_LEN];
+            StdRng::from_entropy().fill_bytes(&mut salt);
+            salt
+        }
+        SALT_LEN => salt.try_into().unwrap(),
+        salt_len => return Err(CipherError::InvalidSaltLen(salt_len)),
+    };
+
+    let (key, iv) = cipher.derive_key(passphrase, &salt, &kdf, hash_type, rounds)?;
+    let encrypted = cipher.into_cipher().encrypt(&key, &iv, data)?;
+
+    Ok([SALTED_HEADER, &salt, &encrypted].concat())
+}
+
+/// Accepts the blob produced by [`openssl_encrypt`], `openssl enc` or CryptoJS
+pub fn openssl_decrypt(
+    data: &mut [u8],
+    passphrase: &[u8],
+    cipher: &str,
+    kdf: &str,
+    hash_type: &str,
+    rounds: Option<u32>,
+) -> Result<Vec<u8>, CipherError> {
+    let cipher = OpensslCipher::from_str(cipher)?;
+    let kdf = OpensslKdf::from_str(kdf)?;
+
+    if data.len() < SALTED_HEADER.len() + SALT_LEN || !data.starts_with(SALTED_HEADER) {
+        return Err(CipherError::NoSaltedHeader);
+    }
+    let (salt, encrypted) = data[SALTED_HEADER.len()..].split_at_mut(SALT_LEN);
+
+    let (key, iv) = cipher.derive_key(passphrase, salt, &kdf, hash_type, rounds)?;
+    let decrypted = cipher.into_cipher().decrypt(&key, &iv, encrypted)?;
+
+    Ok(decrypted.into_owned())
+}
@@ -0,0 +1,7 @@
+use arc4::Arc4;
+
+/// Encryption and decryption are the same operation
+pub fn rc4(data: &mut [u8], key: &[u8]) {
+    let mut cipher = Arc4::with_key(key);
+    cipher.encrypt(data);
+}
@@ -3,9 +3,10 @@ use md5::Md5;
 use rand::{rngs::StdRng, SeedableRng};
 use ripemd::{Ripemd160, Ripemd256, Ripemd320};
 use rsa::{
-    pkcs1::{DecodeRsaPrivateKey, DecodeRsaPublicKey},
-    pkcs8::{DecodePrivateKey, DecodePublicKey, EncodePublicKey, LineEnding},
-    BigUint, Oaep, Pkcs1v15Encrypt, Pkcs1v15Sign, Pss, PublicKey, RsaPrivateKey, RsaPublicKey,
+    errors::Error as _RsaError,
+    pkcs8::{EncodePublicKey, LineEnding},
+    BigUint, Oaep, Pkcs1v15Encrypt, Pkcs1v15Sign, Pss, PublicKey, PublicKeyParts, RsaPrivateKey,
+    RsaPublicKey,
 };
 use sha1::Sha1;
 use sha2::*;
@@ -13,7 +14,13 @@ use sha3::*;
 
 use crate::imp::hashing::{error::HashError, make_hash};
 
-use super::error::RsaError;
+use super::{error::RsaError, rsa_keys::RsaKey};
+
+enum EncryptionScheme {
+    Pkcs1,
+    Oaep(Oaep),
+    NoPadding,
+}
 
 enum SignatureScheme {
     Pkcs1(Pkcs1v15Sign),
@@ -30,73 +37,132 @@ pub fn modulus_to_pem(n: &[u8], e: &[u8]) -> Result<String, RsaError> {
 }
 
 pub fn rsa_encrypt(data: &[u8], key: &str, hash_type: &str) -> Result<Vec<u8>, RsaError> {
-    let pub_key = if key.contains("--BEGIN RSA") {
-        match RsaPublicKey::from_pkcs1_pem(key) {
-            Ok(pub_key) => pub_key,
-            Err(_) => return Err(RsaError::InvalidPublicKey),
-        }
-    } else {
-        match RsaPublicKey::from_public_key_pem(key) {
-            Ok(pub_key) => pub_key,
-            Err(_) => return Err(RsaError::InvalidPublicKey),
-        }
-    };
+    let pub_key = load_public_key(key)?;
 
     let mut rng = StdRng::from_entropy();
-    let encrypted = match oaep_padding_from_str(hash_type)? {
-        Some(oaep) => pub_key.encrypt(&mut rng, oaep, data),
-        None => pub_key.encrypt(&mut rng, Pkcs1v15Encrypt::default(), data),
-    }?;
+    let encrypted = match encryption_padding_from_str(hash_type)? {
+        EncryptionScheme::Pkcs1 => pub_key.encrypt(&mut rng, Pkcs1v15Encrypt, data)?,
+        EncryptionScheme::Oaep(oaep) => pub_key.encrypt(&mut rng, oaep, data)?,
+        EncryptionScheme::NoPadding => raw_encrypt(&pub_key, data)?,
+    };
     Ok(encrypted)
 }
 
 pub fn rsa_decrypt(data: &[u8], key: &str, hash_type: &str) -> Result<Vec<u8>, RsaError> {
-    let priv_key = if key.contains("--BEGIN RSA") {
-        match RsaPrivateKey::from_pkcs1_pem(key) {
-            Ok(priv_key) => priv_key,
-            Err(_) => return Err(RsaError::InvalidPrivateKey),
-        }
-    } else {
-        match RsaPrivateKey::from_pkcs8_pem(key) {
-            Ok(priv_key) => priv_key,
-            Err(_) => return Err(RsaError::InvalidPrivateKey),
-        }
-    };
+    let priv_key = load_private_key(key)?;
 
-    let decrypted = match oaep_padding_from_str(hash_type)? {
-        Some(oaep) => priv_key.decrypt(oaep, data),
-        None => priv_key.decrypt(Pkcs1v15Encrypt::default(), data),
-    }?;
+    let decrypted = match encryption_padding_from_str(hash_type)? {
+        EncryptionScheme::Pkcs1 => priv_key.decrypt(Pkcs1v15Encrypt, data)?,
+        EncryptionScheme::Oaep(oaep) => priv_key.decrypt(oaep, data)?,
+        EncryptionScheme::NoPadding => raw_decrypt(&priv_key, data)?,
+    };
 
     Ok(decrypted)
 }
 
 pub fn rsa_sign(data: &[u8], key: &str, hash_type: &str, mode: &str) -> Result<Vec<u8>, RsaError> {
-    let priv_key = if key.contains("--BEGIN RSA") {
-        match RsaPrivateKey::from_pkcs1_pem(key) {
-            Ok(priv_key) => priv_key,
-            Err(_) => return Err(RsaError::InvalidPrivateKey),
-        }
-    } else {
-        match RsaPrivateKey::from_pkcs8_pem(key) {
-            Ok(priv_key) => priv_key,
-            Err(_) => return Err(RsaError::InvalidPrivateKey),
-        }
-    };
+    let priv_key = load_private_key(key)?;
 
     let hashed_data = make_hash(data, hash_type)?;
 
+    _sign(&priv_key, &hashed_data, hash_type, mode)
+}
+
+/// Same as rsa_sign, but data is already hashed with hash_type
+pub fn rsa_sign_prehashed(
+    hashed_data: &[u8],
+    key: &str,
+    hash_type: &str,
+    mode: &str,
+) -> Result<Vec<u8>, RsaError> {
+    let priv_key = load_private_key(key)?;
+
+    _sign(&priv_key, hashed_data, hash_type, mode)
+}
+
+/// Returns false if the signature doesn't match, errors are returned only for bad input
+pub fn rsa_verify(
+    data: &[u8],
+    signature: &[u8],
+    key: &str,
+    hash_type: &str,
+    mode: &str,
+) -> Result<bool, RsaError> {
+    let pub_key = load_public_key(key)?;
+
+    let hashed_data = make_hash(data, hash_type)?;
+
+    let verified = match sign_padding_from_str(hash_type, mode)? {
+        SignatureScheme::Pkcs1(padding) => pub_key.verify(padding, &hashed_data, signature),
+        SignatureScheme::Pss(padding) => pub_key.verify(padding, &hashed_data, signature),
+    };
+
+    Ok(verified.is_ok())
+}
+
+fn _sign(
+    priv_key: &RsaPrivateKey,
+    hashed_data: &[u8],
+    hash_type: &str,
+    mode: &str,
+) -> Result<Vec<u8>, RsaError> {
+    let mut rng = StdRng::from_entropy();
     let signed = match sign_padding_from_str(hash_type, mode)? {
-        SignatureScheme::Pkcs1(padding) => priv_key.sign(padding, &hashed_data)?,
-        SignatureScheme::Pss(padding) => priv_key.sign(padding, &hashed_data)?,
+        SignatureScheme::Pkcs1(padding) => priv_key.sign(padding, hashed_data)?,
+        SignatureScheme::Pss(padding) => priv_key.sign_with_rng(&mut rng, padding, hashed_data)?,
     };
 
     Ok(signed)
 }
 
-fn oaep_padding_from_str(hash_type: &str) -> Result<Option<Oaep>, RsaError> {
-    if hash_type.is_empty() {
-        return Ok(None);
+/// Textbook RSA: c = m^e mod n, output is left padded to the key size
+fn raw_encrypt(pub_key: &RsaPublicKey, data: &[u8]) -> Result<Vec<u8>, RsaError> {
+    let m = BigUint::from_bytes_be(data);
+    if data.len() > pub_key.size() || &m >= pub_key.n() {
+        return Err(_RsaError::MessageTooLong.into());
+    }
+
+    let c = m.modpow(pub_key.e(), pub_key.n());
+    Ok(left_pad(&c.to_bytes_be(), pub_key.size()))
+}
+
+/// Textbook RSA: m = c^d mod n, output is left padded to the key size
+fn raw_decrypt(priv_key: &RsaPrivateKey, data: &[u8]) -> Result<Vec<u8>, RsaError> {
+    let c = BigUint::from_bytes_be(data);
+    if data.len() > priv_key.size() || &c >= priv_key.n() {
+        return Err(_RsaError::Decryption.into());
+    }
+
+    let m = c.modpow(priv_key.d(), priv_key.n());
+    Ok(left_pad(&m.to_bytes_be(), priv_key.size()))
+}
+
+fn left_pad(data: &[u8], size: usize) -> Vec<u8> {
+    let mut padded = vec![0u8; size - data.len()];
+    padded.extend_from_slice(data);
+    padded
+}
+
+/// Private key is accepted too, its public part is used
+fn load_public_key(key: &str) -> Result<RsaPublicKey, RsaError> {
+    match RsaKey::parse(key) {
+        Ok(key) => Ok(key.to_public_key()),
+        Err(_) => Err(RsaError::InvalidPublicKey),
+    }
+}
+
+fn load_private_key(key: &str) -> Result<RsaPrivateKey, RsaError> {
+    match RsaKey::parse(key) {
+        Ok(RsaKey::Private(priv_key)) => Ok(*priv_key),
+        _ => Err(RsaError::InvalidPrivateKey),
+    }
+}
+
+fn encryption_padding_from_str(hash_type: &str) -> Result<EncryptionScheme, RsaError> {
+    match hash_type {
+        "" => return Ok(EncryptionScheme::Pkcs1),
+        "nopadding" => return Ok(EncryptionScheme::NoPadding),
+        _ => (),
     }
 
     let padding = match hash_type {
@@ -120,7 +186,7 @@ fn oaep_padding_from_str(hash_type: &str) -> Result<Option<Oaep>, RsaError> {
         "ripemd320" => Oaep::new::<Ripemd320>(),
         _ => return Err(HashError::InvalidHashType(hash_type.to_owned()).into()),
     };
-    Ok(Some(padding))
+    Ok(EncryptionScheme::Oaep(padding))
 }
 
 fn sign_padding_from_str(hash_type: &str, mode: &str) -> Result<SignatureScheme, RsaError> {
@@ -145,9 +211,8 @@ fn sign_padding_from_str(hash_type: &str, mode: &str) -> Result<SignatureScheme,
 
             SignatureScheme::Pkcs1(padding)
         }
-        "pss" => {
-            let _rng = StdRng::from_entropy();
-            let padding = match hash_type {
+        "pss" | "pss-hash" => {
+            let mut padding = match hash_type {
                 "md5" => Pss::new::<Md5>(),
                 "md4" => Pss::new::<Md4>(),
                 "sha1" => Pss::new::<Sha1>(),
@@ -168,6 +233,10 @@ fn sign_padding_from_str(hash_type: &str, mode: &str) -> Result<SignatureScheme,
                 "ripemd320" => Pss::new::<Ripemd320>(),
                 _ => return Err(HashError::InvalidHashType(hash_type.to_owned()).into()),
             };
+            // salt as long as the hash (RFC 7518, most libraries) instead of the maximum possible
+            if mode == "pss-hash" {
+                padding.salt_len = Some(padding.digest.output_size());
+            }
 
             SignatureScheme::Pss(padding)
         }
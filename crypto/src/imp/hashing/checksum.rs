@@ -0,0 +1,124 @@
+use std::io::Cursor;
+
+use adler::Adler32;
+use crc::{
+    Crc, Digest, CRC_16_ARC, CRC_16_IBM_3740, CRC_16_MODBUS, CRC_16_XMODEM, CRC_32_ISCSI,
+    CRC_32_ISO_HDLC, CRC_64_ECMA_182, CRC_64_XZ,
+};
+use xxhash_rust::{xxh3::Xxh3Default, xxh32::Xxh32, xxh64::Xxh64};
+
+const FNV32_OFFSET: u32 = 0x811c9dc5;
+const FNV32_PRIME: u32 = 0x01000193;
+const FNV64_OFFSET: u64 = 0xcbf29ce484222325;
+const FNV64_PRIME: u64 = 0x00000100000001b3;
+
+static CRC16: Crc<u16> = Crc::<u16>::new(&CRC_16_ARC);
+static CRC16_CCITT: Crc<u16> = Crc::<u16>::new(&CRC_16_IBM_3740);
+static CRC16_XMODEM: Crc<u16> = Crc::<u16>::new(&CRC_16_XMODEM);
+static CRC16_MODBUS: Crc<u16> = Crc::<u16>::new(&CRC_16_MODBUS);
+static CRC32: Crc<u32> = Crc::<u32>::new(&CRC_32_ISO_HDLC);
+static CRC32C: Crc<u32> = Crc::<u32>::new(&CRC_32_ISCSI);
+static CRC64: Crc<u64> = Crc::<u64>::new(&CRC_64_XZ);
+static CRC64_ECMA: Crc<u64> = Crc::<u64>::new(&CRC_64_ECMA_182);
+
+/// Non cryptographic checksums fed in parts, so hash_init sessions can use them too
+pub enum Checksum {
+    Crc16(Digest<'static, u16>),
+    Crc32(Digest<'static, u32>),
+    Crc64(Digest<'static, u64>),
+    Adler32(Adler32),
+    Xxh32(Xxh32),
+    Xxh64(Xxh64),
+    Xxh3(Box<Xxh3Default>),
+    Xxh128(Box<Xxh3Default>),
+    Fnv1a32(u32),
+    Fnv1a64(u64),
+    /// murmur3 has no incremental api, the data is kept until the end
+    Murmur3_32(Vec<u8>),
+    Murmur3_128(Vec<u8>),
+}
+
+impl Checksum {
+    /// Returns None if checksum_type is not a checksum
+    pub fn new(checksum_type: &str) -> Option<Self> {
+        let checksum = match checksum_type {
+            "crc16" => Checksum::Crc16(CRC16.digest()),
+            "crc16-ccitt" => Checksum::Crc16(CRC16_CCITT.digest()),
+            "crc16-xmodem" => Checksum::Crc16(CRC16_XMODEM.digest()),
+            "crc16-modbus" => Checksum::Crc16(CRC16_MODBUS.digest()),
+            "crc32" => Checksum::Crc32(CRC32.digest()),
+            "crc32c" => Checksum::Crc32(CRC32C.digest()),
+            "crc64" => Checksum::Crc64(CRC64.digest()),
+            "crc64-ecma" => Checksum::Crc64(CRC64_ECMA.digest()),
+            "adler32" => Checksum::Adler32(Adler32::new()),
+            "xxh32" => Checksum::Xxh32(Xxh32::new(0)),
+            "xxh64" => Checksum::Xxh64(Xxh64::new(0)),
+            "xxh3" => Checksum::Xxh3(Box::new(Xxh3Default::new())),
+            "xxh128" => Checksum::Xxh128(Box::new(Xxh3Default::new())),
+            "murmur3-32" => Checksum::Murmur3_32(Vec::new()),
+            "murmur3-128" => Checksum::Murmur3_128(Vec::new()),
+            "fnv1a-32" => Checksum::Fnv1a32(FNV32_OFFSET),
+            "fnv1a-64" => Checksum::Fnv1a64(FNV64_OFFSET),
+            _ => return None,
+        };
+        Some(checksum)
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        match self {
+            Checksum::Crc16(digest) => digest.update(data),
+            Checksum::Crc32(digest) => digest.update(data),
+            Checksum::Crc64(digest) => digest.update(data),
+            Checksum::Adler32(adler) => adler.write_slice(data),
+            Checksum::Xxh32(xxh) => xxh.update(data),
+            Checksum::Xxh64(xxh) => xxh.update(data),
+            Checksum::Xxh3(xxh) | Checksum::Xxh128(xxh) => xxh.update(data),
+            Checksum::Fnv1a32(hash) => {
+                *hash = data.iter().fold(*hash, |hash, &byte| {
+                    (hash ^ byte as u32).wrapping_mul(FNV32_PRIME)
+                })
+            }
+            Checksum::Fnv1a64(hash) => {
+                *hash = data.iter().fold(*hash, |hash, &byte| {
+                    (hash ^ byte as u64).wrapping_mul(FNV64_PRIME)
+                })
+            }
+            Checksum::Murmur3_32(buffer) | Checksum::Murmur3_128(buffer) => {
+                buffer.extend_from_slice(data)
+            }
+        }
+    }
+
+    /// Big endian, same as the usual hex representation
+    pub fn finalize(self) -> Vec<u8> {
+        match self {
+            Checksum::Crc16(digest) => digest.finalize().to_be_bytes().to_vec(),
+            Checksum::Crc32(digest) => digest.finalize().to_be_bytes().to_vec(),
+            Checksum::Crc64(digest) => digest.finalize().to_be_bytes().to_vec(),
+            Checksum::Adler32(adler) => adler.checksum().to_be_bytes().to_vec(),
+            Checksum::Xxh32(xxh) => xxh.digest().to_be_bytes().to_vec(),
+            Checksum::Xxh64(xxh) => xxh.digest().to_be_bytes().to_vec(),
+            Checksum::Xxh3(xxh) => xxh.digest().to_be_bytes().to_vec(),
+            Checksum::Xxh128(xxh) => xxh.digest128().to_be_bytes().to_vec(),
+            Checksum::Fnv1a32(hash) => hash.to_be_bytes().to_vec(),
+            Checksum::Fnv1a64(hash) => hash.to_be_bytes().to_vec(),
+            // reading from memory can't fail
+            Checksum::Murmur3_32(buffer) => murmur3::murmur3_32(&mut Cursor::new(buffer), 0)
+                .unwrap_or_default()
+                .to_be_bytes()
+                .to_vec(),
+            Checksum::Murmur3_128(buffer) => murmur3::murmur3_x64_128(&mut Cursor::new(buffer), 0)
+                .unwrap_or_default()
+                .to_be_bytes()
+                .to_vec(),
+        }
+    }
+}
+
+/// Non cryptographic checksums, result is big endian (same as the usual hex representation).
+/// Returns None if checksum_type is not a checksum
+pub fn make_checksum(data: &[u8], checksum_type: &str) -> Option<Vec<u8>> {
+    let mut checksum = Checksum::new(checksum_type)?;
+    checksum.update(data);
+    Some(checksum.finalize())
+}
@@ -7,4 +7,12 @@ pub enum HashError {
     InvalidHashType(String),
     #[error("invalid hmac key provided")]
     BadHmacKey(#[from] InvalidLength),
+    #[error("hash session not found or expired: {0}")]
+    SessionNotFound(String),
+    #[error("invalid key length for {0}: {1}")]
+    InvalidMacKeyLen(String, usize),
+    #[error("invalid nonce length: {0}. Only 12 accepted")]
+    InvalidNonceLen(usize),
+    #[error("output length {0} is too long, max is {1}")]
+    OutputTooLong(usize, usize),
 }
@@ -1,9 +1,10 @@
-use digest::Digest;
+use blake2::{Blake2bVar, Blake2sVar};
+use digest::{Digest, Update, VariableOutput};
 use sha2::*;
 
-use crate::{base64, call_with_hash_generic};
+use crate::call_with_hash_generic;
 
-use super::error::HashError;
+use super::{checksum::make_checksum, error::HashError};
 
 fn _hash<D: Digest>(data: &[u8]) -> Vec<u8> {
     let mut hasher = <D>::new();
@@ -11,8 +12,63 @@ fn _hash<D: Digest>(data: &[u8]) -> Vec<u8> {
     hasher.finalize().to_vec()
 }
 
+/// BLAKE2 with the output length in bits after the name: blake2b-8 ..= blake2b-512,
+/// blake2s-8 ..= blake2s-256. Without a length it is the longest one
+pub enum Blake2 {
+    B(Blake2bVar),
+    S(Blake2sVar),
+}
+
+impl Blake2 {
+    /// Returns None if hash_type is not BLAKE2
+    pub fn new(hash_type: &str) -> Option<Result<Self, HashError>> {
+        let (variant, bits) = match hash_type.split_once('-') {
+            Some((variant, bits)) => (variant, Some(bits.parse::<usize>().ok()?)),
+            None => (hash_type, None),
+        };
+        let invalid = || HashError::InvalidHashType(hash_type.to_owned());
+
+        let blake2 = match (variant, bits) {
+            ("blake2b", None) => Blake2bVar::new(64).map(Blake2::B),
+            ("blake2s", None) => Blake2sVar::new(32).map(Blake2::S),
+            ("blake2b", Some(bits @ 8..=512)) if bits % 8 == 0 => {
+                Blake2bVar::new(bits / 8).map(Blake2::B)
+            }
+            ("blake2s", Some(bits @ 8..=256)) if bits % 8 == 0 => {
+                Blake2sVar::new(bits / 8).map(Blake2::S)
+            }
+            ("blake2b" | "blake2s", Some(_)) => return Some(Err(invalid())),
+            _ => return None,
+        };
+        Some(blake2.map_err(|_| invalid()))
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        match self {
+            Blake2::B(hasher) => hasher.update(data),
+            Blake2::S(hasher) => hasher.update(data),
+        }
+    }
+
+    pub fn finalize(self) -> Vec<u8> {
+        match self {
+            Blake2::B(hasher) => hasher.finalize_boxed().into_vec(),
+            Blake2::S(hasher) => hasher.finalize_boxed().into_vec(),
+        }
+    }
+}
+
 pub fn make_hash(data: impl AsRef<[u8]>, hash_type: &str) -> Result<Vec<u8>, HashError> {
     let data = data.as_ref();
+    if let Some(checksum) = make_checksum(data, hash_type) {
+        return Ok(checksum);
+    }
+    if let Some(blake2) = Blake2::new(hash_type) {
+        let mut blake2 = blake2?;
+        blake2.update(data);
+        return Ok(blake2.finalize());
+    }
+
     let hashed = call_with_hash_generic!(_hash(data), hash_type, HashError::InvalidHashType);
     Ok(hashed)
 }
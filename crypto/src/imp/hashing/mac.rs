@@ -0,0 +1,97 @@
+use aes::{Aes128, Aes192, Aes256};
+use aes_gcm::{
+    aead::{consts::U12, AeadInPlace},
+    AesGcm, Nonce,
+};
+use cmac::Cmac;
+use des::{TdesEde2, TdesEde3};
+use digest::{KeyInit, Mac};
+use poly1305::Poly1305;
+use siphasher::{sip, sip128};
+
+use super::error::HashError;
+
+fn _mac<M: Mac + KeyInit>(data: &[u8], key: &[u8]) -> Result<Vec<u8>, HashError> {
+    let mut mac = <M as Mac>::new_from_slice(key)?;
+    mac.update(data);
+    Ok(mac.finalize().into_bytes().to_vec())
+}
+
+/// GMAC is GCM with empty plaintext, data goes to AAD
+fn _gmac<C: KeyInit + AeadInPlace>(
+    data: &[u8],
+    key: &[u8],
+    nonce: &[u8],
+) -> Result<Vec<u8>, HashError> {
+    let cipher = C::new_from_slice(key)?;
+    let tag = cipher
+        .encrypt_in_place_detached(Nonce::from_slice(nonce), data, &mut [])
+        .or(Err(HashError::InvalidNonceLen(nonce.len())))?;
+    Ok(tag.to_vec())
+}
+
+/// mac_type: cmac (AES, 16/24/32 bytes key), cmac-3des (16/24 bytes key), gmac (AES key, 12 bytes nonce),
+/// poly1305 (32 bytes one-time key), siphash (siphash-2-4), siphash-1-3, siphash128 (16 bytes key).
+/// nonce is used only by gmac. SipHash output is little endian as in the reference implementation
+pub fn make_mac(
+    data: &[u8],
+    key: &[u8],
+    mac_type: &str,
+    nonce: &[u8],
+) -> Result<Vec<u8>, HashError> {
+    let invalid_key = || HashError::InvalidMacKeyLen(mac_type.to_owned(), key.len());
+
+    let mac = match mac_type {
+        "cmac" | "aes-cmac" | "cmac-aes" => match key.len() {
+            16 => _mac::<Cmac<Aes128>>(data, key)?,
+            24 => _mac::<Cmac<Aes192>>(data, key)?,
+            32 => _mac::<Cmac<Aes256>>(data, key)?,
+            _ => return Err(invalid_key()),
+        },
+        "cmac-3des" | "3des-cmac" | "cmac-tdes" => match key.len() {
+            16 => _mac::<Cmac<TdesEde2>>(data, key)?,
+            24 => _mac::<Cmac<TdesEde3>>(data, key)?,
+            _ => return Err(invalid_key()),
+        },
+        "gmac" | "aes-gmac" => {
+            if nonce.len() != 12 {
+                return Err(HashError::InvalidNonceLen(nonce.len()));
+            }
+            match key.len() {
+                16 => _gmac::<AesGcm<Aes128, U12>>(data, key, nonce)?,
+                24 => _gmac::<AesGcm<Aes192, U12>>(data, key, nonce)?,
+                32 => _gmac::<AesGcm<Aes256, U12>>(data, key, nonce)?,
+                _ => return Err(invalid_key()),
+            }
+        }
+        "poly1305" => {
+            let poly1305 = Poly1305::new_from_slice(key).or(Err(invalid_key()))?;
+            poly1305.compute_unpadded(data).to_vec()
+        }
+        "siphash" | "siphash-2-4" | "siphash-1-3" | "siphash128" | "siphash128-2-4"
+        | "siphash128-1-3" => {
+            let key: &[u8; 16] = key.try_into().or(Err(invalid_key()))?;
+            match mac_type {
+                "siphash-1-3" => sip::SipHasher13::new_with_key(key)
+                    .hash(data)
+                    .to_le_bytes()
+                    .to_vec(),
+                "siphash128" | "siphash128-2-4" => sip128::SipHasher24::new_with_key(key)
+                    .hash(data)
+                    .as_bytes()
+                    .to_vec(),
+                "siphash128-1-3" => sip128::SipHasher13::new_with_key(key)
+                    .hash(data)
+                    .as_bytes()
+                    .to_vec(),
+                _ => sip::SipHasher24::new_with_key(key)
+                    .hash(data)
+                    .to_le_bytes()
+                    .to_vec(),
+            }
+        }
+        _ => return Err(HashError::InvalidHashType(mac_type.to_owned())),
+    };
+
+    Ok(mac)
+}
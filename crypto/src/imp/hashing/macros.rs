@@ -20,6 +20,15 @@ macro_rules! call_with_hash_generic {
         ::hmac::Hmac<$hash_type>
     };
 
+    // Hashes with lazy buffering (BLAKE2, BLAKE3) only work with SimpleHmac
+    (@call_simple, $top_func:ident$(::$tail_func:ident)* ($($arg: expr),*), $hash_type:path) => {
+        $top_func$(::$tail_func)*::<$hash_type>($($arg),*)
+    };
+
+    (@call_simple, $top_func:ident$(::$tail_func:ident)* ($($arg: expr),*), $hash_type:path, $_:tt) => {
+        $top_func$(::$tail_func)*::<::hmac::SimpleHmac<$hash_type>>($($arg),*)
+    };
+
     (@match_hash_str, $top_func:ident$(::$tail_func:ident)* ($($arg: expr),*), $hash_type:expr, $top_err:ident$(::$tail_err:ident)* $(, $use_hmac:tt)?) => {
         match $hash_type {
             "md5" => call_with_hash_generic!(@call, $top_func$(::$tail_func)* ($($arg),*), ::md5::Md5 $(, $use_hmac)?),
@@ -29,6 +38,8 @@ macro_rules! call_with_hash_generic {
             "sha256" => call_with_hash_generic!(@call, $top_func$(::$tail_func)* ($($arg),*), ::sha2::Sha256 $(, $use_hmac)?),
             "sha384" => call_with_hash_generic!(@call, $top_func$(::$tail_func)* ($($arg),*), ::sha2::Sha384 $(, $use_hmac)?),
             "sha512" => call_with_hash_generic!(@call, $top_func$(::$tail_func)* ($($arg),*), ::sha2::Sha512 $(, $use_hmac)?),
+            "sha512/224" => call_with_hash_generic!(@call, $top_func$(::$tail_func)* ($($arg),*), ::sha2::Sha512_224 $(, $use_hmac)?),
+            "sha512/256" => call_with_hash_generic!(@call, $top_func$(::$tail_func)* ($($arg),*), ::sha2::Sha512_256 $(, $use_hmac)?),
             "sha3-224" => call_with_hash_generic!(@call, $top_func$(::$tail_func)* ($($arg),*), ::sha3::Sha3_224 $(, $use_hmac)?),
             "sha3-256" => call_with_hash_generic!(@call, $top_func$(::$tail_func)* ($($arg),*), ::sha3::Sha3_256 $(, $use_hmac)?),
             "sha3-384" => call_with_hash_generic!(@call, $top_func$(::$tail_func)* ($($arg),*), ::sha3::Sha3_384 $(, $use_hmac)?),
@@ -41,6 +52,23 @@ macro_rules! call_with_hash_generic {
             "ripemd160" => call_with_hash_generic!(@call, $top_func$(::$tail_func)* ($($arg),*), ::ripemd::Ripemd160 $(, $use_hmac)?),
             "ripemd256" => call_with_hash_generic!(@call, $top_func$(::$tail_func)* ($($arg),*), ::ripemd::Ripemd256 $(, $use_hmac)?),
             "ripemd320" => call_with_hash_generic!(@call, $top_func$(::$tail_func)* ($($arg),*), ::ripemd::Ripemd320 $(, $use_hmac)?),
+            "blake2b" | "blake2b-512" => call_with_hash_generic!(@call_simple, $top_func$(::$tail_func)* ($($arg),*), ::blake2::Blake2b512 $(, $use_hmac)?),
+            "blake2b-384" => call_with_hash_generic!(@call_simple, $top_func$(::$tail_func)* ($($arg),*), ::blake2::Blake2b<::digest::consts::U48> $(, $use_hmac)?),
+            "blake2b-224" => call_with_hash_generic!(@call_simple, $top_func$(::$tail_func)* ($($arg),*), ::blake2::Blake2b<::digest::consts::U28> $(, $use_hmac)?),
+            "blake2b-256" => call_with_hash_generic!(@call_simple, $top_func$(::$tail_func)* ($($arg),*), ::blake2::Blake2b<::digest::consts::U32> $(, $use_hmac)?),
+            "blake2b-160" => call_with_hash_generic!(@call_simple, $top_func$(::$tail_func)* ($($arg),*), ::blake2::Blake2b<::digest::consts::U20> $(, $use_hmac)?),
+            "blake2b-128" => call_with_hash_generic!(@call_simple, $top_func$(::$tail_func)* ($($arg),*), ::blake2::Blake2b<::digest::consts::U16> $(, $use_hmac)?),
+            "blake2s" | "blake2s-256" => call_with_hash_generic!(@call_simple, $top_func$(::$tail_func)* ($($arg),*), ::blake2::Blake2s256 $(, $use_hmac)?),
+            "blake2s-224" => call_with_hash_generic!(@call_simple, $top_func$(::$tail_func)* ($($arg),*), ::blake2::Blake2s<::digest::consts::U28> $(, $use_hmac)?),
+            "blake2s-160" => call_with_hash_generic!(@call_simple, $top_func$(::$tail_func)* ($($arg),*), ::blake2::Blake2s<::digest::consts::U20> $(, $use_hmac)?),
+            "blake2s-128" => call_with_hash_generic!(@call_simple, $top_func$(::$tail_func)* ($($arg),*), ::blake2::Blake2s<::digest::consts::U16> $(, $use_hmac)?),
+            "whirlpool" => call_with_hash_generic!(@call, $top_func$(::$tail_func)* ($($arg),*), ::whirlpool::Whirlpool $(, $use_hmac)?),
+            "streebog256" => call_with_hash_generic!(@call, $top_func$(::$tail_func)* ($($arg),*), ::streebog::Streebog256 $(, $use_hmac)?),
+            "streebog512" => call_with_hash_generic!(@call, $top_func$(::$tail_func)* ($($arg),*), ::streebog::Streebog512 $(, $use_hmac)?),
+            "sm3" => call_with_hash_generic!(@call, $top_func$(::$tail_func)* ($($arg),*), ::sm3::Sm3 $(, $use_hmac)?),
+            "tiger" => call_with_hash_generic!(@call, $top_func$(::$tail_func)* ($($arg),*), ::tiger::Tiger $(, $use_hmac)?),
+            "tiger2" => call_with_hash_generic!(@call, $top_func$(::$tail_func)* ($($arg),*), ::tiger::Tiger2 $(, $use_hmac)?),
+            "blake3" => call_with_hash_generic!(@call_simple, $top_func$(::$tail_func)* ($($arg),*), ::blake3::Hasher $(, $use_hmac)?),
             _ => return ::core::result::Result::Err($top_err$(::$tail_err)*($hash_type.to_owned())),
         }
     };
@@ -1,6 +1,13 @@
+mod checksum;
 pub mod error;
 mod hash;
 mod hmac;
+mod mac;
 mod macros;
+mod stream;
+mod xof;
 pub use self::hmac::make_hmac;
 pub use hash::make_hash;
+pub use mac::make_mac;
+pub use stream::*;
+pub use xof::make_hash_xof;
@@ -0,0 +1,148 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex, PoisonError, RwLock},
+    time::{Duration, Instant},
+};
+
+use digest::{Digest, DynDigest, KeyInit};
+use once_cell::sync::Lazy;
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+
+use crate::call_with_hash_generic;
+
+use super::{checksum::Checksum, error::HashError, hash::Blake2};
+
+/// Session is dropped if it wasn't updated for this long
+pub const SESSION_TTL: Duration = Duration::from_secs(300);
+
+enum Hasher {
+    Digest(Box<dyn DynDigest + Send + Sync>),
+    Blake2(Blake2),
+    Checksum(Checksum),
+}
+
+impl Hasher {
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Hasher::Digest(digest) => digest.update(data),
+            Hasher::Blake2(blake2) => blake2.update(data),
+            Hasher::Checksum(checksum) => checksum.update(data),
+        }
+    }
+
+    fn finalize(self) -> Vec<u8> {
+        match self {
+            Hasher::Digest(digest) => digest.finalize().to_vec(),
+            Hasher::Blake2(blake2) => blake2.finalize(),
+            Hasher::Checksum(checksum) => checksum.finalize(),
+        }
+    }
+}
+
+struct HashSession {
+    /// Taken by hash_final
+    hasher: Option<Hasher>,
+    ttl: Instant,
+}
+
+impl HashSession {
+    fn new(hasher: Hasher) -> Self {
+        Self {
+            hasher: Some(hasher),
+            ttl: Instant::now() + SESSION_TTL,
+        }
+    }
+
+    fn increase_ttl(&mut self) {
+        self.ttl = Instant::now() + SESSION_TTL;
+    }
+}
+
+/// The map is locked only to find a session, hashing happens under the session's own lock
+static SESSIONS: Lazy<RwLock<BTreeMap<String, Arc<Mutex<HashSession>>>>> =
+    Lazy::new(|| RwLock::new(BTreeMap::new()));
+
+fn _boxed_hash<D: Digest + DynDigest + Send + Sync + 'static>() -> Result<Hasher, HashError> {
+    Ok(Hasher::Digest(Box::new(<D as Digest>::new())))
+}
+
+fn _boxed_hmac<H: KeyInit + DynDigest + Send + Sync + 'static>(
+    key: &[u8],
+) -> Result<Hasher, HashError> {
+    let hmac = <H as KeyInit>::new_from_slice(key)?;
+    Ok(Hasher::Digest(Box::new(hmac)))
+}
+
+fn new_session(hasher: Hasher) -> String {
+    let mut id = [0u8; 16];
+    StdRng::from_entropy().fill_bytes(&mut id);
+    let id = hex::encode(id);
+
+    let mut sessions = SESSIONS.write().unwrap_or_else(PoisonError::into_inner);
+    let now = Instant::now();
+    // a session that is locked right now is in use
+    sessions.retain(|_, session| match session.try_lock() {
+        Ok(session) => session.ttl > now,
+        Err(_) => true,
+    });
+    sessions.insert(id.clone(), Arc::new(Mutex::new(HashSession::new(hasher))));
+
+    id
+}
+
+/// Returns session id for hash_update/hash_final
+/// Checksums (crc32, xxh64, ...) work here too
+pub fn hash_init(hash_type: &str) -> Result<String, HashError> {
+    let hasher = match (Checksum::new(hash_type), Blake2::new(hash_type)) {
+        (Some(checksum), _) => Hasher::Checksum(checksum),
+        (_, Some(blake2)) => Hasher::Blake2(blake2?),
+        _ => call_with_hash_generic!(_boxed_hash(), hash_type, HashError::InvalidHashType)?,
+    };
+    Ok(new_session(hasher))
+}
+
+/// Returns session id for hash_update/hash_final
+pub fn hmac_init(hash_type: &str, key: &[u8]) -> Result<String, HashError> {
+    let hasher = call_with_hash_generic!(
+        _boxed_hmac(key),
+        hmac(hash_type),
+        HashError::InvalidHashType
+    )?;
+    Ok(new_session(hasher))
+}
+
+fn find_session(id: &str) -> Result<Arc<Mutex<HashSession>>, HashError> {
+    let sessions = SESSIONS.read().unwrap_or_else(PoisonError::into_inner);
+    sessions
+        .get(id)
+        .cloned()
+        .ok_or_else(|| HashError::SessionNotFound(id.to_owned()))
+}
+
+pub fn hash_update(id: &str, data: &[u8]) -> Result<(), HashError> {
+    let session = find_session(id)?;
+    let mut session = session.lock().unwrap_or_else(PoisonError::into_inner);
+    let session = &mut *session;
+    let hasher = match session.hasher.as_mut() {
+        Some(hasher) if session.ttl > Instant::now() => hasher,
+        _ => return Err(HashError::SessionNotFound(id.to_owned())),
+    };
+
+    hasher.update(data);
+    session.increase_ttl();
+    Ok(())
+}
+
+/// Finishes the session, id can't be used after that
+pub fn hash_final(id: &str) -> Result<Vec<u8>, HashError> {
+    let session = {
+        let mut sessions = SESSIONS.write().unwrap_or_else(PoisonError::into_inner);
+        sessions.remove(id)
+    };
+    let session = session.ok_or_else(|| HashError::SessionNotFound(id.to_owned()))?;
+    let mut session = session.lock().unwrap_or_else(PoisonError::into_inner);
+    match session.hasher.take() {
+        Some(hasher) if session.ttl > Instant::now() => Ok(hasher.finalize()),
+        _ => Err(HashError::SessionNotFound(id.to_owned())),
+    }
+}
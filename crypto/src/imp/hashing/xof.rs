@@ -0,0 +1,61 @@
+use digest::{core_api::CoreWrapper, ExtendableOutput, InvalidLength, Update, XofReader};
+use sha3::{CShake128Core, CShake256Core, Shake128, Shake256};
+use tiny_keccak::{Hasher, Kmac};
+
+use super::error::HashError;
+
+/// Longest XOF output, 1 MiB. A typo in the length would otherwise abort Keeper
+/// with an out of memory error
+pub const MAX_XOF_LENGTH: usize = 1 << 20;
+
+fn _xof<X: ExtendableOutput + Update>(mut hasher: X, data: &[u8], output: &mut [u8]) {
+    hasher.update(data);
+    hasher.finalize_xof().read(output);
+}
+
+fn _kmac(mut kmac: Kmac, data: &[u8], output: &mut [u8]) {
+    kmac.update(data);
+    kmac.finalize(output);
+}
+
+/// customization is used by cshake and kmac, key by kmac and blake3 (keyed mode, 32 bytes)
+pub fn make_hash_xof(
+    data: &[u8],
+    xof_type: &str,
+    output_length: usize,
+    customization: &[u8],
+    key: &[u8],
+) -> Result<Vec<u8>, HashError> {
+    if output_length > MAX_XOF_LENGTH {
+        return Err(HashError::OutputTooLong(output_length, MAX_XOF_LENGTH));
+    }
+    let mut output = vec![0u8; output_length];
+
+    match xof_type {
+        "shake128" => _xof(Shake128::default(), data, &mut output),
+        "shake256" => _xof(Shake256::default(), data, &mut output),
+        "cshake128" => _xof(
+            CoreWrapper::from_core(CShake128Core::new(customization)),
+            data,
+            &mut output,
+        ),
+        "cshake256" => _xof(
+            CoreWrapper::from_core(CShake256Core::new(customization)),
+            data,
+            &mut output,
+        ),
+        "kmac128" => _kmac(Kmac::v128(key, customization), data, &mut output),
+        "kmac256" => _kmac(Kmac::v256(key, customization), data, &mut output),
+        "blake3" => {
+            let mut hasher = match key.is_empty() {
+                true => blake3::Hasher::new(),
+                false => blake3::Hasher::new_keyed(key.try_into().or(Err(InvalidLength))?),
+            };
+            hasher.update(data);
+            hasher.finalize_xof().fill(&mut output);
+        }
+        _ => return Err(HashError::InvalidHashType(xof_type.to_owned())),
+    }
+
+    Ok(output)
+}
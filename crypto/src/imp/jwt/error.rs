@@ -0,0 +1,35 @@
+use thiserror::Error;
+
+use crate::imp::{
+    ecc::error::EccError,
+    encryption::error::{CipherError, RsaError},
+    hashing::error::HashError,
+};
+
+#[derive(Error, Debug)]
+pub enum JwtError {
+    #[error("invalid token (expected {0} base64url parts separated by dots)")]
+    InvalidToken(usize),
+    #[error("invalid json: {0}")]
+    InvalidJson(String),
+    #[error("invalid algorithm: {0}")]
+    InvalidAlgorithm(String),
+    #[error("{0} is not supported")]
+    UnsupportedAlgorithm(&'static str),
+    #[error("invalid content encryption: {0}")]
+    InvalidEncryption(String),
+    #[error("algorithm is not set, neither in the header nor as a parameter")]
+    NoAlgorithm,
+    #[error("expected algorithm is required to verify a token")]
+    AlgorithmRequired,
+    #[error("invalid hmac key (expected base64)")]
+    InvalidHmacKey,
+    #[error(transparent)]
+    Rsa(#[from] RsaError),
+    #[error(transparent)]
+    Ecc(#[from] EccError),
+    #[error(transparent)]
+    Hash(#[from] HashError),
+    #[error(transparent)]
+    Cipher(#[from] CipherError),
+}
@@ -0,0 +1,104 @@
+use serde_json::json;
+
+use crate::{
+    imp::{
+        encryption::{aead_decrypt, aead_encrypt, rsa_decrypt, rsa_encrypt, DEFAULT_TAG_LEN},
+        tool::random::random_bytes,
+    },
+    utils::base64,
+};
+
+use super::{error::JwtError, jwt::decode_json_part};
+
+const IV_LEN: usize = 12;
+
+/// Returns OAEP hash for the key management algorithm
+fn oaep_hash(alg: &str) -> Result<&'static str, JwtError> {
+    match alg {
+        "RSA-OAEP-256" | "" => Ok("sha256"),
+        "RSA-OAEP" => Ok("sha1"),
+        _ => Err(JwtError::InvalidAlgorithm(alg.to_owned())),
+    }
+}
+
+/// Returns content encryption key length
+fn gcm_key_len(enc: &str) -> Result<usize, JwtError> {
+    match enc {
+        "A128GCM" => Ok(16),
+        "A192GCM" => Ok(24),
+        "A256GCM" | "" => Ok(32),
+        _ => Err(JwtError::InvalidEncryption(enc.to_owned())),
+    }
+}
+
+/// Compact serialization: header.encrypted_key.iv.ciphertext.tag.
+/// alg: RSA-OAEP-256 (default) or RSA-OAEP, enc: A256GCM (default), A192GCM, A128GCM
+pub fn jwe_encrypt(plaintext: &[u8], key: &str, alg: &str, enc: &str) -> Result<String, JwtError> {
+    let hash_type = oaep_hash(alg)?;
+    let key_len = gcm_key_len(enc)?;
+
+    let header = json!({
+        "alg": if alg.is_empty() { "RSA-OAEP-256" } else { alg },
+        "enc": if enc.is_empty() { "A256GCM" } else { enc },
+    });
+    let header = base64::encode_url(header.to_string());
+
+    let cek = random_bytes(key_len);
+    let iv = random_bytes(IV_LEN);
+    let encrypted_key = rsa_encrypt(&cek, key, hash_type)?;
+
+    let mut ciphertext = aead_encrypt(
+        plaintext.to_vec(),
+        &cek,
+        &iv,
+        header.as_bytes(),
+        "gcm",
+        DEFAULT_TAG_LEN,
+    )?;
+    let tag = ciphertext.split_off(ciphertext.len() - DEFAULT_TAG_LEN);
+
+    Ok([
+        header,
+        base64::encode_url(encrypted_key),
+        base64::encode_url(iv),
+        base64::encode_url(ciphertext),
+        base64::encode_url(tag),
+    ]
+    .join("."))
+}
+
+pub fn jwe_decrypt(token: &str, key: &str) -> Result<Vec<u8>, JwtError> {
+    let parts: Vec<&str> = token.trim().split('.').collect();
+    let [header_part, encrypted_key, iv, ciphertext, tag]: [&str; 5] =
+        parts.try_into().or(Err(JwtError::InvalidToken(5)))?;
+
+    let header = decode_json_part(header_part)?;
+    if header.get("zip").is_some() {
+        return Err(JwtError::InvalidEncryption(
+            "compression is not supported".to_owned(),
+        ));
+    }
+    let alg = header["alg"].as_str().ok_or(JwtError::NoAlgorithm)?;
+    let enc = header["enc"].as_str().unwrap_or_default();
+    let hash_type = oaep_hash(alg)?;
+    let key_len = gcm_key_len(enc)?;
+
+    let decode = |part: &str| base64::decode_url(part).or(Err(JwtError::InvalidToken(5)));
+
+    let cek = rsa_decrypt(&decode(encrypted_key)?, key, hash_type)?;
+    if cek.len() != key_len {
+        return Err(JwtError::InvalidEncryption(enc.to_owned()));
+    }
+
+    let data = [decode(ciphertext)?, decode(tag)?].concat();
+    let plaintext = aead_decrypt(
+        data,
+        &cek,
+        &decode(iv)?,
+        header_part.as_bytes(),
+        "gcm",
+        DEFAULT_TAG_LEN,
+    )?;
+
+    Ok(plaintext)
+}
@@ -0,0 +1,192 @@
+use std::{
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde_json::{json, Value};
+
+use crate::{
+    imp::{
+        ecc::{ec_sign, ec_verify},
+        encryption::{rsa_sign, rsa_verify},
+        hashing::make_hmac,
+    },
+    utils::{base64, encoding, helpers::constant_time_eq},
+};
+
+use super::error::JwtError;
+
+pub enum Algorithm {
+    /// hash
+    Hmac(&'static str),
+    /// hash, padding
+    Rsa(&'static str, &'static str),
+    /// curve, hash
+    Ecdsa(&'static str, &'static str),
+    EdDsa,
+}
+
+impl FromStr for Algorithm {
+    type Err = JwtError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let alg = match s {
+            "HS256" => Algorithm::Hmac("sha256"),
+            "HS384" => Algorithm::Hmac("sha384"),
+            "HS512" => Algorithm::Hmac("sha512"),
+            "RS256" => Algorithm::Rsa("sha256", "pkcs1"),
+            "RS384" => Algorithm::Rsa("sha384", "pkcs1"),
+            "RS512" => Algorithm::Rsa("sha512", "pkcs1"),
+            "PS256" => Algorithm::Rsa("sha256", "pss-hash"),
+            "PS384" => Algorithm::Rsa("sha384", "pss-hash"),
+            "PS512" => Algorithm::Rsa("sha512", "pss-hash"),
+            "ES256" => Algorithm::Ecdsa("p256", "sha256"),
+            "ES384" => Algorithm::Ecdsa("p384", "sha384"),
+            "ES256K" => Algorithm::Ecdsa("secp256k1", "sha256"),
+            "EdDSA" => Algorithm::EdDsa,
+            // there is no P-521 curve in ecc
+            "ES512" => return Err(JwtError::UnsupportedAlgorithm("ES512 (P-521)")),
+            // "none" is rejected on purpose
+            _ => return Err(JwtError::InvalidAlgorithm(s.to_owned())),
+        };
+        Ok(alg)
+    }
+}
+
+impl Algorithm {
+    /// HMAC key is base64 or prefixed (`utf8:secret`), other keys are anything rsa/ecc functions accept (PEM, DER, JWK)
+    fn sign(&self, data: &[u8], key: &str) -> Result<Vec<u8>, JwtError> {
+        let signature = match self {
+            Algorithm::Hmac(hash_type) => make_hmac(data, &hmac_key(key)?, hash_type)?,
+            Algorithm::Rsa(hash_type, mode) => rsa_sign(data, key, hash_type, mode)?,
+            Algorithm::Ecdsa(curve, hash_type) => ec_sign(data, key, curve, hash_type, "raw")?,
+            Algorithm::EdDsa => ec_sign(data, key, "ed25519", "", "")?,
+        };
+        Ok(signature)
+    }
+
+    fn verify(&self, data: &[u8], signature: &[u8], key: &str) -> Result<bool, JwtError> {
+        let verified = match self {
+            Algorithm::Hmac(hash_type) => {
+                let expected = make_hmac(data, &hmac_key(key)?, hash_type)?;
+                constant_time_eq(&expected, signature)
+            }
+            Algorithm::Rsa(hash_type, mode) => rsa_verify(data, signature, key, hash_type, mode)?,
+            Algorithm::Ecdsa(curve, hash_type) => {
+                ec_verify(data, signature, key, curve, hash_type)?
+            }
+            Algorithm::EdDsa => ec_verify(data, signature, key, "ed25519", "")?,
+        };
+        Ok(verified)
+    }
+}
+
+fn hmac_key(key: &str) -> Result<Vec<u8>, JwtError> {
+    encoding::decode(key).or(Err(JwtError::InvalidHmacKey))
+}
+
+pub(super) fn parse_json_object(json: &str) -> Result<Value, JwtError> {
+    let value: Value =
+        serde_json::from_str(json).map_err(|e| JwtError::InvalidJson(e.to_string()))?;
+    match value.is_object() {
+        true => Ok(value),
+        false => Err(JwtError::InvalidJson("expected an object".to_owned())),
+    }
+}
+
+pub(super) fn decode_json_part(part: &str) -> Result<Value, JwtError> {
+    let json = base64::decode_url(part.trim_end_matches('=')).or(Err(JwtError::InvalidToken(3)))?;
+    let json = String::from_utf8(json).map_err(|e| JwtError::InvalidJson(e.to_string()))?;
+    parse_json_object(&json)
+}
+
+fn split_token(token: &str) -> Result<[&str; 3], JwtError> {
+    let parts: Vec<&str> = token.trim().split('.').collect();
+    parts.try_into().or(Err(JwtError::InvalidToken(3)))
+}
+
+/// Empty header means {"alg": alg, "typ": "JWT"}. Empty alg is taken from the header.
+/// ES512 is rejected, P-521 keys are not supported
+pub fn jwt_sign(header: &str, payload: &str, key: &str, alg: &str) -> Result<String, JwtError> {
+    let mut header = match header.trim().is_empty() {
+        true => json!({}),
+        false => parse_json_object(header)?,
+    };
+    if !alg.is_empty() {
+        header["alg"] = json!(alg);
+    }
+    if header.get("typ").is_none() {
+        header["typ"] = json!("JWT");
+    }
+
+    let algorithm = header["alg"].as_str().ok_or(JwtError::NoAlgorithm)?;
+    let algorithm = Algorithm::from_str(algorithm)?;
+    let payload = parse_json_object(payload)?;
+
+    let signing_input = format!(
+        "{}.{}",
+        base64::encode_url(header.to_string()),
+        base64::encode_url(payload.to_string())
+    );
+    let signature = algorithm.sign(signing_input.as_bytes(), key)?;
+
+    Ok(format!(
+        "{}.{}",
+        signing_input,
+        base64::encode_url(signature)
+    ))
+}
+
+/// Returns false if the signature doesn't match, the token is expired (exp) or not yet valid (nbf).
+/// alg is required, tokens signed with any other algorithm are rejected. Taking it from the
+/// header would let a forged HS256 token use the public key as the HMAC secret.
+/// ES512 is rejected, P-521 keys are not supported
+pub fn jwt_verify(token: &str, key: &str, alg: &str) -> Result<bool, JwtError> {
+    let algorithm = Algorithm::from_str(match alg {
+        "" => return Err(JwtError::AlgorithmRequired),
+        alg => alg,
+    })?;
+    let [header_part, payload_part, signature_part] = split_token(token)?;
+    let header = decode_json_part(header_part)?;
+    let payload = decode_json_part(payload_part)?;
+    let signature = base64::decode_url(signature_part.trim_end_matches('='))
+        .or(Err(JwtError::InvalidToken(3)))?;
+
+    let token_alg = header["alg"].as_str().ok_or(JwtError::NoAlgorithm)?;
+    if alg != token_alg {
+        return Ok(false);
+    }
+
+    let signing_input = format!("{}.{}", header_part, payload_part);
+    if !algorithm.verify(signing_input.as_bytes(), &signature, key)? {
+        return Ok(false);
+    }
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|now| now.as_secs_f64())
+        .unwrap_or_default();
+    if let Some(exp) = payload["exp"].as_f64() {
+        if now >= exp {
+            return Ok(false);
+        }
+    }
+    if let Some(nbf) = payload["nbf"].as_f64() {
+        if now < nbf {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+/// Doesn't check the signature. Returns {"header": {...}, "payload": {...}}
+pub fn jwt_decode(token: &str) -> Result<String, JwtError> {
+    let [header_part, payload_part, _] = split_token(token)?;
+
+    Ok(json!({
+        "header": decode_json_part(header_part)?,
+        "payload": decode_json_part(payload_part)?,
+    })
+    .to_string())
+}
@@ -0,0 +1,6 @@
+pub mod error;
+mod jwe;
+mod jwt;
+
+pub use jwe::*;
+pub use jwt::*;
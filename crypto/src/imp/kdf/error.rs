@@ -2,6 +2,8 @@ use bcrypt::BcryptError;
 use scrypt::errors::{InvalidOutputLen, InvalidParams};
 use thiserror::Error;
 
+use crate::imp::hashing::error::HashError;
+
 #[derive(Error, Debug)]
 pub enum KdfError {
     #[error("bcrypt error: {0}")]
@@ -10,4 +12,22 @@ pub enum KdfError {
     Scrypt(#[from] InvalidOutputLen),
     #[error("scrypt invalid config: {0}")]
     ScryptInvalidConf(#[from] InvalidParams),
+    #[error("argon2 error: {0}")]
+    Argon2(#[from] argon2::Error),
+    #[error("invalid argon2 variant: {0}")]
+    InvalidArgon2Variant(String),
+    #[error("bcrypt-pbkdf error: {0}")]
+    BcryptPbkdf(#[from] bcrypt_pbkdf::Error),
+    #[error(transparent)]
+    Hash(#[from] HashError),
+    #[error("invalid hkdf prk: {0}")]
+    InvalidPrk(#[from] hkdf::InvalidPrkLength),
+    #[error("output length {0} is too long, max is {1}")]
+    OutputTooLong(usize, usize),
+    #[error("memory cost of {0} bytes is too high, max is {1}")]
+    MemoryTooHigh(u64, u64),
+    #[error("invalid password hash: {0}")]
+    PasswordHash(#[from] password_hash::Error),
+    #[error("unsupported password hash algorithm: {0}")]
+    UnsupportedPasswordHash(String),
 }
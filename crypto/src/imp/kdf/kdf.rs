@@ -1,9 +1,54 @@
+use argon2::{Algorithm, Argon2, Params, Version as Argon2Version};
 use bcrypt::{hash_with_salt, Version};
-use pbkdf2::pbkdf2 as _pbkdf2;
-use scrypt as _scrypt;
+use digest::{core_api::BlockSizeUser, Digest};
+use hkdf::SimpleHkdf;
+use password_hash::{PasswordHash, PasswordHasher, SaltString};
+use pbkdf2::{pbkdf2 as _pbkdf2, Pbkdf2};
+use rand::{rngs::StdRng, SeedableRng};
+use scrypt::{self as _scrypt, Scrypt};
 
 use super::error::KdfError;
-use crate::{call_with_hash_generic, imp::hashing::error::HashError, base64};
+use crate::{call_with_hash_generic, imp::hashing::error::HashError};
+
+/// Longest key of pbkdf2, evpkdf, scrypt, argon2 and bcrypt_pbkdf (bcrypt_pbkdf's own limit).
+/// Keeps a typo in the length from allocating gigabytes
+pub const MAX_KEY_LENGTH: usize = 1024;
+
+/// Most memory scrypt and argon2 may use, 1 GiB. A wrong cost would allocate terabytes
+/// and abort Keeper, allocation failures can't be caught
+pub const MAX_MEMORY: u64 = 1 << 30;
+
+fn check_memory(memory: u64) -> Result<(), KdfError> {
+    match memory <= MAX_MEMORY {
+        true => Ok(()),
+        false => Err(KdfError::MemoryTooHigh(memory, MAX_MEMORY)),
+    }
+}
+
+/// scrypt keeps 128 * r * (n + p) bytes
+pub fn check_scrypt_memory(n: u64, r: u32, p: u32) -> Result<(), KdfError> {
+    let memory = n
+        .saturating_add(p.into())
+        .saturating_mul(r.into())
+        .saturating_mul(128);
+    check_memory(memory)
+}
+
+/// m_cost is in KiB
+fn check_argon2_memory(m_cost: u32) -> Result<(), KdfError> {
+    check_memory(u64::from(m_cost) * 1024)
+}
+
+fn output_buffer(length: usize, max_length: usize) -> Result<Vec<u8>, KdfError> {
+    match length <= max_length {
+        true => Ok(vec![0u8; length]),
+        false => Err(KdfError::OutputTooLong(length, max_length)),
+    }
+}
+
+fn invalid_hash_type(hash_type: String) -> KdfError {
+    HashError::InvalidHashType(hash_type).into()
+}
 
 pub fn bcrypt(data: Vec<u8>, cost: u32, salt: [u8; 16]) -> Result<String, KdfError> {
     let hashed = hash_with_salt(data, cost, salt)?;
@@ -17,12 +62,13 @@ pub fn scrypt(
     p: u32,
     len: usize,
     salt: &[u8],
-) -> Result<String, KdfError> {
+) -> Result<Vec<u8>, KdfError> {
     let params = _scrypt::Params::new(log_n, r, p)?;
-    let mut output = vec![0u8; len];
-    _scrypt::scrypt(data.as_ref(), salt.as_ref(), &params, &mut output)?;
+    check_scrypt_memory(1 << params.log_n(), r, p)?;
+    let mut output = output_buffer(len, MAX_KEY_LENGTH)?;
+    _scrypt::scrypt(data, salt, &params, &mut output)?;
 
-    Ok(base64::encode(output))
+    Ok(output)
 }
 
 pub fn pbkdf2(
@@ -31,14 +77,12 @@ pub fn pbkdf2(
     rounds: u32,
     key_length: usize,
     hash_type: &str,
-) -> Result<Vec<u8>, HashError> {
-    let data = data.as_ref();
-    let salt = salt.as_ref();
-    let mut output = vec![0u8; key_length];
+) -> Result<Vec<u8>, KdfError> {
+    let mut output = output_buffer(key_length, MAX_KEY_LENGTH)?;
     call_with_hash_generic!(
         _pbkdf2(data, salt, rounds, &mut output),
         hmac(hash_type),
-        HashError::InvalidHashType
+        invalid_hash_type
     );
     Ok(output)
 }
@@ -49,12 +93,165 @@ pub fn evpkdf(
     rounds: usize,
     output_length: usize,
     hash_type: &str,
-) -> Result<Vec<u8>, HashError> {
-    let mut output = vec![0u8; output_length];
+) -> Result<Vec<u8>, KdfError> {
+    let mut output = output_buffer(output_length, MAX_KEY_LENGTH)?;
     call_with_hash_generic!(
         evpkdf::evpkdf(data, salt, rounds, &mut output),
         hash_type,
-        HashError::InvalidHashType
+        invalid_hash_type
     );
     Ok(output)
 }
+
+fn argon2_context(
+    variant: &str,
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+    output_length: Option<usize>,
+) -> Result<Argon2<'static>, KdfError> {
+    let algorithm = match variant {
+        "" => Algorithm::default(),
+        _ => Algorithm::new(variant).or(Err(KdfError::InvalidArgon2Variant(variant.to_owned())))?,
+    };
+    check_argon2_memory(m_cost)?;
+    let params = Params::new(m_cost, t_cost, p_cost, output_length)?;
+
+    Ok(Argon2::new(algorithm, Argon2Version::V0x13, params))
+}
+
+/// variant: argon2i, argon2d, argon2id (default). Returns raw key
+pub fn argon2(
+    data: &[u8],
+    salt: &[u8],
+    variant: &str,
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+    output_length: usize,
+) -> Result<Vec<u8>, KdfError> {
+    let mut output = output_buffer(output_length, MAX_KEY_LENGTH)?;
+    let argon2 = argon2_context(variant, m_cost, t_cost, p_cost, Some(output_length))?;
+    argon2.hash_password_into(data, salt, &mut output)?;
+
+    Ok(output)
+}
+
+/// Returns PHC string ($argon2id$v=19$m=...,t=...,p=...$salt$hash).
+/// Random 16 bytes salt is used if salt is empty
+pub fn argon2_hash(
+    data: &[u8],
+    salt: &[u8],
+    variant: &str,
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+) -> Result<String, KdfError> {
+    let argon2 = argon2_context(variant, m_cost, t_cost, p_cost, None)?;
+    let salt = match salt.is_empty() {
+        true => SaltString::generate(StdRng::from_entropy()),
+        false => SaltString::b64_encode(salt)?,
+    };
+
+    Ok(argon2.hash_password(data, &salt)?.to_string())
+}
+
+// SimpleHmac works with every hash, BLAKE2 and BLAKE3 can't be used with Hmac
+fn _hkdf_extract<H: Digest + BlockSizeUser + Clone>(data: &[u8], salt: &[u8], prk: &mut Vec<u8>) {
+    // empty salt is the same as zero-filled salt of hash length, HMAC pads the key with zeros anyway
+    let (output, _) = SimpleHkdf::<H>::extract(Some(salt), data);
+    *prk = output.to_vec();
+}
+
+fn _hkdf_expand<H: Digest + BlockSizeUser + Clone>(
+    prk: &[u8],
+    info: &[u8],
+    output_length: usize,
+) -> Result<Vec<u8>, KdfError> {
+    let hkdf = SimpleHkdf::<H>::from_prk(prk)?;
+    let max_length = 255 * <H as Digest>::output_size();
+    let mut output = output_buffer(output_length, max_length)?;
+    hkdf.expand(info, &mut output)
+        .or(Err(KdfError::OutputTooLong(output_length, max_length)))?;
+    Ok(output)
+}
+
+/// HKDF-Extract (RFC 5869), returns pseudorandom key of hash length
+pub fn hkdf_extract(data: &[u8], salt: &[u8], hash_type: &str) -> Result<Vec<u8>, KdfError> {
+    let mut prk = Vec::new();
+    call_with_hash_generic!(
+        _hkdf_extract(data, salt, &mut prk),
+        hash_type,
+        invalid_hash_type
+    );
+    Ok(prk)
+}
+
+/// HKDF-Expand (RFC 5869), prk must be at least hash length and output_length can't exceed
+/// 255 * hash length
+pub fn hkdf_expand(
+    prk: &[u8],
+    info: &[u8],
+    output_length: usize,
+    hash_type: &str,
+) -> Result<Vec<u8>, KdfError> {
+    call_with_hash_generic!(
+        _hkdf_expand(prk, info, output_length),
+        hash_type,
+        invalid_hash_type
+    )
+}
+
+pub fn hkdf(
+    data: &[u8],
+    salt: &[u8],
+    info: &[u8],
+    output_length: usize,
+    hash_type: &str,
+) -> Result<Vec<u8>, KdfError> {
+    let prk = hkdf_extract(data, salt, hash_type)?;
+    hkdf_expand(&prk, info, output_length, hash_type)
+}
+
+/// OpenSSH flavour of bcrypt used for key derivation (bcrypt_pbkdf from OpenBSD)
+pub fn bcrypt_pbkdf(
+    data: &[u8],
+    salt: &[u8],
+    rounds: u32,
+    output_length: usize,
+) -> Result<Vec<u8>, KdfError> {
+    let mut output = output_buffer(output_length, MAX_KEY_LENGTH)?;
+    bcrypt_pbkdf::bcrypt_pbkdf(data, salt, rounds, &mut output)?;
+    Ok(output)
+}
+
+/// Checks password against bcrypt ($2a$, $2b$, $2x$, $2y$) or PHC string
+/// ($argon2i$, $argon2d$, $argon2id$, $scrypt$, $pbkdf2-sha256$, ...).
+/// Returns false if the password doesn't match, errors are returned only for bad hash
+pub fn password_verify(data: &[u8], hash: &str) -> Result<bool, KdfError> {
+    let hash = hash.trim();
+
+    if hash.starts_with("$2") {
+        return Ok(bcrypt::verify(data, hash)?);
+    }
+
+    // parameters come from the hash string, so they are checked like the ones of argon2/scrypt
+    let hash = PasswordHash::new(hash)?;
+    match hash.algorithm.as_str() {
+        "argon2i" | "argon2d" | "argon2id" => {
+            check_argon2_memory(Params::try_from(&hash)?.m_cost())?
+        }
+        "scrypt" => {
+            let params = _scrypt::Params::try_from(&hash)?;
+            check_scrypt_memory(1 << params.log_n(), params.r(), params.p())?
+        }
+        "pbkdf2" | "pbkdf2-sha256" | "pbkdf2-sha512" => {}
+        algorithm => return Err(KdfError::UnsupportedPasswordHash(algorithm.to_owned())),
+    }
+
+    match hash.verify_password(&[&Argon2::default(), &Scrypt, &Pbkdf2], data) {
+        Ok(()) => Ok(true),
+        Err(password_hash::Error::Password) => Ok(false),
+        Err(err) => Err(err.into()),
+    }
+}
@@ -1,4 +1,9 @@
+pub mod ecc;
 pub mod encryption;
 pub mod hashing;
+pub mod jwt;
 pub mod kdf;
+pub mod otp;
+pub mod pipeline;
 pub mod tool;
+pub mod x509;
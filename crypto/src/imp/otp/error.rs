@@ -0,0 +1,19 @@
+use thiserror::Error;
+
+use crate::imp::hashing::error::HashError;
+
+#[derive(Error, Debug)]
+pub enum OtpError {
+    #[error("invalid secret (expected base32)")]
+    InvalidSecret,
+    #[error("invalid number of digits: {0}. Only 4-10 accepted")]
+    InvalidDigits(u32),
+    #[error("invalid period: 0")]
+    InvalidPeriod,
+    #[error("invalid window: {0}, max is {1}")]
+    InvalidWindow(u64, u64),
+    #[error("invalid otpauth uri: {0}")]
+    InvalidUri(String),
+    #[error(transparent)]
+    HashError(#[from] HashError),
+}
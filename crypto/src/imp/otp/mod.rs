@@ -0,0 +1,4 @@
+pub mod error;
+mod otp;
+
+pub use otp::*;
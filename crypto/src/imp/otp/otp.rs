@@ -0,0 +1,247 @@
+use std::{
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use base32::Alphabet;
+use percent_encoding::percent_decode_str;
+use serde_json::json;
+
+use crate::{
+    imp::hashing::{error::HashError, make_hmac},
+    utils::{encoding, helpers::constant_time_eq},
+};
+
+use super::error::OtpError;
+
+pub const DEFAULT_DIGITS: u32 = 6;
+pub const DEFAULT_PERIOD: u64 = 30;
+pub const DEFAULT_HASH: &str = "sha1";
+/// Steps accepted before and after the current one
+pub const DEFAULT_WINDOW: u64 = 1;
+/// A wider window makes guessing the code much easier
+pub const MAX_WINDOW: u64 = 10;
+
+pub fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|now| now.as_secs())
+        .unwrap_or_default()
+}
+
+/// Base32, spaces and dashes are ignored, case doesn't matter.
+/// Other encodings need a prefix (`hex:`, `utf8:`), ':' is never part of base32
+fn decode_secret(secret: &str) -> Result<Vec<u8>, OtpError> {
+    if secret.contains(':') {
+        return encoding::decode(secret)
+            .ok()
+            .filter(|secret| !secret.is_empty())
+            .ok_or(OtpError::InvalidSecret);
+    }
+    let secret: String = secret
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .collect();
+
+    base32::decode(Alphabet::RFC4648 { padding: false }, &secret)
+        .filter(|secret| !secret.is_empty())
+        .ok_or(OtpError::InvalidSecret)
+}
+
+fn hash_or_default(hash_type: &str) -> String {
+    match hash_type.is_empty() {
+        true => DEFAULT_HASH.to_owned(),
+        false => hash_type.to_lowercase(),
+    }
+}
+
+/// RFC 4226: HMAC of the big endian counter, dynamic truncation, last `digits` decimal digits
+fn _hotp(key: &[u8], counter: u64, digits: u32, hash_type: &str) -> Result<String, OtpError> {
+    if !(4..=10).contains(&digits) {
+        return Err(OtpError::InvalidDigits(digits));
+    }
+
+    let hash_type = hash_or_default(hash_type);
+    let mac = make_hmac(&counter.to_be_bytes(), key, &hash_type)?;
+
+    let offset = (mac[mac.len() - 1] & 0x0f) as usize;
+    let binary = mac
+        .get(offset..offset + 4)
+        .ok_or(HashError::InvalidHashType(hash_type))?;
+    let binary = u32::from_be_bytes(binary.try_into().unwrap()) & 0x7fff_ffff;
+
+    let code = binary as u64 % 10u64.pow(digits);
+    Ok(format!("{:0width$}", code, width = digits as usize))
+}
+
+pub fn hotp(secret: &str, counter: u64, digits: u32, hash_type: &str) -> Result<String, OtpError> {
+    let key = decode_secret(secret)?;
+    _hotp(&key, counter, digits, hash_type)
+}
+
+/// RFC 6238, counter is the number of periods since the unix epoch
+pub fn totp(
+    secret: &str,
+    digits: u32,
+    period: u64,
+    hash_type: &str,
+    timestamp: u64,
+) -> Result<String, OtpError> {
+    if period == 0 {
+        return Err(OtpError::InvalidPeriod);
+    }
+
+    let key = decode_secret(secret)?;
+    _hotp(&key, timestamp / period, digits, hash_type)
+}
+
+/// Accepts codes from `window` periods before and after the timestamp, window is at most 10
+pub fn totp_verify(
+    secret: &str,
+    code: &str,
+    digits: u32,
+    period: u64,
+    hash_type: &str,
+    window: u64,
+    timestamp: u64,
+) -> Result<bool, OtpError> {
+    if period == 0 {
+        return Err(OtpError::InvalidPeriod);
+    }
+    if window > MAX_WINDOW {
+        return Err(OtpError::InvalidWindow(window, MAX_WINDOW));
+    }
+
+    let key = decode_secret(secret)?;
+    let counter = timestamp / period;
+    let code = code.trim();
+
+    // every code in the window is checked, so the time doesn't tell which one matched
+    let mut verified = false;
+    for counter in counter.saturating_sub(window)..=counter.saturating_add(window) {
+        let expected = _hotp(&key, counter, digits, hash_type)?;
+        verified |= constant_time_eq(expected.as_bytes(), code.as_bytes());
+    }
+
+    Ok(verified)
+}
+
+pub enum OtpKind {
+    Totp,
+    Hotp,
+}
+
+/// otpauth://totp/Issuer:account?secret=...&issuer=...&algorithm=SHA1&digits=6&period=30
+pub struct OtpAuth {
+    pub kind: OtpKind,
+    pub label: String,
+    pub issuer: Option<String>,
+    pub secret: String,
+    pub hash_type: String,
+    pub digits: u32,
+    pub period: u64,
+    pub counter: u64,
+}
+
+impl FromStr for OtpAuth {
+    type Err = OtpError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| OtpError::InvalidUri(reason.to_owned());
+        let decode = |value: &str| percent_decode_str(value).decode_utf8_lossy().into_owned();
+
+        let uri = s.trim();
+        let rest = match uri.get(..10) {
+            Some(scheme) if scheme.eq_ignore_ascii_case("otpauth://") => &uri[10..],
+            _ => return Err(invalid("expected otpauth:// scheme")),
+        };
+
+        let (kind, rest) = rest.split_once('/').ok_or_else(|| invalid("no type"))?;
+        let kind = match kind.to_lowercase().as_str() {
+            "totp" => OtpKind::Totp,
+            "hotp" => OtpKind::Hotp,
+            _ => return Err(invalid(&format!("unknown type {}", kind))),
+        };
+        let (label, query) = rest.split_once('?').unwrap_or((rest, ""));
+
+        let mut otp_auth = OtpAuth {
+            kind,
+            label: decode(label),
+            issuer: None,
+            secret: String::new(),
+            hash_type: DEFAULT_HASH.to_owned(),
+            digits: DEFAULT_DIGITS,
+            period: DEFAULT_PERIOD,
+            counter: 0,
+        };
+        let mut has_counter = false;
+
+        for (name, value) in query.split('&').filter_map(|param| param.split_once('=')) {
+            let value = decode(value);
+            match name.to_lowercase().as_str() {
+                "secret" => otp_auth.secret = value,
+                "issuer" => otp_auth.issuer = Some(value),
+                "algorithm" => otp_auth.hash_type = value.to_lowercase(),
+                "digits" => otp_auth.digits = value.parse().or(Err(invalid("bad digits")))?,
+                "period" => otp_auth.period = value.parse().or(Err(invalid("bad period")))?,
+                "counter" => {
+                    otp_auth.counter = value.parse().or(Err(invalid("bad counter")))?;
+                    has_counter = true;
+                }
+                _ => (),
+            }
+        }
+
+        if otp_auth.secret.is_empty() {
+            return Err(invalid("no secret"));
+        }
+        if matches!(otp_auth.kind, OtpKind::Hotp) && !has_counter {
+            return Err(invalid("no counter for hotp"));
+        }
+
+        Ok(otp_auth)
+    }
+}
+
+impl OtpAuth {
+    pub fn code(&self, timestamp: u64) -> Result<String, OtpError> {
+        match self.kind {
+            OtpKind::Totp => totp(
+                &self.secret,
+                self.digits,
+                self.period,
+                &self.hash_type,
+                timestamp,
+            ),
+            OtpKind::Hotp => hotp(&self.secret, self.counter, self.digits, &self.hash_type),
+        }
+    }
+}
+
+/// Returns all uri parameters as JSON, missing ones are filled with defaults
+pub fn otpauth_parse(uri: &str) -> Result<String, OtpError> {
+    let otp_auth = OtpAuth::from_str(uri)?;
+
+    let mut parsed = json!({
+        "type": match otp_auth.kind {
+            OtpKind::Totp => "totp",
+            OtpKind::Hotp => "hotp",
+        },
+        "label": otp_auth.label,
+        "issuer": otp_auth.issuer,
+        "secret": otp_auth.secret,
+        "algorithm": otp_auth.hash_type,
+        "digits": otp_auth.digits,
+    });
+    match otp_auth.kind {
+        OtpKind::Totp => parsed["period"] = json!(otp_auth.period),
+        OtpKind::Hotp => parsed["counter"] = json!(otp_auth.counter),
+    }
+
+    Ok(parsed.to_string())
+}
+
+/// Current code for the uri (timestamp is ignored for hotp)
+pub fn otpauth_code(uri: &str, timestamp: u64) -> Result<String, OtpError> {
+    OtpAuth::from_str(uri)?.code(timestamp)
+}
@@ -0,0 +1,32 @@
+use thiserror::Error;
+
+use crate::{
+    imp::{encryption::error::CipherError, hashing::error::HashError, kdf::error::KdfError},
+    utils::encoding::EncodingError,
+};
+
+#[derive(Error, Debug)]
+pub enum PipelineError {
+    #[error("invalid recipe: {0}")]
+    InvalidRecipe(String),
+    #[error("step {0} ({1}): {2}")]
+    Step(usize, String, Box<PipelineError>),
+    #[error("unknown operation: {0}")]
+    UnknownOp(String),
+    #[error("missing parameter: {0}")]
+    MissingParam(&'static str),
+    #[error("invalid parameter: {0}")]
+    InvalidParam(&'static str),
+    #[error("unknown variable: {0}")]
+    UnknownVariable(String),
+    #[error("value is not utf8 text")]
+    NotText,
+    #[error(transparent)]
+    Hash(#[from] HashError),
+    #[error(transparent)]
+    Cipher(#[from] CipherError),
+    #[error(transparent)]
+    Kdf(#[from] KdfError),
+    #[error(transparent)]
+    Encoding(#[from] EncodingError),
+}
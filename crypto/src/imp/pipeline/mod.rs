@@ -0,0 +1,4 @@
+pub mod error;
+mod recipe;
+
+pub use recipe::*;
@@ -0,0 +1,227 @@
+use std::{collections::HashMap, str::FromStr};
+
+use serde_json::{Map, Value};
+
+use crate::{
+    imp::{encryption, hashing, kdf},
+    utils::encoding::{self, Encoding},
+};
+
+use super::error::PipelineError;
+
+/// Recipe is either an array of steps or `{"vars": {..}, "steps": [..], "output": ".."}`.
+/// Each step is `{"op": "..", params..}` and gets the previous step's result,
+/// `"input"` takes another value instead and `"save"` stores the result as a variable.
+/// Binary params are `$name` variables (`$input` is the pipeline input) or values
+/// with an encoding prefix like handler arguments.
+/// Returns the final value in `output` encoding, base64 if not set
+pub fn pipeline(recipe: &str, input: &[u8]) -> Result<String, PipelineError> {
+    let recipe: Value =
+        serde_json::from_str(recipe).map_err(|e| PipelineError::InvalidRecipe(e.to_string()))?;
+
+    let (steps, initial_vars, output) = match &recipe {
+        Value::Array(steps) => (steps, None, None),
+        Value::Object(recipe) => (
+            recipe
+                .get("steps")
+                .and_then(Value::as_array)
+                .ok_or_else(|| PipelineError::InvalidRecipe("expected a steps array".to_owned()))?,
+            recipe.get("vars"),
+            recipe.get("output"),
+        ),
+        _ => {
+            return Err(PipelineError::InvalidRecipe(
+                "expected an array of steps or an object".to_owned(),
+            ))
+        }
+    };
+
+    let mut vars = HashMap::from([("input".to_owned(), input.to_vec())]);
+    if let Some(initial_vars) = initial_vars {
+        let initial_vars = initial_vars.as_object().ok_or_else(|| {
+            PipelineError::InvalidRecipe("vars must be an object of strings".to_owned())
+        })?;
+        for (name, value) in initial_vars {
+            let value = value.as_str().ok_or_else(|| {
+                PipelineError::InvalidRecipe(format!("variable {name} is not a string"))
+            })?;
+            vars.insert(name.to_owned(), encoding::decode(value)?);
+        }
+    }
+
+    let mut value = input.to_vec();
+    for (i, step) in steps.iter().enumerate() {
+        let op = step["op"].as_str().unwrap_or_default();
+        let step_error = |e| PipelineError::Step(i + 1, op.to_owned(), Box::new(e));
+
+        let params = step.as_object().ok_or_else(|| {
+            step_error(PipelineError::InvalidRecipe(
+                "step is not an object".to_owned(),
+            ))
+        })?;
+        let step = Step {
+            params,
+            vars: &vars,
+        };
+
+        value = step.run(op, value).map_err(step_error)?;
+        if let Some(name) = params.get("save").and_then(Value::as_str) {
+            vars.insert(name.to_owned(), value.clone());
+        }
+    }
+
+    let output = match output.and_then(Value::as_str) {
+        Some(output) => Encoding::from_str(output)?,
+        None => Encoding::Base64,
+    };
+    Ok(output.encode(&value)?)
+}
+
+struct Step<'a> {
+    params: &'a Map<String, Value>,
+    vars: &'a HashMap<String, Vec<u8>>,
+}
+
+impl<'a> Step<'a> {
+    fn run(&self, op: &str, previous: Vec<u8>) -> Result<Vec<u8>, PipelineError> {
+        let mut data = match self.params.contains_key("input") {
+            true => self.bytes("input")?,
+            false => previous,
+        };
+
+        let output = match op {
+            "hash" => hashing::make_hash(&data, self.str("hash")?)?,
+            "hmac" => hashing::make_hmac(&data, &self.bytes("key")?, self.str("hash")?)?,
+            "aes_encrypt" | "aes_decrypt" | "blowfish_encrypt" | "blowfish_decrypt"
+            | "block_encrypt" | "block_decrypt" => {
+                let cipher = match op {
+                    "block_encrypt" | "block_decrypt" => self.str("cipher")?,
+                    _ => op.split('_').next().unwrap_or_default(),
+                };
+                let key = self.bytes("key")?;
+                let iv = self.bytes_or_empty("iv")?;
+                let mode = self.str("mode")?;
+                let padding = self.str_or_empty("padding")?;
+
+                match op.ends_with("_encrypt") {
+                    true => encryption::block_encrypt(cipher, &mut data, &key, &iv, mode, padding)?
+                        .into_owned(),
+                    false => {
+                        encryption::block_decrypt(cipher, &mut data, &key, &iv, mode, padding)?
+                            .into_owned()
+                    }
+                }
+            }
+            "rc4" => {
+                encryption::rc4(&mut data, &self.bytes("key")?);
+                data
+            }
+            "xor" => {
+                encryption::xor(&mut data, &self.bytes("key")?);
+                data
+            }
+            "pbkdf2" => kdf::pbkdf2(
+                &data,
+                &self.bytes("salt")?,
+                self.number("iterations")?,
+                self.number("len")?,
+                self.str("hash")?,
+            )?,
+            "evpkdf" => kdf::evpkdf(
+                &data,
+                &self.bytes_or_empty("salt")?,
+                self.number("iterations")?,
+                self.number("len")?,
+                self.str("hash")?,
+            )?,
+            "scrypt" => kdf::scrypt(
+                &data,
+                self.number("log_n")?,
+                self.number("r")?,
+                self.number("p")?,
+                self.number("len")?,
+                &self.bytes("salt")?,
+            )?,
+            "argon2" => kdf::argon2(
+                &data,
+                &self.bytes("salt")?,
+                self.str("variant")?,
+                self.number("m_cost")?,
+                self.number("t_cost")?,
+                self.number("p_cost")?,
+                self.number("len")?,
+            )?,
+            "hkdf" => kdf::hkdf(
+                &data,
+                &self.bytes_or_empty("salt")?,
+                &self.bytes_or_empty("info")?,
+                self.number("len")?,
+                self.str("hash")?,
+            )?,
+            "bcrypt_pbkdf" => kdf::bcrypt_pbkdf(
+                &data,
+                &self.bytes("salt")?,
+                self.number("rounds")?,
+                self.number("len")?,
+            )?,
+            // bytes to their text form and back, e.g. hmac -> hex before concatenation
+            "encode" => Encoding::from_str(self.str("encoding")?)?
+                .encode(&data)?
+                .into_bytes(),
+            "decode" => {
+                let text = std::str::from_utf8(&data).or(Err(PipelineError::NotText))?;
+                Encoding::from_str(self.str("encoding")?)?.decode(text)?
+            }
+            "concat" => [data, self.bytes("with")?].concat(),
+            "" => return Err(PipelineError::MissingParam("op")),
+            _ => return Err(PipelineError::UnknownOp(op.to_owned())),
+        };
+
+        Ok(output)
+    }
+
+    fn str(&self, name: &'static str) -> Result<&'a str, PipelineError> {
+        match self.params.get(name) {
+            Some(value) => value.as_str().ok_or(PipelineError::InvalidParam(name)),
+            None => Err(PipelineError::MissingParam(name)),
+        }
+    }
+
+    fn str_or_empty(&self, name: &'static str) -> Result<&'a str, PipelineError> {
+        match self.params.contains_key(name) {
+            true => self.str(name),
+            false => Ok(""),
+        }
+    }
+
+    /// `$name` is a variable, anything else is decoded like a handler argument
+    fn bytes(&self, name: &'static str) -> Result<Vec<u8>, PipelineError> {
+        let value = self.str(name)?;
+        match value.strip_prefix('$') {
+            Some(var) => self
+                .vars
+                .get(var)
+                .cloned()
+                .ok_or_else(|| PipelineError::UnknownVariable(var.to_owned())),
+            None => Ok(encoding::decode(value)?),
+        }
+    }
+
+    fn bytes_or_empty(&self, name: &'static str) -> Result<Vec<u8>, PipelineError> {
+        match self.params.contains_key(name) {
+            true => self.bytes(name),
+            false => Ok(Vec::new()),
+        }
+    }
+
+    fn number<T: TryFrom<u64>>(&self, name: &'static str) -> Result<T, PipelineError> {
+        let value = self
+            .params
+            .get(name)
+            .ok_or(PipelineError::MissingParam(name))?;
+        value
+            .as_u64()
+            .and_then(|n| T::try_from(n).ok())
+            .ok_or(PipelineError::InvalidParam(name))
+    }
+}
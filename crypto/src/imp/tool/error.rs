@@ -0,0 +1,13 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ToolError {
+    #[error("alphabet is empty")]
+    EmptyAlphabet,
+    #[error("invalid character class: {0}. Only lower, upper, digits, symbols accepted")]
+    InvalidCharClass(String),
+    #[error("password length {0} is less than the number of required character classes {1}")]
+    PasswordTooShort(usize, usize),
+    #[error("length {0} is too long, max is {1}")]
+    TooLong(usize, usize),
+}
@@ -1,8 +1,96 @@
-use rand::{rngs::SmallRng, Rng, SeedableRng};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+use crate::utils::helpers::or_default;
+
+use super::error::ToolError;
+
+const LOWER: &str = "abcdefghijklmnopqrstuvwxyz";
+const UPPER: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const DIGITS: &str = "0123456789";
+const SYMBOLS: &str = "!#$%&()*+,-./:;<=>?@[]^_{|}~";
+const HEX: &str = "0123456789abcdef";
+const BASE62: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+const URLSAFE: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Longest random_bytes, random_token and random_password result, 1 MiB.
+/// A typo in the length would otherwise abort Keeper with an out of memory error
+pub const MAX_LENGTH: usize = 1 << 20;
+
+/// Lengths coming from callers are checked before allocating,
+/// internal fixed sizes go to random_bytes directly
+pub fn check_length(len: usize) -> Result<usize, ToolError> {
+    match len <= MAX_LENGTH {
+        true => Ok(len),
+        false => Err(ToolError::TooLong(len, MAX_LENGTH)),
+    }
+}
+
+/// ChaCha based CSPRNG seeded from the OS, safe for keys, IVs and nonces
+fn rng() -> StdRng {
+    StdRng::from_entropy()
+}
 
 pub fn random_bytes(len: usize) -> Vec<u8> {
-    let mut rng = SmallRng::from_entropy();
+    let mut rng = rng();
     let mut out = vec![0; len];
     rng.fill(out.as_mut_slice());
     out
 }
+
+/// alphabet: hex, base62 (default), urlsafe, digits or any custom set of characters
+pub fn random_token(len: usize, alphabet: &str) -> Result<String, ToolError> {
+    let len = check_length(len)?;
+    let alphabet = match alphabet {
+        "hex" => HEX,
+        "base62" | "" => BASE62,
+        "urlsafe" => URLSAFE,
+        "digits" => DIGITS,
+        _ => alphabet,
+    };
+    let alphabet: Vec<char> = alphabet.chars().collect();
+    if alphabet.is_empty() {
+        return Err(ToolError::EmptyAlphabet);
+    }
+
+    let mut rng = rng();
+    Ok((0..len)
+        .map(|_| *alphabet.choose(&mut rng).unwrap())
+        .collect())
+}
+
+/// classes: comma separated lower, upper, digits, symbols (all of them if empty).
+/// Every class is present in the password at least once.
+/// symbols replaces the default set of special characters if not empty
+pub fn random_password(len: usize, classes: &str, symbols: &str) -> Result<String, ToolError> {
+    let len = check_length(len)?;
+    let classes = or_default(classes, "lower,upper,digits,symbols");
+
+    let mut sets: Vec<Vec<char>> = Vec::new();
+    for class in classes.split(',').map(str::trim) {
+        let set = match class {
+            "lower" => LOWER,
+            "upper" => UPPER,
+            "digits" => DIGITS,
+            "symbols" if !symbols.is_empty() => symbols,
+            "symbols" => SYMBOLS,
+            _ => return Err(ToolError::InvalidCharClass(class.to_owned())),
+        };
+        sets.push(set.chars().collect());
+    }
+
+    if len < sets.len() {
+        return Err(ToolError::PasswordTooShort(len, sets.len()));
+    }
+
+    let mut rng = rng();
+    let all: Vec<char> = sets.concat();
+
+    let mut password: Vec<char> = sets
+        .iter()
+        .map(|set| *set.choose(&mut rng).unwrap())
+        .collect();
+    password.extend((password.len()..len).map(|_| *all.choose(&mut rng).unwrap()));
+    password.shuffle(&mut rng);
+
+    Ok(password.into_iter().collect())
+}
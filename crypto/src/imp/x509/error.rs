@@ -0,0 +1,48 @@
+use thiserror::Error;
+
+use crate::imp::{
+    ecc::error::EccError,
+    encryption::error::{CipherError, RsaError},
+    hashing::error::HashError,
+    kdf::error::KdfError,
+};
+
+#[derive(Error, Debug)]
+pub enum X509Error {
+    #[error("invalid certificate (expected PEM or base64 DER)")]
+    InvalidCertificate,
+    #[error("invalid private key (expected RSA, p256, p384, secp256k1 or ed25519)")]
+    InvalidKey,
+    #[error("raw private key fits several curves, use PEM, DER or JWK")]
+    AmbiguousKey,
+    #[error("invalid subject: {0}")]
+    InvalidSubject(String),
+    #[error("invalid validity period: {0} days")]
+    InvalidDays(u32),
+    #[error("{0} is not supported for certificate signing")]
+    UnsupportedHash(String),
+    #[error("invalid pkcs12: {0}")]
+    InvalidPkcs12(String),
+    #[error("invalid password or corrupted pkcs12")]
+    InvalidPassword,
+    #[error("invalid pkcs12 iteration count: {0}, expected 1 to {1}")]
+    InvalidIterations(i64, i32),
+    #[error("unsupported pkcs12 encryption: {0}")]
+    UnsupportedPbe(String),
+    #[error("private key doesn't match the first certificate")]
+    KeyMismatch,
+    #[error("pbes2: {0}")]
+    Pkcs5(String),
+    #[error("asn.1 error: {0}")]
+    Der(#[from] x509_cert::der::Error),
+    #[error(transparent)]
+    Rsa(#[from] RsaError),
+    #[error(transparent)]
+    Ecc(#[from] EccError),
+    #[error(transparent)]
+    HashError(#[from] HashError),
+    #[error(transparent)]
+    Cipher(#[from] CipherError),
+    #[error(transparent)]
+    Kdf(#[from] KdfError),
+}
@@ -0,0 +1,6 @@
+pub mod error;
+mod pkcs12;
+mod x509;
+
+pub use self::pkcs12::*;
+pub use x509::*;
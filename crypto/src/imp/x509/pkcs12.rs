@@ -0,0 +1,456 @@
+use cms::{
+    content_info::{CmsVersion, ContentInfo},
+    encrypted_data::EncryptedData,
+    enveloped_data::EncryptedContentInfo,
+};
+use pkcs12::{
+    cert_type::CertBag,
+    digest_info::DigestInfo,
+    kdf::{derive_key_utf8, Pkcs12KeyType},
+    mac_data::MacData,
+    pbe_params::{EncryptedPrivateKeyInfo, Pkcs12PbeParams},
+    pfx::{Pfx, Version},
+    safe_bag::{SafeBag, SafeContents},
+    PKCS_12_CERT_BAG_OID, PKCS_12_KEY_BAG_OID, PKCS_12_PBEWITH_SHAAND40_BIT_RC2_CBC,
+    PKCS_12_PBE_WITH_SHAAND128_BIT_RC2_CBC, PKCS_12_PBE_WITH_SHAAND2_KEY_TRIPLE_DES_CBC,
+    PKCS_12_PBE_WITH_SHAAND3_KEY_TRIPLE_DES_CBC, PKCS_12_PKCS8_KEY_BAG_OID, PKCS_12_X509_CERT_OID,
+};
+use pkcs5::pbes2;
+use serde_json::json;
+use sha1::Sha1;
+use sha2::{Sha256, Sha384, Sha512};
+use x509_cert::{
+    attr::{Attribute, Attributes},
+    der::{
+        asn1::{BmpString, ContextSpecific, ObjectIdentifier, OctetString, SetOfVec},
+        oid::db::{rfc5911, rfc5912},
+        pem::{self, LineEnding},
+        Any, Decode, Encode, EncodePem,
+    },
+    spki::AlgorithmIdentifierOwned,
+    Certificate,
+};
+
+use crate::{
+    imp::{
+        encryption::block_decrypt,
+        hashing::{make_hash, make_hmac},
+        kdf::check_scrypt_memory,
+        tool::random::random_bytes,
+    },
+    utils::{encoding, helpers::constant_time_eq},
+};
+
+use super::{
+    error::X509Error,
+    x509::{oid_name, SigningKey},
+};
+
+/// Same as OpenSSL 3 defaults: PBKDF2-SHA256 with AES-256-CBC, HMAC-SHA256 MAC
+const ITERATIONS: u32 = 2048;
+const SALT_LEN: usize = 16;
+/// Files from OpenSSL and Windows use a few thousand, more only burns CPU on a crafted file
+const MAX_ITERATIONS: i32 = 1_000_000;
+
+const FRIENDLY_NAME: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.9.20");
+const LOCAL_KEY_ID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.9.21");
+
+/// friendlyName and localKeyId of a bag
+#[derive(Default)]
+struct BagAttributes {
+    friendly_name: Option<String>,
+    local_key_id: Option<Vec<u8>>,
+}
+
+impl BagAttributes {
+    fn parse(attributes: &Option<Attributes>) -> Self {
+        let mut parsed = BagAttributes::default();
+
+        for attribute in attributes.iter().flat_map(|attributes| attributes.iter()) {
+            let value = match attribute.values.iter().next() {
+                Some(value) => value,
+                None => continue,
+            };
+            match attribute.oid {
+                FRIENDLY_NAME => {
+                    parsed.friendly_name = value
+                        .decode_as::<BmpString>()
+                        .ok()
+                        .map(|name| name.to_string())
+                }
+                LOCAL_KEY_ID => {
+                    parsed.local_key_id = value
+                        .decode_as::<OctetString>()
+                        .ok()
+                        .map(|id| id.into_bytes())
+                }
+                _ => (),
+            }
+        }
+
+        parsed
+    }
+
+    fn encode(local_key_id: &[u8], friendly_name: &str) -> Result<Attributes, X509Error> {
+        let mut attributes = Attributes::new();
+        attributes.insert(Attribute {
+            oid: LOCAL_KEY_ID,
+            values: SetOfVec::try_from(vec![Any::encode_from(&OctetString::new(local_key_id)?)?])?,
+        })?;
+        if !friendly_name.is_empty() {
+            attributes.insert(Attribute {
+                oid: FRIENDLY_NAME,
+                values: SetOfVec::try_from(vec![Any::encode_from(&BmpString::from_utf8(
+                    friendly_name,
+                )?)?])?,
+            })?;
+        }
+        Ok(attributes)
+    }
+}
+
+fn check_iterations(iterations: impl Into<i64>) -> Result<i32, X509Error> {
+    let iterations = iterations.into();
+    match i32::try_from(iterations) {
+        Ok(iterations @ 1..=MAX_ITERATIONS) => Ok(iterations),
+        _ => Err(X509Error::InvalidIterations(iterations, MAX_ITERATIONS)),
+    }
+}
+
+/// PBKDF2 iterations get the same limit as the PKCS#12 KDF, scrypt the memory limit of kdf
+fn check_pbes2(params: &pbes2::Parameters) -> Result<(), X509Error> {
+    match &params.kdf {
+        pbes2::Kdf::Pbkdf2(kdf) => {
+            check_iterations(kdf.iteration_count)?;
+        }
+        pbes2::Kdf::Scrypt(kdf) => check_scrypt_memory(
+            kdf.cost_parameter,
+            kdf.block_size.into(),
+            kdf.parallelization.into(),
+        )?,
+        kdf => return Err(X509Error::UnsupportedPbe(kdf.oid().to_string())),
+    }
+    Ok(())
+}
+
+/// MAC key is derived with the PKCS#12 KDF using the MAC hash
+fn mac(
+    data: &[u8],
+    password: &str,
+    salt: &[u8],
+    iterations: i32,
+    hash: &ObjectIdentifier,
+) -> Result<Vec<u8>, X509Error> {
+    let iterations = check_iterations(iterations)?;
+    let (key, hash_type) = match *hash {
+        rfc5912::ID_SHA_1 => (
+            derive_key_utf8::<Sha1>(password, salt, Pkcs12KeyType::Mac, iterations, 20)?,
+            "sha1",
+        ),
+        rfc5912::ID_SHA_256 => (
+            derive_key_utf8::<Sha256>(password, salt, Pkcs12KeyType::Mac, iterations, 32)?,
+            "sha256",
+        ),
+        rfc5912::ID_SHA_384 => (
+            derive_key_utf8::<Sha384>(password, salt, Pkcs12KeyType::Mac, iterations, 48)?,
+            "sha384",
+        ),
+        rfc5912::ID_SHA_512 => (
+            derive_key_utf8::<Sha512>(password, salt, Pkcs12KeyType::Mac, iterations, 64)?,
+            "sha512",
+        ),
+        _ => return Err(X509Error::UnsupportedPbe(oid_name(hash))),
+    };
+    Ok(make_hmac(data, &key, hash_type)?)
+}
+
+/// PBES2 from PKCS#5 or one of the legacy PKCS#12 schemes (3DES, RC2)
+fn decrypt(
+    algorithm: &AlgorithmIdentifierOwned,
+    data: &[u8],
+    password: &str,
+) -> Result<Vec<u8>, X509Error> {
+    let (cipher, key_len) = match algorithm.oid {
+        PKCS_12_PBE_WITH_SHAAND3_KEY_TRIPLE_DES_CBC => ("3des", 24),
+        PKCS_12_PBE_WITH_SHAAND2_KEY_TRIPLE_DES_CBC => ("3des", 16),
+        PKCS_12_PBE_WITH_SHAAND128_BIT_RC2_CBC => ("rc2", 16),
+        PKCS_12_PBEWITH_SHAAND40_BIT_RC2_CBC => ("rc2", 5),
+        _ => {
+            // PBES2 takes the password as UTF-8, not BMPString
+            let algorithm = algorithm.to_der()?;
+            let scheme = pkcs5::EncryptionScheme::try_from(algorithm.as_slice())
+                .map_err(|e| X509Error::UnsupportedPbe(e.to_string()))?;
+            if let pkcs5::EncryptionScheme::Pbes2(params) = &scheme {
+                check_pbes2(params)?;
+            }
+            return scheme
+                .decrypt(password, data)
+                .or(Err(X509Error::InvalidPassword));
+        }
+    };
+
+    let params = algorithm
+        .parameters
+        .as_ref()
+        .ok_or_else(|| X509Error::UnsupportedPbe(oid_name(&algorithm.oid)))?
+        .decode_as::<Pkcs12PbeParams>()?;
+    let iterations = check_iterations(params.iterations)?;
+    let derive =
+        |id, len| derive_key_utf8::<Sha1>(password, params.salt.as_bytes(), id, iterations, len);
+    let key = derive(Pkcs12KeyType::EncryptionKey, key_len)?;
+    let iv = derive(Pkcs12KeyType::Iv, 8)?;
+
+    let mut data = data.to_vec();
+    let decrypted = block_decrypt(cipher, &mut data, &key, &iv, "cbc", "pkcs7")
+        .or(Err(X509Error::InvalidPassword))?;
+    Ok(decrypted.into_owned())
+}
+
+fn encrypt(data: &[u8], password: &str) -> Result<(AlgorithmIdentifierOwned, Vec<u8>), X509Error> {
+    let salt = random_bytes(SALT_LEN);
+    let iv: [u8; 16] = random_bytes(16).try_into().unwrap();
+
+    let params = pbes2::Parameters::pbkdf2_sha256_aes256cbc(ITERATIONS, &salt, &iv)
+        .map_err(|e| X509Error::Pkcs5(e.to_string()))?;
+    let encrypted = params
+        .encrypt(password, data)
+        .map_err(|e| X509Error::Pkcs5(e.to_string()))?;
+    let algorithm = pkcs5::EncryptionScheme::from(params).to_der()?;
+
+    Ok((AlgorithmIdentifierOwned::from_der(&algorithm)?, encrypted))
+}
+
+fn data_content(content_info: &ContentInfo) -> Result<Vec<u8>, X509Error> {
+    Ok(content_info
+        .content
+        .decode_as::<OctetString>()?
+        .into_bytes())
+}
+
+fn data_content_info(data: &[u8]) -> Result<ContentInfo, X509Error> {
+    Ok(ContentInfo {
+        content_type: rfc5911::ID_DATA,
+        content: Any::encode_from(&OctetString::new(data)?)?,
+    })
+}
+
+/// PEM chain or a single base64 (or prefixed) DER certificate
+fn parse_certs(certs: &str) -> Result<Vec<Certificate>, X509Error> {
+    let certs = certs.trim();
+    let certs = match certs.starts_with("-----") {
+        true => {
+            Certificate::load_pem_chain(certs.as_bytes()).or(Err(X509Error::InvalidCertificate))?
+        }
+        false => {
+            let der = encoding::decode(certs).or(Err(X509Error::InvalidCertificate))?;
+            vec![Certificate::from_der(&der).or(Err(X509Error::InvalidCertificate))?]
+        }
+    };
+    match certs.is_empty() {
+        true => Err(X509Error::InvalidCertificate),
+        false => Ok(certs),
+    }
+}
+
+/// Returns {"key": PEM, "certs": [PEM, ...], "friendly_name": ...}.
+/// The certificate of the key goes first, key is PKCS#8 and loads in rsa/ecc functions
+pub fn pkcs12_parse(data: &[u8], password: &str) -> Result<String, X509Error> {
+    let pfx = Pfx::from_der(data).map_err(|e| X509Error::InvalidPkcs12(e.to_string()))?;
+    if pfx.auth_safe.content_type != rfc5911::ID_DATA {
+        return Err(X509Error::InvalidPkcs12(
+            "public key integrity mode is not supported".to_owned(),
+        ));
+    }
+    let auth_safe = data_content(&pfx.auth_safe)?;
+
+    if let Some(mac_data) = &pfx.mac_data {
+        let expected = mac(
+            &auth_safe,
+            password,
+            mac_data.mac_salt.as_bytes(),
+            mac_data.iterations,
+            &mac_data.mac.algorithm.oid,
+        )?;
+        if !constant_time_eq(&expected, mac_data.mac.digest.as_bytes()) {
+            return Err(X509Error::InvalidPassword);
+        }
+    }
+
+    let mut keys = Vec::new();
+    let mut certs = Vec::new();
+
+    for content_info in Vec::<ContentInfo>::from_der(&auth_safe)? {
+        let safe_contents = match content_info.content_type {
+            rfc5911::ID_DATA => data_content(&content_info)?,
+            rfc5911::ID_ENCRYPTED_DATA => {
+                let encrypted = content_info
+                    .content
+                    .decode_as::<EncryptedData>()?
+                    .enc_content_info;
+                let data = encrypted
+                    .encrypted_content
+                    .ok_or_else(|| X509Error::InvalidPkcs12("no encrypted content".to_owned()))?;
+                decrypt(&encrypted.content_enc_alg, data.as_bytes(), password)?
+            }
+            oid => {
+                return Err(X509Error::InvalidPkcs12(format!(
+                    "unsupported content type {}",
+                    oid_name(&oid)
+                )))
+            }
+        };
+
+        // bag_value is decoded together with its [0] tag
+        for bag in SafeContents::from_der(&safe_contents)? {
+            let attributes = BagAttributes::parse(&bag.bag_attributes);
+            match bag.bag_id {
+                PKCS_12_PKCS8_KEY_BAG_OID => {
+                    let info =
+                        ContextSpecific::<EncryptedPrivateKeyInfo>::from_der(&bag.bag_value)?.value;
+                    let key = decrypt(
+                        &info.encryption_algorithm,
+                        info.encrypted_data.as_bytes(),
+                        password,
+                    )?;
+                    keys.push((key, attributes));
+                }
+                PKCS_12_KEY_BAG_OID => {
+                    let key = ContextSpecific::<Any>::from_der(&bag.bag_value)?
+                        .value
+                        .to_der()?;
+                    keys.push((key, attributes));
+                }
+                PKCS_12_CERT_BAG_OID => {
+                    let cert_bag = ContextSpecific::<CertBag>::from_der(&bag.bag_value)?.value;
+                    if cert_bag.cert_id == PKCS_12_X509_CERT_OID {
+                        let cert = Certificate::from_der(cert_bag.cert_value.as_bytes())?;
+                        certs.push((cert, attributes));
+                    }
+                }
+                // CRLs, secrets and nested safe contents are ignored
+                _ => (),
+            }
+        }
+    }
+
+    let (key, key_attributes) = keys.into_iter().next().unzip();
+    let key_attributes = key_attributes.unwrap_or_default();
+
+    if let Some(local_key_id) = &key_attributes.local_key_id {
+        if let Some(position) = certs
+            .iter()
+            .position(|(_, attributes)| attributes.local_key_id.as_ref() == Some(local_key_id))
+        {
+            let leaf = certs.remove(position);
+            certs.insert(0, leaf);
+        }
+    }
+
+    let friendly_name = key_attributes.friendly_name.or_else(|| {
+        certs
+            .first()
+            .and_then(|(_, attributes)| attributes.friendly_name.clone())
+    });
+    let key = key
+        .map(|key| pem::encode_string("PRIVATE KEY", LineEnding::LF, &key))
+        .transpose()
+        .map_err(x509_cert::der::Error::from)?;
+    let certs = certs
+        .iter()
+        .map(|(cert, _)| cert.to_pem(LineEnding::LF))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(json!({
+        "key": key,
+        "certs": certs,
+        "friendly_name": friendly_name,
+    })
+    .to_string())
+}
+
+/// certs is a PEM chain starting with the certificate of the key.
+/// Returns DER, encrypted the same way as OpenSSL 3 does by default
+pub fn pkcs12_build(
+    key: &str,
+    certs: &str,
+    password: &str,
+    friendly_name: &str,
+) -> Result<Vec<u8>, X509Error> {
+    let certs = parse_certs(certs)?;
+    let (signing_key, public_key) = SigningKey::parse(key)?;
+    if public_key != certs[0].tbs_certificate.subject_public_key_info {
+        return Err(X509Error::KeyMismatch);
+    }
+    let local_key_id = make_hash(certs[0].to_der()?, "sha1")?;
+    let attributes = BagAttributes::encode(&local_key_id, friendly_name)?;
+
+    let mut cert_bags = SafeContents::new();
+    for (i, cert) in certs.iter().enumerate() {
+        let cert_bag = CertBag {
+            cert_id: PKCS_12_X509_CERT_OID,
+            cert_value: OctetString::new(cert.to_der()?)?,
+        };
+        cert_bags.push(SafeBag {
+            bag_id: PKCS_12_CERT_BAG_OID,
+            bag_value: cert_bag.to_der()?,
+            bag_attributes: (i == 0).then(|| attributes.clone()),
+        });
+    }
+    let (algorithm, encrypted) = encrypt(&cert_bags.to_der()?, password)?;
+    let cert_data = EncryptedData {
+        version: CmsVersion::V0,
+        enc_content_info: EncryptedContentInfo {
+            content_type: rfc5911::ID_DATA,
+            content_enc_alg: algorithm,
+            encrypted_content: Some(OctetString::new(encrypted)?),
+        },
+        unprotected_attrs: None,
+    };
+
+    let (algorithm, encrypted) = encrypt(&signing_key.to_pkcs8_der()?, password)?;
+    let key_info = EncryptedPrivateKeyInfo {
+        encryption_algorithm: algorithm,
+        encrypted_data: OctetString::new(encrypted)?,
+    };
+    let key_bags: SafeContents = vec![SafeBag {
+        bag_id: PKCS_12_PKCS8_KEY_BAG_OID,
+        bag_value: key_info.to_der()?,
+        bag_attributes: Some(attributes),
+    }];
+
+    let auth_safe = vec![
+        ContentInfo {
+            content_type: rfc5911::ID_ENCRYPTED_DATA,
+            content: Any::encode_from(&cert_data)?,
+        },
+        data_content_info(&key_bags.to_der()?)?,
+    ]
+    .to_der()?;
+
+    let mac_salt = random_bytes(SALT_LEN);
+    let digest = mac(
+        &auth_safe,
+        password,
+        &mac_salt,
+        ITERATIONS as i32,
+        &rfc5912::ID_SHA_256,
+    )?;
+    let mac_data = MacData {
+        mac: DigestInfo {
+            algorithm: AlgorithmIdentifierOwned {
+                oid: rfc5912::ID_SHA_256,
+                parameters: Some(Any::null()),
+            },
+            digest: OctetString::new(digest)?,
+        },
+        mac_salt: OctetString::new(mac_salt)?,
+        iterations: ITERATIONS as i32,
+    };
+
+    let pfx = Pfx {
+        version: Version::V3,
+        auth_safe: data_content_info(&auth_safe)?,
+        mac_data: Some(mac_data),
+    };
+    Ok(pfx.to_der()?)
+}
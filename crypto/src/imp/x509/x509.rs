@@ -0,0 +1,395 @@
+use std::{net::IpAddr, str::FromStr, time::Duration};
+
+use rsa::{
+    pkcs8::{EncodePrivateKey, EncodePublicKey},
+    PublicKeyParts,
+};
+use serde_json::{json, Value};
+use x509_cert::{
+    attr::Attribute,
+    certificate::{TbsCertificate, Version},
+    der::{
+        asn1::{BitString, Ia5String, ObjectIdentifier, OctetString},
+        oid::{
+            db::{rfc5912, rfc8410, DB},
+            AssociatedOid,
+        },
+        Any, Decode, DecodePem, Encode, EncodePem,
+    },
+    ext::{
+        pkix::{name::GeneralName, BasicConstraints, SubjectAltName, SubjectKeyIdentifier},
+        Extension,
+    },
+    name::Name,
+    request::{CertReq, CertReqInfo, ExtensionReq},
+    serial_number::SerialNumber,
+    spki::{AlgorithmIdentifierOwned, SubjectPublicKeyInfoOwned},
+    time::Validity,
+    Certificate,
+};
+
+use crate::{
+    imp::{
+        ecc::{ec_private_key, ec_public_key, ec_sign},
+        encryption::{rsa_sign, RsaKey},
+        hashing::make_hash,
+        tool::random::random_bytes,
+    },
+    utils::{base64, encoding},
+};
+
+use super::error::X509Error;
+
+pub const DEFAULT_DAYS: u32 = 365;
+
+const EC_CURVES: [&str; 4] = ["p256", "p384", "secp256k1", "ed25519"];
+
+/// Private key used to sign certificates and requests
+pub(super) enum SigningKey<'a> {
+    Rsa(&'a str),
+    /// curve name as accepted by ecc functions
+    Ec(&'a str, &'a str),
+}
+
+impl<'a> SigningKey<'a> {
+    /// RSA is tried first, then every supported curve. A key accepted by more than one curve
+    /// (raw 32 bytes scalar) is rejected, PEM, DER and JWK name the curve
+    pub(super) fn parse(key: &'a str) -> Result<(Self, SubjectPublicKeyInfoOwned), X509Error> {
+        if let Ok(RsaKey::Private(priv_key)) = RsaKey::parse(key) {
+            let public_key = priv_key
+                .to_public_key()
+                .to_public_key_der()
+                .or(Err(X509Error::InvalidKey))?;
+            let spki = SubjectPublicKeyInfoOwned::from_der(public_key.as_bytes())?;
+            return Ok((SigningKey::Rsa(key), spki));
+        }
+
+        let mut found = None;
+        for curve in EC_CURVES {
+            if let Ok(public_key) = ec_public_key(key, curve, "der") {
+                if found.is_some() {
+                    return Err(X509Error::AmbiguousKey);
+                }
+                found = Some((curve, public_key));
+            }
+        }
+
+        let (curve, public_key) = found.ok_or(X509Error::InvalidKey)?;
+        let public_key = base64::decode(public_key).or(Err(X509Error::InvalidKey))?;
+        let spki = SubjectPublicKeyInfoOwned::from_der(&public_key)?;
+        Ok((SigningKey::Ec(key, curve), spki))
+    }
+
+    /// Unencrypted PKCS#8 DER
+    pub(super) fn to_pkcs8_der(&self) -> Result<Vec<u8>, X509Error> {
+        let der = match self {
+            SigningKey::Rsa(key) => match RsaKey::parse(key)? {
+                RsaKey::Private(priv_key) => priv_key
+                    .to_pkcs8_der()
+                    .or(Err(X509Error::InvalidKey))?
+                    .as_bytes()
+                    .to_vec(),
+                RsaKey::Public(_) => return Err(X509Error::InvalidKey),
+            },
+            SigningKey::Ec(key, curve) => {
+                base64::decode(ec_private_key(key, curve, "der")?).or(Err(X509Error::InvalidKey))?
+            }
+        };
+        Ok(der)
+    }
+
+    /// Empty hash_type means sha256 (sha384 for p384), ignored for ed25519
+    fn hash_type<'b>(&self, hash_type: &'b str) -> &'b str {
+        match (self, hash_type) {
+            (SigningKey::Ec(_, "p384"), "") => "sha384",
+            (_, "") => "sha256",
+            _ => hash_type,
+        }
+    }
+
+    fn algorithm(&self, hash_type: &str) -> Result<AlgorithmIdentifierOwned, X509Error> {
+        let unsupported = || X509Error::UnsupportedHash(hash_type.to_owned());
+
+        let (oid, parameters) = match self {
+            SigningKey::Rsa(_) => {
+                let oid = match hash_type {
+                    "sha1" => rfc5912::SHA_1_WITH_RSA_ENCRYPTION,
+                    "sha224" => rfc5912::SHA_224_WITH_RSA_ENCRYPTION,
+                    "sha256" => rfc5912::SHA_256_WITH_RSA_ENCRYPTION,
+                    "sha384" => rfc5912::SHA_384_WITH_RSA_ENCRYPTION,
+                    "sha512" => rfc5912::SHA_512_WITH_RSA_ENCRYPTION,
+                    _ => return Err(unsupported()),
+                };
+                // RFC 4055: parameters MUST be NULL for PKCS#1 v1.5
+                (oid, Some(Any::null()))
+            }
+            SigningKey::Ec(_, "ed25519") => (rfc8410::ID_ED_25519, None),
+            SigningKey::Ec(..) => {
+                let oid = match hash_type {
+                    "sha224" => rfc5912::ECDSA_WITH_SHA_224,
+                    "sha256" => rfc5912::ECDSA_WITH_SHA_256,
+                    "sha384" => rfc5912::ECDSA_WITH_SHA_384,
+                    "sha512" => rfc5912::ECDSA_WITH_SHA_512,
+                    _ => return Err(unsupported()),
+                };
+                (oid, None)
+            }
+        };
+
+        Ok(AlgorithmIdentifierOwned { oid, parameters })
+    }
+
+    fn sign(&self, data: &[u8], hash_type: &str) -> Result<BitString, X509Error> {
+        let signature = match self {
+            SigningKey::Rsa(key) => rsa_sign(data, key, hash_type, "pkcs1")?,
+            SigningKey::Ec(key, "ed25519") => ec_sign(data, key, "ed25519", "", "")?,
+            SigningKey::Ec(key, curve) => ec_sign(data, key, curve, hash_type, "der")?,
+        };
+        Ok(BitString::from_bytes(&signature)?)
+    }
+}
+
+fn parse_subject(subject: &str) -> Result<Name, X509Error> {
+    let subject = subject.trim();
+    if subject.is_empty() {
+        return Err(X509Error::InvalidSubject("empty".to_owned()));
+    }
+
+    // plain "example.com" is a shortcut for "CN=example.com"
+    let subject = match subject.contains('=') {
+        true => subject.to_owned(),
+        false => format!("CN={}", subject),
+    };
+    Name::from_str(&subject).map_err(|e| X509Error::InvalidSubject(e.to_string()))
+}
+
+/// Comma separated, each entry is an IP address, email, URI or DNS name
+fn parse_sans(sans: &str) -> Result<Option<SubjectAltName>, X509Error> {
+    let mut names = Vec::new();
+
+    for san in sans.split(',').map(str::trim).filter(|san| !san.is_empty()) {
+        let name = if let Ok(ip) = IpAddr::from_str(san) {
+            let ip = match ip {
+                IpAddr::V4(ip) => ip.octets().to_vec(),
+                IpAddr::V6(ip) => ip.octets().to_vec(),
+            };
+            GeneralName::IpAddress(OctetString::new(ip)?)
+        } else if san.contains("://") {
+            GeneralName::UniformResourceIdentifier(Ia5String::new(san)?)
+        } else if san.contains('@') {
+            GeneralName::Rfc822Name(Ia5String::new(san)?)
+        } else {
+            GeneralName::DnsName(Ia5String::new(san)?)
+        };
+        names.push(name);
+    }
+
+    match names.is_empty() {
+        true => Ok(None),
+        false => Ok(Some(SubjectAltName(names))),
+    }
+}
+
+fn extension<T: AssociatedOid + Encode>(value: &T, critical: bool) -> Result<Extension, X509Error> {
+    Ok(Extension {
+        extn_id: T::OID,
+        critical,
+        extn_value: OctetString::new(value.to_der()?)?,
+    })
+}
+
+/// PKCS#10 request, SANs are put into the extensionRequest attribute.
+/// Returns PEM
+pub fn x509_csr(
+    key: &str,
+    subject: &str,
+    sans: &str,
+    hash_type: &str,
+) -> Result<String, X509Error> {
+    let (signing_key, public_key) = SigningKey::parse(key)?;
+    let hash_type = signing_key.hash_type(hash_type);
+    let algorithm = signing_key.algorithm(hash_type)?;
+
+    let mut info = CertReqInfo {
+        version: Default::default(),
+        subject: parse_subject(subject)?,
+        public_key,
+        attributes: Default::default(),
+    };
+    if let Some(sans) = parse_sans(sans)? {
+        let extensions = ExtensionReq(vec![extension(&sans, false)?]);
+        info.attributes.insert(Attribute::try_from(extensions)?)?;
+    }
+
+    let signature = signing_key.sign(&info.to_der()?, hash_type)?;
+    let csr = CertReq {
+        info,
+        algorithm,
+        signature,
+    };
+
+    Ok(csr.to_pem(Default::default())?)
+}
+
+/// Self-signed v3 certificate valid from now for `days`, marked as CA.
+/// Returns PEM
+pub fn x509_self_signed(
+    key: &str,
+    subject: &str,
+    sans: &str,
+    days: u32,
+    hash_type: &str,
+) -> Result<String, X509Error> {
+    if days == 0 {
+        return Err(X509Error::InvalidDays(days));
+    }
+
+    let (signing_key, public_key) = SigningKey::parse(key)?;
+    let hash_type = signing_key.hash_type(hash_type);
+    let algorithm = signing_key.algorithm(hash_type)?;
+    let subject = parse_subject(subject)?;
+
+    // positive 128 bit serial
+    let mut serial = random_bytes(16);
+    serial[0] &= 0x7f;
+
+    let key_id = make_hash(public_key.subject_public_key.raw_bytes(), "sha1")?;
+    let mut extensions = vec![
+        extension(
+            &BasicConstraints {
+                ca: true,
+                path_len_constraint: None,
+            },
+            true,
+        )?,
+        extension(&SubjectKeyIdentifier(OctetString::new(key_id)?), false)?,
+    ];
+    if let Some(sans) = parse_sans(sans)? {
+        extensions.push(extension(&sans, false)?);
+    }
+
+    let tbs_certificate = TbsCertificate {
+        version: Version::V3,
+        serial_number: SerialNumber::new(&serial)?,
+        signature: algorithm.clone(),
+        issuer: subject.clone(),
+        validity: Validity::from_now(Duration::from_secs(days as u64 * 24 * 60 * 60))?,
+        subject,
+        subject_public_key_info: public_key,
+        issuer_unique_id: None,
+        subject_unique_id: None,
+        extensions: Some(extensions),
+    };
+
+    let signature = signing_key.sign(&tbs_certificate.to_der()?, hash_type)?;
+    let certificate = Certificate {
+        tbs_certificate,
+        signature_algorithm: algorithm,
+        signature,
+    };
+
+    Ok(certificate.to_pem(Default::default())?)
+}
+
+/// Name from the OID database (e.g. sha256WithRSAEncryption), dotted OID otherwise
+pub(super) fn oid_name(oid: &ObjectIdentifier) -> String {
+    DB.by_oid(oid)
+        .map(str::to_owned)
+        .unwrap_or_else(|| oid.to_string())
+}
+
+fn public_key_info(spki: &SubjectPublicKeyInfoOwned) -> Result<Value, X509Error> {
+    let der = spki.to_der()?;
+    let pem = spki.to_pem(Default::default())?;
+
+    let info = match spki.algorithm.oid {
+        rfc5912::RSA_ENCRYPTION => {
+            let bits = RsaKey::parse(&base64::encode(&der))
+                .map(|key| key.to_public_key().n().bits())
+                .unwrap_or_default();
+            json!({ "algorithm": "rsa", "bits": bits, "pem": pem })
+        }
+        rfc5912::ID_EC_PUBLIC_KEY => {
+            let curve = spki
+                .algorithm
+                .parameters
+                .as_ref()
+                .and_then(|parameters| parameters.decode_as::<ObjectIdentifier>().ok())
+                .map(|curve| oid_name(&curve));
+            json!({ "algorithm": "ec", "curve": curve, "pem": pem })
+        }
+        rfc8410::ID_ED_25519 => json!({ "algorithm": "ed25519", "pem": pem }),
+        rfc8410::ID_X_25519 => json!({ "algorithm": "x25519", "pem": pem }),
+        oid => json!({ "algorithm": oid_name(&oid), "pem": pem }),
+    };
+    Ok(info)
+}
+
+fn general_name(name: &GeneralName) -> String {
+    match name {
+        GeneralName::DnsName(dns) => format!("DNS:{}", dns),
+        GeneralName::Rfc822Name(email) => format!("email:{}", email),
+        GeneralName::UniformResourceIdentifier(uri) => format!("URI:{}", uri),
+        GeneralName::IpAddress(ip) => {
+            let ip = match ip.as_bytes().len() {
+                4 => <[u8; 4]>::try_from(ip.as_bytes()).map(IpAddr::from).ok(),
+                16 => <[u8; 16]>::try_from(ip.as_bytes()).map(IpAddr::from).ok(),
+                _ => None,
+            };
+            match ip {
+                Some(ip) => format!("IP:{}", ip),
+                None => "IP:<invalid>".to_owned(),
+            }
+        }
+        GeneralName::DirectoryName(name) => format!("DirName:{}", name),
+        GeneralName::RegisteredId(oid) => format!("RID:{}", oid),
+        GeneralName::OtherName(other) => format!("othername:{}", other.type_id),
+        GeneralName::EdiPartyName(_) => "EdiPartyName:<unsupported>".to_owned(),
+    }
+}
+
+/// Accepts PEM or base64 (or prefixed) DER. Returns subject, issuer, SANs, validity,
+/// public key and sha1/sha256 fingerprints as JSON
+pub fn x509_parse(cert: &str) -> Result<String, X509Error> {
+    let cert = cert.trim();
+    let certificate = match cert.starts_with("-----") {
+        true => Certificate::from_pem(cert).or(Err(X509Error::InvalidCertificate))?,
+        false => {
+            let der = encoding::decode(cert).or(Err(X509Error::InvalidCertificate))?;
+            Certificate::from_der(&der).or(Err(X509Error::InvalidCertificate))?
+        }
+    };
+    let der = certificate.to_der()?;
+    let tbs = &certificate.tbs_certificate;
+
+    let sans = tbs
+        .get::<SubjectAltName>()?
+        .map(|(_, sans)| sans.0.iter().map(general_name).collect::<Vec<_>>())
+        .unwrap_or_default();
+    let is_ca = tbs
+        .get::<BasicConstraints>()?
+        .map(|(_, constraints)| constraints.ca)
+        .unwrap_or_default();
+
+    let validity = &tbs.validity;
+    let parsed = json!({
+        "version": tbs.version as u8 + 1,
+        "serial": hex::encode(tbs.serial_number.as_bytes()),
+        "subject": tbs.subject.to_string(),
+        "issuer": tbs.issuer.to_string(),
+        "not_before": validity.not_before.to_date_time().to_string(),
+        "not_after": validity.not_after.to_date_time().to_string(),
+        "not_before_unix": validity.not_before.to_unix_duration().as_secs(),
+        "not_after_unix": validity.not_after.to_unix_duration().as_secs(),
+        "sans": sans,
+        "is_ca": is_ca,
+        "signature_algorithm": oid_name(&certificate.signature_algorithm.oid),
+        "public_key": public_key_info(&tbs.subject_public_key_info)?,
+        "fingerprints": {
+            "sha1": hex::encode(make_hash(&der, "sha1")?),
+            "sha256": hex::encode(make_hash(&der, "sha256")?),
+        },
+    });
+
+    Ok(parsed.to_string())
+}
@@ -1,21 +1,20 @@
-#![feature(try_trait_v2)]
 #![feature(exhaustive_patterns)]
-#![feature(trace_macros)]
+// modules like handlers/ecc/ecc.rs are laid out on purpose
+#![allow(clippy::module_inception)]
 
+#[cfg(windows)]
 mod dllmain;
 mod handlers;
 mod imp;
 mod utils;
-mod wstring;
-
-use std::sync::atomic::AtomicBool;
 
 pub use crate::utils::*;
 
-pub const ERR: &str = "ERR|";
-pub const DEBUG: bool = option_env!("DLL_DEBUG").is_some();
-
 #[global_allocator]
 static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 
-pub static CONSOLE_OPEN: AtomicBool = AtomicBool::new(false);
+pk_ffi::info! {
+    author: "_Skill_",
+    version: "0.7",
+    description: "Много полезных функций шифрования/хэширования",
+}
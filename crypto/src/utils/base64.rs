@@ -1,7 +1,10 @@
 use base64::{
     alphabet,
     engine::{
-        general_purpose::{NO_PAD, STANDARD as BASE64},
+        general_purpose::{
+            NO_PAD, STANDARD as BASE64, URL_SAFE as BASE64_URL_PADDED,
+            URL_SAFE_NO_PAD as BASE64_URL,
+        },
         GeneralPurpose,
     },
     DecodeError, Engine,
@@ -20,3 +23,16 @@ pub fn decode<T: AsRef<[u8]>>(input: T) -> Result<Vec<u8>, DecodeError> {
 pub fn decode_bcrypt<T: AsRef<[u8]>>(input: T) -> Result<Vec<u8>, DecodeError> {
     BCRYPT.decode(input)
 }
+
+pub fn encode_url<T: AsRef<[u8]>>(input: T) -> String {
+    BASE64_URL.encode(input)
+}
+
+/// With `=` padding, as Python's urlsafe_b64encode
+pub fn encode_url_padded<T: AsRef<[u8]>>(input: T) -> String {
+    BASE64_URL_PADDED.encode(input)
+}
+
+pub fn decode_url<T: AsRef<[u8]>>(input: T) -> Result<Vec<u8>, DecodeError> {
+    BASE64_URL.decode(input)
+}
@@ -0,0 +1,128 @@
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use super::base64;
+
+#[derive(Error, Debug)]
+pub enum EncodingError {
+    #[error(
+        "invalid encoding: {0}. Only utf8, hex, base64, base64url, base64url_pad, latin1 accepted"
+    )]
+    InvalidEncoding(String),
+    #[error("invalid {0} input: {1}")]
+    InvalidInput(&'static str, String),
+    #[error("result is not valid utf8, use another output encoding")]
+    InvalidUtf8Output,
+}
+
+/// Encoding of binary arguments and results in handlers.
+/// Arguments pick it with a prefix (`hex:00ff`, `utf8:text`), results with a prefix
+/// on the first argument (`hex>...`, see [`output_prefix`]). Base64 is the default
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Base64,
+    Base64Url,
+    Base64UrlPadded,
+    Hex,
+    Utf8,
+    Latin1,
+}
+
+impl FromStr for Encoding {
+    type Err = EncodingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let encoding = match s {
+            "base64" | "" => Encoding::Base64,
+            "base64url" => Encoding::Base64Url,
+            "base64url_pad" => Encoding::Base64UrlPadded,
+            "hex" => Encoding::Hex,
+            "utf8" => Encoding::Utf8,
+            "latin1" => Encoding::Latin1,
+            _ => return Err(EncodingError::InvalidEncoding(s.to_owned())),
+        };
+        Ok(encoding)
+    }
+}
+
+impl Encoding {
+    pub fn name(self) -> &'static str {
+        match self {
+            Encoding::Base64 => "base64",
+            Encoding::Base64Url => "base64url",
+            Encoding::Base64UrlPadded => "base64url_pad",
+            Encoding::Hex => "hex",
+            Encoding::Utf8 => "utf8",
+            Encoding::Latin1 => "latin1",
+        }
+    }
+
+    pub fn decode(self, input: &str) -> Result<Vec<u8>, EncodingError> {
+        // wrapped lines (`base64 file`) and spaced hex dumps are accepted
+        let compact = || input.split_whitespace().collect::<String>();
+        match self {
+            Encoding::Base64 => base64::decode(compact()).map_err(|e| self.invalid(e)),
+            // padding is optional in urlsafe input
+            Encoding::Base64Url | Encoding::Base64UrlPadded => {
+                base64::decode_url(compact().trim_end_matches('=')).map_err(|e| self.invalid(e))
+            }
+            Encoding::Hex => hex::decode(compact()).map_err(|e| self.invalid(e)),
+            Encoding::Utf8 => Ok(input.as_bytes().to_vec()),
+            Encoding::Latin1 => input
+                .chars()
+                .map(|c| u8::try_from(c).map_err(|_| self.invalid(format!("character {c:?}"))))
+                .collect(),
+        }
+    }
+
+    fn invalid(self, error: impl ToString) -> EncodingError {
+        EncodingError::InvalidInput(self.name(), error.to_string())
+    }
+
+    pub fn encode(self, data: &[u8]) -> Result<String, EncodingError> {
+        let encoded = match self {
+            Encoding::Base64 => base64::encode(data),
+            Encoding::Base64Url => base64::encode_url(data),
+            Encoding::Base64UrlPadded => base64::encode_url_padded(data),
+            Encoding::Hex => hex::encode(data),
+            Encoding::Utf8 => {
+                String::from_utf8(data.to_vec()).or(Err(EncodingError::InvalidUtf8Output))?
+            }
+            Encoding::Latin1 => data.iter().map(|&b| b as char).collect(),
+        };
+        Ok(encoded)
+    }
+}
+
+/// Binary argument of a handler: `<encoding>:<value>` or plain base64
+pub fn decode<T: AsRef<str>>(input: T) -> Result<Vec<u8>, EncodingError> {
+    let input = input.as_ref();
+    // ':' is never part of base64, so a prefix can't be confused with data
+    match input.split_once(':') {
+        Some((prefix, value)) => Encoding::from_str(prefix)?.decode(value),
+        None => Encoding::Base64.decode(input),
+    }
+}
+
+/// Result encoding picked per call with `<encoding>>` in front of the first argument
+/// (`hex>sha256`, `base64url>32`). Returns base64 and the argument as is without one.
+/// '>' is never part of base64, hex, numbers or names, so it can't be confused with data
+pub fn output_prefix(arg: String) -> Result<(Encoding, String), EncodingError> {
+    match arg.split_once('>') {
+        Some((prefix, value))
+            if !prefix.is_empty()
+                && prefix
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_') =>
+        {
+            Ok((Encoding::from_str(prefix)?, value.to_owned()))
+        }
+        _ => Ok((Encoding::Base64, arg)),
+    }
+}
+
+/// Binary result of a handler in the encoding from [`output_prefix`]
+pub fn encode<T: AsRef<[u8]>>(data: T, output: Encoding) -> Result<String, EncodingError> {
+    output.encode(data.as_ref())
+}
@@ -0,0 +1,14 @@
+use subtle::ConstantTimeEq;
+
+/// Empty parameters from Keeper mean the default
+pub fn or_default<'a>(value: &'a str, default: &'a str) -> &'a str {
+    match value.is_empty() {
+        true => default,
+        false => value,
+    }
+}
+
+/// For MACs and codes, the time does not depend on where the first difference is
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.ct_eq(b).into()
+}
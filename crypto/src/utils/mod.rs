@@ -1,3 +1,5 @@
 pub mod base64;
-pub mod macros;
+pub mod encoding;
+pub mod helpers;
+#[cfg(windows)]
 pub(crate) mod panic_hook;
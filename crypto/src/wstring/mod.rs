@@ -1,3 +0,0 @@
-mod imp;
-pub use imp::LPCWSTR;
-pub use imp::{FromWidechar, ToWidechar};
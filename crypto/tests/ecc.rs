@@ -0,0 +1,89 @@
+mod common;
+
+use common::{is_err, P256_PRIVATE, P256_PUBLIC};
+
+exports! {
+    ec_generate(curve_ptr, format_ptr);
+    ec_public_key(key_ptr, curve_ptr, format_ptr);
+    ec_sign(data_ptr, key_ptr, curve_ptr, hash_type_ptr, signature_format_ptr);
+    ec_verify(data_ptr, signature_ptr, key_ptr, curve_ptr, hash_type_ptr);
+    ecdh(private_key_ptr, public_key_ptr, curve_ptr);
+}
+
+// "test data"
+const DATA: &str = "dGVzdCBkYXRh";
+
+#[test]
+fn generate() {
+    for curve in ["p256", "p384", "secp256k1", "ed25519", "x25519"] {
+        let generated = ec_generate(curve, "");
+        let (private, public) = generated.split_once('|').unwrap();
+        assert_eq!(ec_public_key(private, curve, ""), public, "{curve}");
+    }
+    assert!(is_err(&ec_generate("p521", "")));
+    assert!(is_err(&ec_generate("p256", "xml")));
+}
+
+#[test]
+fn ecdsa() {
+    assert_eq!(ec_public_key(P256_PRIVATE, "p256", "pem"), P256_PUBLIC);
+
+    // made with python cryptography, ECDSA signatures are randomized
+    let signature = "MEUCIQD/FXw9Zd1oCv9kjoXR36L8bOepHbUfErtCoogzacJpDgIgFUaF90JkDy3bkzvIc4nHHMu2QIaUFwbLKW7FDLME1us=";
+    assert_eq!(ec_verify(DATA, signature, P256_PUBLIC, "p256", ""), "true");
+    assert_eq!(
+        ec_verify("b3RoZXIgZGF0YQ==", signature, P256_PUBLIC, "p256", ""),
+        "false"
+    );
+
+    let signature = ec_sign(DATA, P256_PRIVATE, "p256", "sha256", "raw");
+    assert_eq!(common::decode_len(&signature), 64);
+    assert_eq!(
+        ec_verify(DATA, &signature, P256_PUBLIC, "p256", "sha256"),
+        "true"
+    );
+    assert!(is_err(&ec_sign(DATA, P256_PUBLIC, "p256", "", "")));
+}
+
+#[test]
+fn ed25519() {
+    let private = "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=";
+    let public = "O2onvM62pC1io6jQKm8Nc2UyFXcd4kOmOsBIoYtZ2ik=";
+    assert_eq!(ec_public_key(private, "ed25519", "raw"), public);
+
+    let signature = ec_sign(DATA, private, "ed25519", "", "");
+    assert_eq!(
+        signature,
+        "3GxFHsR4IfkYn8OxV4Ds0yGDY/yPDwvruCe54iVbpO6CySAJsT+7Fcmx3on8rVBclgAEXQZIKllnWDjkfHtkAw=="
+    );
+    assert_eq!(ec_verify(DATA, &signature, public, "ed25519", ""), "true");
+}
+
+#[test]
+fn key_agreement() {
+    let shared = "LtdqtUmx5zwDHrSclEjweYrqgbaYJ5oMPcPkn7/EuVM=";
+    assert_eq!(
+        ecdh(
+            "AQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQE=",
+            "zo060cy2M+x7cMF4FKXHbs0CloUFDTRHRboFhw5YfVk=",
+            "x25519"
+        ),
+        shared
+    );
+    assert_eq!(
+        ecdh(
+            "AgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgI=",
+            "pOCSkrZRwni5dyxWn1+puxPZBrRqtoyd+dwrRAn4ogk=",
+            "x25519"
+        ),
+        shared
+    );
+
+    let generated = ec_generate("p256", "");
+    let (private, public) = generated.split_once('|').unwrap();
+    assert_eq!(
+        ecdh(private, P256_PUBLIC, "p256"),
+        ecdh(P256_PRIVATE, public, "p256")
+    );
+    assert!(is_err(&ecdh(private, P256_PUBLIC, "ed25519")));
+}
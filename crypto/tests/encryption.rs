@@ -0,0 +1,474 @@
+mod common;
+
+use common::is_err;
+
+exports! {
+    aes_encrypt(data_ptr, key_ptr, iv_ptr, mode_ptr, padding_ptr);
+    aes_decrypt(data_ptr, key_ptr, iv_ptr, mode_ptr, padding_ptr);
+    aes_key_wrap(data_ptr, kek_ptr, mode_ptr);
+    aes_key_unwrap(data_ptr, kek_ptr, mode_ptr);
+    aead_encrypt(data_ptr, key_ptr, nonce_ptr, aad_ptr, mode_ptr, tag_len_ptr);
+    aead_decrypt(data_ptr, key_ptr, nonce_ptr, aad_ptr, mode_ptr, tag_len_ptr);
+    block_encrypt(cipher_ptr, data_ptr, key_ptr, iv_ptr, mode_ptr, padding_ptr);
+    block_decrypt(cipher_ptr, data_ptr, key_ptr, iv_ptr, mode_ptr, padding_ptr);
+    blowfish_encrypt(data_ptr, key_ptr, iv_ptr, mode_ptr, padding_ptr);
+    blowfish_decrypt(data_ptr, key_ptr, iv_ptr, mode_ptr, padding_ptr);
+    openssl_encrypt(data_ptr, passphrase_ptr, cipher_ptr, kdf_ptr, hash_type_ptr, rounds_ptr, salt_ptr);
+    openssl_decrypt(data_ptr, passphrase_ptr, cipher_ptr, kdf_ptr, hash_type_ptr, rounds_ptr);
+    fernet_encrypt(data_ptr, key_ptr);
+    fernet_decrypt(token_ptr, key_ptr, ttl_ptr);
+    secretbox_encrypt(data_ptr, key_ptr, nonce_ptr);
+    secretbox_decrypt(data_ptr, key_ptr, nonce_ptr);
+    box_encrypt(data_ptr, public_key_ptr, secret_key_ptr, nonce_ptr);
+    box_decrypt(data_ptr, public_key_ptr, secret_key_ptr, nonce_ptr);
+    sealed_box_encrypt(data_ptr, public_key_ptr);
+    sealed_box_decrypt(data_ptr, secret_key_ptr);
+    rc4(data_ptr, key_ptr);
+    xor(data_ptr, key_ptr);
+}
+
+// "test data"
+const DATA: &str = "dGVzdCBkYXRh";
+// 000102..0f
+const KEY: &str = "AAECAwQFBgcICQoLDA0ODw==";
+// 101112..1f
+const IV: &str = "EBESExQVFhcYGRobHB0eHw==";
+
+#[test]
+fn aes() {
+    let encrypted = aes_encrypt(DATA, KEY, IV, "cbc", "pkcs7");
+    assert_eq!(encrypted, "F+mUP2dJfu6gTrY5q08gcw==");
+    assert_eq!(aes_decrypt(&encrypted, KEY, IV, "cbc", "pkcs7"), DATA);
+
+    assert!(is_err(&aes_encrypt(DATA, "utf8:short", IV, "cbc", "pkcs7")));
+    assert!(is_err(&aes_encrypt(DATA, KEY, IV, "unknown", "pkcs7")));
+    // wrong padding after decryption
+    assert!(is_err(&aes_decrypt(&encrypted, KEY, KEY, "cbc", "pkcs7")));
+}
+
+#[test]
+fn aes_modes() {
+    // NIST SP 800-38A F.3.13 (CFB128) and F.4.1 (OFB), AES-128
+    let key = "hex:2b7e151628aed2a6abf7158809cf4f3c";
+    let iv = "hex:000102030405060708090a0b0c0d0e0f";
+    let plaintext = "hex:6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51\
+                     30c81c46a35ce411e5fbc1191a0a52eff69f2445df4f9b17ad2b417be66c3710";
+    let plaintext_b64 =
+        "a8G+4i5An5bpPX4Rc5MXKq4tilceA6ycnrdvrEWvjlEwyBxGo1zkEeX7wRkaClLv9p8kRd9PmxetK0F75mw3EA==";
+    for (mode, encrypted) in [
+        (
+            "cfb",
+            "Oz/ZLrctrSAzNEn46Dz7SsimRTegs6k/zePNrZ8c5YsmdR9no8uxQLGAjPGHpPTfwEsFNXxdHA7qxMZvn/fy5g==",
+        ),
+        (
+            "ofb",
+            "Oz/ZLrctrSAzNEn46Dz7SneJUI0WkY8D9TxS2sVO2CWXQAUenF/s9kNE96giYO3MMExlKPZZx3hmpRDZwdauXg==",
+        ),
+        // SP 800-38A has no PCBC, this is E(P[i] ^ P[i-1] ^ C[i-1]) over AES-ECB
+        (
+            "pcbc",
+            "dkmrrIEZskbO6Y6bEukZfZ6Lr/Eq1ScKDR7vk9cDeZRXALOYA3efo1o8YApJoWPAM64Znyc3nyG+bdV9KVzIfQ==",
+        ),
+    ] {
+        let padding = match mode {
+            "pcbc" => "nopadding",
+            _ => "",
+        };
+        assert_eq!(aes_encrypt(plaintext, key, iv, mode, padding), encrypted, "{mode}");
+        assert_eq!(aes_decrypt(encrypted, key, iv, mode, padding), plaintext_b64, "{mode}");
+    }
+
+    // F.3.7, 18 bytes
+    let encrypted = aes_encrypt(
+        "hex:6bc1bee22e409f96e93d7e117393172aae2d",
+        key,
+        iv,
+        "cfb8",
+        "",
+    );
+    assert_eq!(encrypted, "O3lCTJwN1Da6zp4O1FhqTzK5");
+    assert_eq!(
+        aes_decrypt(&encrypted, key, iv, "cfb8", ""),
+        "a8G+4i5An5bpPX4Rc5MXKq4t"
+    );
+
+    // block modes need the padding spelled out
+    assert!(is_err(&aes_encrypt(DATA, KEY, IV, "cbc", "")));
+}
+
+#[test]
+fn aes_key_wrapping() {
+    // RFC 3394 4.1
+    let key_data = "ABEiM0RVZneImaq7zN3u/w==";
+    let wrapped = aes_key_wrap(key_data, KEY, "");
+    assert_eq!(wrapped, "H6aLCoEStEeu80vY+1p7gp0+hiNx0s/l");
+    assert_eq!(aes_key_unwrap(&wrapped, KEY, "kw"), key_data);
+
+    let wrapped = aes_key_wrap(DATA, KEY, "kwp");
+    assert_eq!(aes_key_unwrap(&wrapped, KEY, "kwp"), DATA);
+    assert!(is_err(&aes_key_unwrap(&wrapped, IV, "kwp")));
+}
+
+#[test]
+fn aead() {
+    let nonce = "AAAAAAAAAAAAAAAA";
+    // "aad"
+    let aad = "YWFk";
+    let encrypted = aead_encrypt(DATA, KEY, nonce, aad, "gcm", "");
+    assert_eq!(encrypted, "PbP0J7n/x/iCGT8fJmrDG3HnCAjMyr5Udg==");
+    assert_eq!(aead_decrypt(&encrypted, KEY, nonce, aad, "gcm", ""), DATA);
+    // aad is authenticated too
+    assert!(is_err(&aead_decrypt(&encrypted, KEY, nonce, "", "gcm", "")));
+
+    let key = "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=";
+    let encrypted = aead_encrypt(DATA, key, nonce, "", "chacha20-poly1305", "");
+    assert_eq!(encrypted, "62KUynU1WQ75ao9etqp50VeRBzk+9M6MEg==");
+    assert_eq!(
+        aead_decrypt(&encrypted, key, nonce, "", "chacha20-poly1305", ""),
+        DATA
+    );
+
+    // "nonce123"
+    let nonce = "bm9uY2UxMjM=";
+    let encrypted = aead_encrypt(DATA, KEY, nonce, "", "ccm", "8");
+    assert_eq!(aead_decrypt(&encrypted, KEY, nonce, "", "ccm", "8"), DATA);
+
+    assert!(is_err(&aead_encrypt(
+        DATA, KEY, nonce, "", "gcm", "sixteen"
+    )));
+}
+
+#[test]
+fn block_ciphers() {
+    // FIPS 81 / Stinson example
+    assert_eq!(
+        block_encrypt(
+            "des",
+            "ASNFZ4mrze8=",
+            "EzRXeZu83/E=",
+            "",
+            "ecb",
+            "nopadding"
+        ),
+        "hegTVA8KtAU="
+    );
+
+    let des3_key = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYX";
+    for (cipher, key, iv) in [
+        ("aes", KEY, IV),
+        ("3des", des3_key, "AAECAwQFBgc="),
+        ("camellia", KEY, IV),
+        ("twofish", KEY, IV),
+    ] {
+        let encrypted = block_encrypt(cipher, DATA, key, iv, "cbc", "pkcs7");
+        assert!(!is_err(&encrypted), "{cipher}: {encrypted}");
+        assert_eq!(
+            block_decrypt(cipher, &encrypted, key, iv, "cbc", "pkcs7"),
+            DATA
+        );
+    }
+
+    assert!(is_err(&block_encrypt(
+        "rot13", DATA, KEY, IV, "cbc", "pkcs7"
+    )));
+    assert_eq!(
+        block_encrypt("des", DATA, KEY, IV, "cbc", "pkcs7"),
+        "ERR|invalid key length for des: 16"
+    );
+}
+
+#[test]
+fn xts_vectors() {
+    // IEEE 1619 vector 1 uses the same all-zero key twice, which the standard now forbids
+    let zero = "hex:0000000000000000000000000000000000000000000000000000000000000000";
+    let tweak = "hex:00000000000000000000000000000000";
+    assert_eq!(
+        aes_encrypt(zero, zero, tweak, "xts", ""),
+        "ERR|xts data key and tweak key must be different"
+    );
+
+    // IEEE 1619 vectors 2 and 15 (17 bytes, ciphertext stealing)
+    let key = "hex:1111111111111111111111111111111122222222222222222222222222222222";
+    let tweak = "hex:33333333330000000000000000000000";
+    let encrypted = "xFQYXmoWk245M0A4rO+Di/sYb/90gK3EKJOC7NbTlPA=";
+    let plaintext = "REREREREREREREREREREREREREREREREREREREREREQ=";
+    assert_eq!(aes_encrypt(plaintext, key, tweak, "xts", ""), encrypted);
+    assert_eq!(aes_decrypt(encrypted, key, tweak, "xts", ""), plaintext);
+
+    let key = "hex:fffefdfcfbfaf9f8f7f6f5f4f3f2f1f0bfbebdbcbbbab9b8b7b6b5b4b3b2b1b0";
+    let tweak = "hex:9a785634120000000000000000000000";
+    let encrypted = "bBYl20ZxUi09dZlgHefKCe0=";
+    let plaintext = "AAECAwQFBgcICQoLDA0ODxA=";
+    assert_eq!(aes_encrypt(plaintext, key, tweak, "xts", ""), encrypted);
+    assert_eq!(aes_decrypt(encrypted, key, tweak, "xts", ""), plaintext);
+
+    // OpenSSL aes-256-xts, key 00..3f, tweak a0..af, 17 and 33 bytes from 0x40
+    let key =
+        "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8gISIjJCUmJygpKissLS4vMDEyMzQ1Njc4OTo7PD0+Pw==";
+    let tweak = "oKGio6SlpqeoqaqrrK2urw==";
+    for (plaintext, encrypted) in [
+        ("QEFCQ0RFRkdISUpLTE1OT1A=", "gL5l9bRtdUrlEQtjTysYXSo="),
+        (
+            "QEFCQ0RFRkdISUpLTE1OT1BRUlNUVVZXWFlaW1xdXl9g",
+            "Kjz13ZEoTbMJQQ4ruN9L9GnulVzeJV4ns/HwangjleqV",
+        ),
+    ] {
+        assert_eq!(aes_encrypt(plaintext, key, tweak, "xts", ""), encrypted);
+        assert_eq!(aes_decrypt(encrypted, key, tweak, "xts", ""), plaintext);
+    }
+}
+
+#[test]
+fn cipher_mode_matrix() {
+    // "twenty-three bytes long", ends in a partial block for both block sizes
+    let data = "dHdlbnR5LXRocmVlIGJ5dGVzIGxvbmc=";
+    let key16 = "hex:000102030405060708090a0b0c0d0e0f";
+    let (iv8, iv16) = (
+        "hex:1011121314151617",
+        "hex:101112131415161718191a1b1c1d1e1f",
+    );
+
+    for (cipher, key, block_size) in [
+        ("aes", key16, 16),
+        ("blowfish", key16, 8),
+        ("des", "hex:0123456789abcdef", 8),
+        (
+            "3des",
+            "hex:000102030405060708090a0b0c0d0e0f1011121314151617",
+            8,
+        ),
+        ("camellia", key16, 16),
+        ("sm4", key16, 16),
+        ("twofish", key16, 16),
+        ("serpent", key16, 16),
+        ("rc2", key16, 8),
+    ] {
+        let iv = match block_size {
+            8 => iv8,
+            _ => iv16,
+        };
+        for mode in ["ecb", "cbc", "pcbc", "ctr", "cfb", "cfb8", "ofb"] {
+            let padding = match mode {
+                "ecb" | "cbc" | "pcbc" => "pkcs7",
+                _ => "",
+            };
+            let encrypted = block_encrypt(cipher, data, key, iv, mode, padding);
+            assert!(!is_err(&encrypted), "{cipher}-{mode}: {encrypted}");
+            assert_ne!(encrypted, data, "{cipher}-{mode}");
+            assert_eq!(
+                block_decrypt(cipher, &encrypted, key, iv, mode, padding),
+                data,
+                "{cipher}-{mode}"
+            );
+        }
+
+        // two different keys of the cipher's size, tweak in place of the iv
+        let xts_key = format!("{key16}f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff");
+        let encrypted = block_encrypt(cipher, data, &xts_key, iv16, "xts", "");
+        match block_size {
+            16 => assert_eq!(
+                block_decrypt(cipher, &encrypted, &xts_key, iv16, "xts", ""),
+                data,
+                "{cipher}-xts"
+            ),
+            _ => assert!(is_err(&encrypted), "{cipher}-xts: {encrypted}"),
+        }
+    }
+
+    for padding in ["pkcs7", "zero", "iso7816", "iso10126", "ansi_x923"] {
+        let encrypted = block_encrypt("aes", DATA, KEY, IV, "cbc", padding);
+        assert_eq!(
+            block_decrypt("aes", &encrypted, KEY, IV, "cbc", padding),
+            DATA,
+            "{padding}"
+        );
+    }
+}
+
+#[test]
+fn blowfish() {
+    let zero = "hex:0000000000000000";
+    assert_eq!(
+        blowfish_encrypt(zero, zero, "", "ecb", "nopadding"),
+        "TvmXRWGY3Xg="
+    );
+    assert_eq!(
+        blowfish_decrypt("TvmXRWGY3Xg=", zero, "", "ecb", "nopadding"),
+        "AAAAAAAAAAA="
+    );
+
+    let encrypted = blowfish_encrypt(DATA, KEY, zero, "cbc", "pkcs7");
+    assert_eq!(
+        blowfish_decrypt(&encrypted, KEY, zero, "cbc", "pkcs7"),
+        DATA
+    );
+}
+
+#[test]
+fn blowfish_vectors() {
+    // Eric Young's bftest.c: "7654321 Now is the time for " with its trailing zero byte,
+    // zero padded to 32 bytes for cbc. cfb8 and pcbc are built from ECB in python
+    let key = "hex:0123456789abcdeff0e1d2c3b4a59687";
+    let iv = "hex:fedcba9876543210";
+    let data = "hex:37363534333231204e6f77206973207468652074696d6520666f722000";
+    let block_data = "hex:37363534333231204e6f77206973207468652074696d6520666f722000000000";
+    for (mode, data, expected) in [
+        (
+            "cbc",
+            block_data,
+            "6b77b4d63006dee605b156e27403979358deb9e7154616d959f1652bd5ff92cc",
+        ),
+        (
+            "cfb",
+            data,
+            "e73214a2822139caf26ecf6d2eb9e76e3da3de04d1517200519d57a6c3",
+        ),
+        (
+            "ofb",
+            data,
+            "e73214a2822139ca62b343cc5b65587310dd908d0c241b2263c2cf80da",
+        ),
+        (
+            "cfb8",
+            data,
+            "e7bb1fc3073eb8314b378689fab0b1160244415d4d017fce0d9b4cb2fa",
+        ),
+        (
+            "pcbc",
+            block_data,
+            "6b77b4d63006dee660e44d1f4652ceddc902a82fc205c02e9ba40bd6a48395c6",
+        ),
+    ] {
+        assert_eq!(
+            blowfish_encrypt(&format!("hex>{data}"), key, iv, mode, "nopadding"),
+            expected,
+            "{mode}"
+        );
+        assert_eq!(
+            blowfish_decrypt(&format!("hex>hex:{expected}"), key, iv, mode, "nopadding"),
+            &data[4..],
+            "{mode}"
+        );
+    }
+}
+
+#[test]
+fn openssl() {
+    // "secret"
+    let passphrase = "c2VjcmV0";
+    let salt = "AQIDBAUGBwg=";
+    // openssl enc -aes-256-cbc -md md5 -a
+    let encrypted = openssl_encrypt(DATA, passphrase, "", "", "", "", salt);
+    assert_eq!(encrypted, "U2FsdGVkX18BAgMEBQYHCHVNfdKbQXUzTwhXhPUv9HE=");
+    assert_eq!(
+        openssl_decrypt(&encrypted, passphrase, "", "", "", ""),
+        DATA
+    );
+
+    let encrypted = openssl_encrypt(
+        DATA,
+        passphrase,
+        "aes-128-ctr",
+        "pbkdf2",
+        "sha256",
+        "1000",
+        "",
+    );
+    assert_eq!(
+        openssl_decrypt(
+            &encrypted,
+            passphrase,
+            "aes-128-ctr",
+            "pbkdf2",
+            "sha256",
+            "1000"
+        ),
+        DATA
+    );
+    assert!(is_err(&openssl_decrypt(DATA, passphrase, "", "", "", "")));
+}
+
+#[test]
+fn fernet() {
+    let key = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8=";
+    // Fernet(key)._encrypt_from_parts(b"test data", 1700000000, bytes(range(16)))
+    let token = "gAAAAABlU_EAAAECAwQFBgcICQoLDA0OD0OMFvRvQIyFXVdsSKtdilwhXYm31PZZ4lMg5ARUr9uBKxZu6OVDb0HlaJ7_72O0mw==";
+    assert_eq!(fernet_decrypt(token, key, ""), DATA);
+    assert_eq!(
+        fernet_decrypt(token, key, "60"),
+        "ERR|invalid fernet token: token has expired"
+    );
+
+    let token = fernet_encrypt(DATA, key);
+    assert!(token.starts_with("gAAAAA"));
+    assert_eq!(fernet_decrypt(&token, key, "60"), DATA);
+    // "short"
+    assert!(is_err(&fernet_decrypt(&token, "c2hvcnQ=", "")));
+}
+
+#[test]
+fn nacl() {
+    let key = "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=";
+    let nonce = "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA";
+    let encrypted = secretbox_encrypt(DATA, key, nonce);
+    assert_eq!(secretbox_decrypt(&encrypted, key, nonce), DATA);
+    assert!(is_err(&secretbox_encrypt(DATA, key, "AAAA")));
+
+    let (alice_secret, alice_public) = (
+        "AQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQE=",
+        "pOCSkrZRwni5dyxWn1+puxPZBrRqtoyd+dwrRAn4ogk=",
+    );
+    let (bob_secret, bob_public) = (
+        "AgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgI=",
+        "zo060cy2M+x7cMF4FKXHbs0CloUFDTRHRboFhw5YfVk=",
+    );
+    let encrypted = box_encrypt(DATA, bob_public, alice_secret, nonce);
+    assert_eq!(
+        box_decrypt(&encrypted, alice_public, bob_secret, nonce),
+        DATA
+    );
+    assert!(is_err(&box_decrypt(
+        &encrypted, bob_public, bob_secret, nonce
+    )));
+
+    let sealed = sealed_box_encrypt(DATA, bob_public);
+    assert_eq!(sealed_box_decrypt(&sealed, bob_secret), DATA);
+    assert!(is_err(&sealed_box_decrypt(&sealed, alice_secret)));
+}
+
+#[test]
+fn nacl_vectors() {
+    // tests/box.c and tests/secretbox.c from NaCl, checked against libsodium crypto_box_easy
+    let alice_secret = "dwdtCnMYpX08FsFyUbJmRd9ML4frwJkqsXf7pR25LCo=";
+    let alice_public = "hSDwCYkwp1R0i33ctD73Wg2/Og0mOBr066SpjqqbTmo=";
+    let bob_secret = "XasIfmJKikt54X+Lg4AO5m87sSkmGLb9HC+LJ/+I4Os=";
+    let bob_public = "3p7bfXt9wbTTW2HC7OQ1Nz+DQ8hbeGdNrfx+FG+IK08=";
+    // shared key of alice and bob (firstkey in NaCl tests)
+    let key = "GydVZHPphdRizVEZeppGx2AJVJ6sZHTyBsTuCET2g4k=";
+    let nonce = "aWlu6VW2K3PNYr2odfxz1oIZ4ANregs3";
+    let message = "vgdfxTyB8tXPFBMW6+sMe1IoxSpMYsvUS2aEm2QkT/zl7LqvM711GhrHKNRebGEpbNw8ASM1YfQdtmzOMUrbMQ476CUMRvBtzuo6f6E0gFfi9lVq1rExigJKg48hrx/eBIl360j1n/1JJMocYJAuUvCgibx2iXBA4IL5N3Y4SGReBwU=";
+    let boxed = "8//HcD+UAOUqfftLPTMF2Y6ZO59IaBJzwpZQujL8ds5IMy6nFk2WpEdvuMUxoRhqwN/BfJjc6HtNp/AR7EjJcnHSwg+bko/iJw1vuGPVFzi0ju7jFKfMirkyFkVI5SaukCJDaFF6z+q9a7NzK8Dp2pmDK2HKAbbeViRKnojV+bN5c/YipD0UplmbH2VMtFp041Wl";
+
+    assert_eq!(secretbox_encrypt(message, key, nonce), boxed);
+    assert_eq!(secretbox_decrypt(boxed, key, nonce), message);
+    assert_eq!(box_encrypt(message, bob_public, alice_secret, nonce), boxed);
+    assert_eq!(box_decrypt(boxed, alice_public, bob_secret, nonce), message);
+
+    // libsodium crypto_box_seal of "sealed by libsodium" to bob
+    let sealed = "M+p48ZsXq3OskM8dxFG0lIGqsr3XDFokdC6EM6CHBD3aNi854WnXwIf90IxVk7bD6/rx/Vm08fa5Wd8S1GlN+TWcrA==";
+    assert_eq!(
+        sealed_box_decrypt(sealed, bob_secret),
+        "c2VhbGVkIGJ5IGxpYnNvZGl1bQ=="
+    );
+    assert!(is_err(&sealed_box_decrypt(sealed, alice_secret)));
+}
+
+#[test]
+fn stream() {
+    assert_eq!(rc4("utf8:Plaintext", "utf8:Key"), "u/MW6NlArwrT");
+    assert_eq!(rc4("u/MW6NlArwrT", "utf8:Key"), "UGxhaW50ZXh0");
+
+    assert_eq!(xor("utf8:abc", "hex:01"), "YGNi");
+    let encrypted = xor(DATA, "12345");
+    assert_eq!(xor(&encrypted, "12345"), DATA);
+}
@@ -0,0 +1,310 @@
+mod common;
+
+use common::is_err;
+
+exports! {
+    hash(hash_type, data_ptr);
+    hmac(hash_type, data_ptr, key_ptr);
+    mac(mac_type, data_ptr, key_ptr, nonce_ptr);
+    hash_init(hash_type);
+    hash_update(id_ptr, data_ptr);
+    hash_final(id_ptr);
+    hmac_init(hash_type, key_ptr);
+    hmac_update(id_ptr, data_ptr);
+    hmac_final(id_ptr);
+    hash_xof(hash_type, data_ptr, output_length_ptr, customization_ptr, key_ptr);
+}
+
+#[test]
+fn hashes() {
+    assert_eq!(
+        hash("sha256", "YWJj"),
+        "ungWv48Bz+pBQUDeXa4iI7ADYaOWF3qctBD/YfIAFa0="
+    );
+    assert_eq!(hash("md5", "utf8:abc"), "kAFQmDzST7DWlj99KOF/cg==");
+    assert_eq!(
+        hash("sha3-256", "hex:616263"),
+        "Ophdp0/iJbIEXBcta9OQvYVfCG4+nVJbRr/iRRFDFTI="
+    );
+
+    assert!(is_err(&hash("sha0", "YWJj")));
+    assert!(is_err(&hash("sha256", "not base64")));
+}
+
+#[test]
+fn checksums() {
+    // "abc"
+    assert_eq!(hash("crc32", "YWJj"), "NSRBwg==");
+}
+
+#[test]
+fn blake2() {
+    // RFC 7693 appendix A and B, "abc"
+    assert_eq!(
+        hash("blake2b", "YWJj"),
+        "uoClP5gcTQ1qJ5e2nxL26UwhLxRoWsS3SxK7b9v/otF9h8U5Kqt5LcJS1d5FM8yVGNOKqNvxklq5I4bt1ACZIw=="
+    );
+    assert_eq!(
+        hash("blake2s-256", "YWJj"),
+        "UIxejDJ8FOLhpyujTutFLzdFiyCe1jopTZmbTIZnWYI="
+    );
+    // hashlib.blake2b(b"abc", digest_size=28) and so on
+    assert_eq!(
+        hash("blake2b-224", "YWJj"),
+        "m9I3sCop5DvdZzivpbU/8O7heNYhC2GORRGuyA=="
+    );
+    assert_eq!(hash("blake2b-8", "YWJj"), "aw==");
+    assert_eq!(hash("blake2s-8", "YWJj"), "DQ==");
+    assert_eq!(hash("blake2s-128", "YWJj"), "qkk4EZsdx7h8utD/0gDQrg==");
+
+    let id = hash_init("blake2b-160");
+    assert_eq!(hash_update(&id, "YWJj"), "OK");
+    assert_eq!(hash_final(&id), "OEJk9nbzlTaEBSPyhJIc3Gi2hGs=");
+    assert!(!is_err(&hmac("blake2b-224", "YWJj", "YWJj")));
+
+    for hash_type in [
+        "blake2b-0",
+        "blake2b-520",
+        "blake2s-264",
+        "blake2b-12",
+        "blake2b-x",
+    ] {
+        assert!(is_err(&hash(hash_type, "YWJj")), "{hash_type}");
+    }
+}
+
+#[test]
+fn hmacs() {
+    assert_eq!(
+        hmac(
+            "sha256",
+            "utf8:The quick brown fox jumps over the lazy dog",
+            "utf8:key"
+        ),
+        "97yD9DBThCSxMpjmqm+xQ+9NWaFJRhdZl0edvC0aPNg="
+    );
+    assert!(is_err(&hmac("crc32", "YWJj", "YWJj")));
+}
+
+#[test]
+fn macs() {
+    assert_eq!(
+        mac("cmac", "", "K34VFiiu0qar9xWICc9PPA==", ""),
+        "ux1pKelZNyh/o30Sm3VnRg=="
+    );
+    let key = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8=";
+    // "hello"
+    assert_eq!(
+        mac("poly1305", "aGVsbG8=", key, ""),
+        "IruthERAWHCIoLjQ6AAZMQ=="
+    );
+    // "short"
+    assert!(is_err(&mac("cmac", "", "c2hvcnQ=", "")));
+    assert!(is_err(&mac("unknown", "", key, "")));
+
+    // RFC 8439 2.5.2
+    assert_eq!(
+        mac(
+            "poly1305",
+            "utf8:Cryptographic Forum Research Group",
+            "hda+eFdVbTN/RFL+QtUGqAEDgIr7DbL9Sr/2r0FJ9Rs=",
+            ""
+        ),
+        "qAYdwTBRNsbCK4uvDAEnqQ=="
+    );
+}
+
+#[test]
+fn siphash_vectors() {
+    // vectors.h from the reference implementation: key 00..0f, message 00..(n-1)
+    let key = "AAECAwQFBgcICQoLDA0ODw==";
+    let vectors = [
+        ("", "MQ4O3Ufbb3I="),
+        ("AA==", "/Wfck8U5+HQ="),
+        ("AAECAwQFBg==", "N9EBi/UAAqs="),
+        ("AAECAwQFBgc=", "YiSTmnn19ZM="),
+        ("AAECAwQFBgcICQoLDA0O", "5UW+SWHKKaE="),
+        (
+            "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8gISIjJCUmJygpKissLS4vMDEyMzQ1Njc4OTo7PD0+",
+            "ckUG60wyipU=",
+        ),
+    ];
+    for (data, expected) in vectors {
+        assert_eq!(mac("siphash", data, key, ""), expected, "{data}");
+        assert_eq!(mac("siphash-2-4", data, key, ""), expected, "{data}");
+    }
+    assert_eq!(mac("siphash128", "", key, ""), "o4F/BLolqOZt9nIUx1UCkw==");
+    assert_eq!(
+        mac("siphash128", "AAECAwQFBgcICQoLDA0O", key, ""),
+        "VJPpmTOwqBF+COwPl8/D2Q=="
+    );
+    assert!(is_err(&mac("siphash", "", "AAECAwQFBgcICQoLDA0O", "")));
+}
+
+#[test]
+fn gmac_vectors() {
+    // GCM spec test cases 1, 7 and 13: zero key and nonce, nothing to authenticate
+    let nonce = "AAAAAAAAAAAAAAAA";
+    assert_eq!(
+        mac("gmac", "", "AAAAAAAAAAAAAAAAAAAAAA==", nonce),
+        "WOL8zvp+MGE2fx1XpOdFWg=="
+    );
+    assert_eq!(
+        mac("gmac", "", "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA", nonce),
+        "zTOyisdz90ugDtHzElckNQ=="
+    );
+    assert_eq!(
+        mac(
+            "gmac",
+            "",
+            "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=",
+            nonce
+        ),
+        "Uw+K+8dFNrmpY7TxxMtziw=="
+    );
+    // key, nonce and AAD of test case 4 without the plaintext (python cryptography AESGCM)
+    assert_eq!(
+        mac(
+            "gmac",
+            "/u36zt6tvu/+7frO3q2+76ut2tI=",
+            "/v/pkoZlcxxtao+UZzCDCA==",
+            "yv66vvrO263eyviI"
+        ),
+        "NGQ0/VHVzQxYh+xj45uQeg=="
+    );
+    assert!(is_err(&mac("gmac", "", "AAAAAAAAAAAAAAAAAAAAAA==", "AAAA")));
+}
+
+#[test]
+fn streaming() {
+    // "hello ", "world"
+    let (hello, world) = ("aGVsbG8g", "d29ybGQ=");
+
+    let id = hash_init("sha256");
+    assert!(!is_err(&id));
+    assert_eq!(hash_update(&id, hello), "OK");
+    assert_eq!(hash_update(&id, world), "OK");
+    assert_eq!(
+        hash_final(&id),
+        "uU0nuZNNPgilLlLX2n2r+sSE7+N6U4DukIj3rOLvzek="
+    );
+    // the session is gone after hash_final
+    assert!(is_err(&hash_update(&id, hello)));
+    assert!(is_err(&hash_final(&id)));
+
+    // "key"
+    let id = hmac_init("sha1", "a2V5");
+    assert_eq!(hmac_update(&id, hello), "OK");
+    assert_eq!(hmac_update(&id, world), "OK");
+    assert_eq!(hmac_final(&id), "NN0jS5JoNZNWBSj2GT6mjIAF9hU=");
+
+    assert!(is_err(&hash_init("unknown")));
+
+    for checksum in ["crc32", "xxh128", "fnv1a-64", "murmur3-32"] {
+        let id = hash_init(checksum);
+        assert_eq!(hash_update(&id, hello), "OK");
+        assert_eq!(hash_update(&id, world), "OK");
+        // "hello world"
+        assert_eq!(
+            hash_final(&id),
+            hash(checksum, "aGVsbG8gd29ybGQ="),
+            "{checksum}"
+        );
+    }
+}
+
+#[test]
+fn streaming_threads() {
+    let ids: Vec<String> = (0..8).map(|_| hash_init("sha256")).collect();
+    std::thread::scope(|scope| {
+        for id in &ids {
+            scope.spawn(move || {
+                for _ in 0..100 {
+                    // "a"
+                    assert_eq!(hash_update(id, "YQ=="), "OK");
+                }
+            });
+        }
+    });
+
+    // sha256 of 100 "a"
+    let expected = hash("sha256", &format!("utf8:{}", "a".repeat(100)));
+    for id in &ids {
+        assert_eq!(hash_final(id), expected);
+    }
+}
+
+#[test]
+fn xof() {
+    // "abc"
+    assert_eq!(
+        hash_xof("shake128", "YWJj", "16", "", ""),
+        "WIEJLdgYv1z4o923k/vLpw=="
+    );
+    assert!(is_err(&hash_xof("shake128", "YWJj", "many", "", "")));
+    assert_eq!(
+        hash_xof("shake128", "YWJj", "99999999999", "", ""),
+        "ERR|output length 99999999999 is too long, max is 1048576"
+    );
+    assert_eq!(
+        common::decode_len(&hash_xof("shake256", "YWJj", "1048576", "", "")),
+        1048576
+    );
+}
+
+#[test]
+fn xof_vectors() {
+    // NIST SP 800-185 samples
+    let data = "hex:00010203";
+    let long_data = format!(
+        "hex:{}",
+        (0..200u8).map(|b| format!("{b:02x}")).collect::<String>()
+    );
+    let email = "utf8:Email Signature";
+    let tagged = "utf8:My Tagged Application";
+    let key = "hex:404142434445464748494a4b4c4d4e4f505152535455565758595a5b5c5d5e5f";
+
+    let samples = [
+        // cSHAKE samples #1, #2 and #4
+        ("cshake128", data, "32", email, ""),
+        ("cshake128", &long_data, "32", email, ""),
+        ("cshake256", data, "64", email, ""),
+        // KMAC samples #1, #2 and #4
+        ("kmac128", data, "32", "", key),
+        ("kmac128", data, "32", tagged, key),
+        ("kmac256", data, "64", tagged, key),
+    ];
+    let expected = [
+        "wcNpJbZAmgTxtQT8vKnYK0AXJ3y17SsgZfwdOBTVqvU=",
+        "xSIdUOT4ItlqLoiBqWFCDylLeyT+PSCUuu0sZSTMFms=",
+        "0AiCjiuArJ0iGP/uHQcMSLjkyHv/MslpnVtolu7g7dFkAg4r4FYIWNnADAN+NKlpN8Vhp0xBK7THRkaVJygcjA==",
+        "5XgLDT6m99OkKcVwaqQ6APrb19SWKIOeMYckP0Vu4U4=",
+        "Ox+6ljzYsLWejBptcYiLcUNlGvi6CnBwwJeeKBEySqU=",
+        "IMVwwxNG9wPJrDbGHAPLZMOXDQz8eH6beVmdJzpo0vf2nUzD3p0QSjUWifJ89vWVHwED8z9PJIcQJNnCd3Oo3Q==",
+    ];
+    for ((xof_type, data, len, customization, key), expected) in samples.into_iter().zip(expected) {
+        assert_eq!(
+            hash_xof(xof_type, data, len, customization, key),
+            expected,
+            "{xof_type} {customization}"
+        );
+    }
+
+    // BLAKE3 test_vectors.json, input_len 0, hash and keyed_hash with the full 131 bytes
+    assert_eq!(
+        hash_xof("blake3", "", "131", "", ""),
+        "rxNJufX5oaagQE3qNtzJSZvLJcmtwRK3zJqTyuQfMmLgDwPntprya3+q8J/NMzBQM43f4IW4zIacqYsgbAgkOib1\
+         SHeJ6PZgr+bJnvngxSuS5zkwJKgEWc+R9Hb5/9vacAHCLhWbQCYx8nfKlvLe/fEHgoIxTnY2maMcU2MWVCHM4U0="
+    );
+    assert_eq!(
+        hash_xof(
+            "blake3",
+            "",
+            "131",
+            "",
+            "utf8:whats the Elvish word for friend"
+        ),
+        "krK3VgTtPHYfnW9iOSyKkietDqPwlXPng/FJik7WDSaxgXGi8ipLlIIscB8QcVPbokkYxLrk0pRcIOzhM4difTtz\
+         y/l7eX1eWZSMfveI9UNy30XkXkKTx9wYwdQRRKl1i+WJYIVr4eq74iwmUxkN5WDKOyrEqmkqkhBpQlTDcehRvI8="
+    );
+}
@@ -0,0 +1,201 @@
+mod common;
+
+use common::is_err;
+
+exports! {
+    bcrypt(data_ptr, cost_ptr, salt_ptr);
+    scrypt(data_ptr, log_n_ptr, r_ptr, p_ptr, len_ptr, salt_ptr);
+    pbkdf2(data_ptr, salt_ptr, rounds_ptr, len_ptr, hash_type_ptr);
+    evpkdf(data_ptr, salt_ptr, rounds_ptr, output_len_ptr, hash_type_ptr);
+    argon2(data_ptr, salt_ptr, variant_ptr, m_cost_ptr, t_cost_ptr, p_cost_ptr, len_ptr);
+    argon2_hash(data_ptr, salt_ptr, variant_ptr, m_cost_ptr, t_cost_ptr, p_cost_ptr);
+    hkdf(data_ptr, salt_ptr, info_ptr, len_ptr, hash_type_ptr);
+    hkdf_extract(data_ptr, salt_ptr, hash_type_ptr);
+    hkdf_expand(prk_ptr, info_ptr, len_ptr, hash_type_ptr);
+    bcrypt_pbkdf(data_ptr, salt_ptr, rounds_ptr, len_ptr);
+    password_verify(data_ptr, hash_ptr);
+}
+
+// "password"
+const PASSWORD: &str = "cGFzc3dvcmQ=";
+// OpenBSD test vector for "U*U"
+const BCRYPT_HASH: &str = "$2a$05$CCCCCCCCCCCCCCCCCCCCC.E5YPO9kmyuRGyh0XouQYb4YMJKvyOeW";
+
+#[test]
+fn bcrypt_hashes() {
+    assert_eq!(
+        bcrypt("utf8:U*U", "5", "CCCCCCCCCCCCCCCCCCCCC."),
+        BCRYPT_HASH
+    );
+    assert!(is_err(&bcrypt("utf8:U*U", "5", "c2hvcnQ=")));
+    assert!(is_err(&bcrypt("utf8:U*U", "", "CCCCCCCCCCCCCCCCCCCCC.")));
+}
+
+#[test]
+fn pbkdf2_and_scrypt() {
+    // RFC 6070
+    assert_eq!(
+        pbkdf2(PASSWORD, "utf8:salt", "1", "20", "sha1"),
+        "DGDID5YfDnHzqbUkr2ASBi/gN6Y="
+    );
+    assert_eq!(
+        scrypt(PASSWORD, "4", "8", "1", "16", "utf8:NaCl"),
+        "9d+zly55CLIkEMXF83iJBw=="
+    );
+    // EVP_BytesToKey as in `openssl enc -md md5`
+    assert_eq!(
+        evpkdf("utf8:secret", "hex:0102030405060708", "1", "48", "md5"),
+        "yeWhvSFtvhMX4jDO9I847n8OF61kAiFEvM7EoaooeaviSzK7vE7wLsvLZXZSOtiT"
+    );
+
+    assert!(is_err(&pbkdf2(PASSWORD, "utf8:salt", "1", "20", "crc32")));
+    assert!(is_err(&scrypt(
+        PASSWORD,
+        "100",
+        "8",
+        "1",
+        "16",
+        "utf8:NaCl"
+    )));
+}
+
+#[test]
+fn output_lengths() {
+    let too_long = "ERR|output length 1000000000000 is too long, max is 1024";
+    let len = "1000000000000";
+    assert_eq!(pbkdf2(PASSWORD, "utf8:salt", "1", len, "sha1"), too_long);
+    assert_eq!(evpkdf(PASSWORD, "", "1", len, "md5"), too_long);
+    assert_eq!(scrypt(PASSWORD, "4", "8", "1", len, "utf8:NaCl"), too_long);
+    assert_eq!(
+        argon2(PASSWORD, "utf8:somesalt", "", "", "", "", len),
+        too_long
+    );
+    assert_eq!(bcrypt_pbkdf(PASSWORD, "utf8:salt", "4", len), too_long);
+    assert_eq!(
+        hkdf(PASSWORD, "", "", len, "sha512"),
+        "ERR|output length 1000000000000 is too long, max is 16320"
+    );
+    assert_eq!(
+        common::decode_len(&pbkdf2(PASSWORD, "utf8:salt", "1", "1024", "sha1")),
+        1024
+    );
+}
+
+#[test]
+fn memory_costs() {
+    let salt = "utf8:somesalt";
+    // scrypt needs 128 * r * (2^log_n + p) bytes
+    assert_eq!(
+        scrypt(PASSWORD, "21", "8", "1", "16", salt),
+        "ERR|memory cost of 2147484672 bytes is too high, max is 1073741824"
+    );
+    assert_eq!(
+        argon2(PASSWORD, salt, "", "1048577", "1", "1", "32"),
+        "ERR|memory cost of 1073742848 bytes is too high, max is 1073741824"
+    );
+    assert!(is_err(&argon2_hash(
+        PASSWORD, salt, "", "4000000", "1", "1"
+    )));
+
+    // hashlib.scrypt(b"password", salt=b"somesalt", n=16, r=8, p=1)
+    let phc = "$scrypt$ln=4,r=8,p=1$c29tZXNhbHQ$7xe5L3Roj67jYaBKf3ePT2Y6rVHHGUWO44Z8iz+O6PQ";
+    assert_eq!(password_verify(PASSWORD, phc), "true");
+    let phc = phc.replace("ln=4", "ln=30");
+    assert!(password_verify(PASSWORD, &phc).starts_with("ERR|memory cost of"));
+
+    let phc = argon2_hash(PASSWORD, salt, "argon2id", "64", "1", "1");
+    let phc = phc.replace("m=64", "m=4000000");
+    assert_eq!(
+        password_verify(PASSWORD, &phc),
+        "ERR|memory cost of 4096000000 bytes is too high, max is 1073741824"
+    );
+}
+
+#[test]
+fn bcrypt_verify() {
+    // "U*U", "U*V"
+    assert_eq!(password_verify("VSpV", BCRYPT_HASH), "true");
+    assert_eq!(password_verify("VSpW", BCRYPT_HASH), "false");
+}
+
+#[test]
+fn argon2_hashes() {
+    // "somesalt"
+    let salt = "c29tZXNhbHQ=";
+    let key = argon2(PASSWORD, salt, "", "64", "1", "1", "32");
+    assert_eq!(common::decode_len(&key), 32);
+    assert_eq!(
+        argon2(PASSWORD, salt, "argon2id", "64", "1", "1", "32"),
+        key
+    );
+    assert_ne!(argon2(PASSWORD, salt, "argon2i", "64", "1", "1", "32"), key);
+
+    let phc = argon2_hash(PASSWORD, "", "argon2id", "64", "1", "1");
+    assert!(phc.starts_with("$argon2id$v=19$m=64,t=1,p=1$"), "{phc}");
+    assert_eq!(password_verify(PASSWORD, &phc), "true");
+    // "wrong"
+    assert_eq!(password_verify("d3Jvbmc=", &phc), "false");
+
+    assert!(is_err(&argon2(PASSWORD, salt, "argon3", "", "", "", "32")));
+    assert!(is_err(&password_verify(PASSWORD, "$md5$whatever")));
+}
+
+#[test]
+fn hkdf_keys() {
+    // RFC 5869 test case 1
+    let ikm = "CwsLCwsLCwsLCwsLCwsLCwsLCwsLCw==";
+    let salt = "AAECAwQFBgcICQoLDA==";
+    let info = "8PHy8/T19vf4+Q==";
+    let okm = "PLJfJfqs1XqQQ09k0DYvKi0tCpDPGlpMXbAtVuzExb80AHII1biHGFhl";
+    let prk = "B3cJNiwuMt8N3D8NxHu6Y5C2xzu1D5wxIuyEStfCs+U=";
+
+    assert_eq!(hkdf(ikm, salt, info, "42", "sha256"), okm);
+    assert_eq!(hkdf_extract(ikm, salt, "sha256"), prk);
+    assert_eq!(hkdf_expand(prk, info, "42", "sha256"), okm);
+    assert!(is_err(&hkdf(ikm, salt, info, "100000", "sha256")));
+    assert_eq!(
+        hkdf(ikm, salt, info, "8161", "sha256"),
+        "ERR|output length 8161 is too long, max is 8160"
+    );
+    assert!(is_err(&hkdf(ikm, salt, info, "42", "sha0")));
+    // prk shorter than hash length
+    assert!(is_err(&hkdf_expand(salt, info, "42", "sha256")));
+
+    // RFC 5869 test case 2, longer inputs and outputs
+    let ikm = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8gISIjJCUmJygpKissLS4vMDEyMzQ1Njc4OTo7PD0+P0BBQkNERUZHSElKS0xNTk8=";
+    let salt = "YGFiY2RlZmdoaWprbG1ub3BxcnN0dXZ3eHl6e3x9fn+AgYKDhIWGh4iJiouMjY6PkJGSk5SVlpeYmZqbnJ2en6ChoqOkpaanqKmqq6ytrq8=";
+    let info = "sLGys7S1tre4ubq7vL2+v8DBwsPExcbHyMnKy8zNzs/Q0dLT1NXW19jZ2tvc3d7f4OHi4+Tl5ufo6err7O3u7/Dx8vP09fb3+Pn6+/z9/v8=";
+    let prk = "Bqa4jFhTNhoGEEyc6zW0XO92ABSQRnEBShk/QMFfwkQ=";
+    let okm = "sR45jcgDJ6HI5/eMWWpJNE8BLtotTvrYoFDMTBmvqXxZBFqZyseCcnHLQcZeWQ4J2jJ1YAwvCbg2d5OprKPbccwwxYF57D6HwUwB1cHzQ08dhw==";
+    assert_eq!(hkdf(ikm, salt, info, "82", "sha256"), okm);
+    assert_eq!(hkdf_extract(ikm, salt, "sha256"), prk);
+    assert_eq!(hkdf_expand(prk, info, "82", "sha256"), okm);
+
+    // RFC 5869 test case 3, empty salt and info
+    let ikm = "CwsLCwsLCwsLCwsLCwsLCwsLCwsLCw==";
+    let prk = "Ge8koyxxexZ/M6kdb2SL35ZZZ3av22N3rENMHCk8ywQ=";
+    let okm = "jaTndaVjwY9xX4AqBjxaMbihH1xe4Yeew0VOXzxzjS2dIBOV+qS2GpbI";
+    assert_eq!(hkdf(ikm, "", "", "42", "sha256"), okm);
+    assert_eq!(hkdf_extract(ikm, "", "sha256"), prk);
+    assert_eq!(hkdf_expand(prk, "", "42", "sha256"), okm);
+
+    // RFC 5869 test case 4, sha1
+    let ikm = "CwsLCwsLCwsLCws=";
+    let salt = "AAECAwQFBgcICQoLDA==";
+    let info = "8PHy8/T19vf4+Q==";
+    let prk = "m2wYxDKnv48OccjriPSzC6orokM=";
+    let okm = "CFoB6hsQ82kzBotW76WtgaTxS4IvWwkVaKnN1PFV/aLCLkIkeNMF8/iW";
+    assert_eq!(hkdf(ikm, salt, info, "42", "sha1"), okm);
+    assert_eq!(hkdf_extract(ikm, salt, "sha1"), prk);
+    assert_eq!(hkdf_expand(prk, info, "42", "sha1"), okm);
+}
+
+#[test]
+fn bcrypt_pbkdf_keys() {
+    // "salt"
+    let salt = "c2FsdA==";
+    let key = bcrypt_pbkdf(PASSWORD, salt, "4", "32");
+    assert_eq!(common::decode_len(&key), 32);
+    assert_eq!(bcrypt_pbkdf(PASSWORD, salt, "4", "32"), key);
+    assert!(is_err(&bcrypt_pbkdf(PASSWORD, salt, "0", "32")));
+}
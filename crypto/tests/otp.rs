@@ -0,0 +1,80 @@
+mod common;
+
+use common::is_err;
+
+exports! {
+    totp(secret_ptr, digits_ptr, period_ptr, hash_type_ptr, timestamp_ptr);
+    hotp(secret_ptr, counter_ptr, digits_ptr, hash_type_ptr);
+    totp_verify(secret_ptr, code_ptr, digits_ptr, period_ptr, hash_type_ptr, window_ptr, timestamp_ptr);
+    otpauth_parse(uri_ptr);
+    otpauth_code(uri_ptr, timestamp_ptr);
+}
+
+// "12345678901234567890" from RFC 4226 and RFC 6238
+const SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+#[test]
+fn codes() {
+    assert_eq!(hotp(SECRET, "0", "", ""), "755224");
+    assert_eq!(hotp(SECRET, "1", "", ""), "287082");
+    assert_eq!(hotp("utf8:12345678901234567890", "1", "", ""), "287082");
+    assert_eq!(
+        hotp("hex:3132333435363738393031323334353637383930", "1", "", ""),
+        "287082"
+    );
+    assert_eq!(totp(SECRET, "8", "", "", "59"), "94287082");
+    assert_eq!(totp(SECRET, "8", "30", "sha1", "1111111109"), "07081804");
+    assert_eq!(totp(SECRET, "", "", "", "").len(), 6);
+
+    assert!(is_err(&hotp("not base32!", "0", "", "")));
+    assert!(is_err(&totp(SECRET, "six", "", "", "")));
+}
+
+#[test]
+fn verify() {
+    assert_eq!(
+        totp_verify(SECRET, "94287082", "8", "", "", "", "59"),
+        "true"
+    );
+    // one period later is still inside the default window
+    assert_eq!(
+        totp_verify(SECRET, "94287082", "8", "", "", "", "89"),
+        "true"
+    );
+    assert_eq!(
+        totp_verify(SECRET, "94287082", "8", "", "", "0", "89"),
+        "false"
+    );
+    assert_eq!(
+        totp_verify(SECRET, "00000000", "8", "", "", "", "59"),
+        "false"
+    );
+    assert_eq!(
+        totp_verify(SECRET, "94287082", "8", "", "", "10", "359"),
+        "true"
+    );
+    assert_eq!(
+        totp_verify(SECRET, "94287082", "8", "", "", "11", "59"),
+        "ERR|invalid window: 11, max is 10"
+    );
+    assert_eq!(
+        totp_verify(SECRET, "942870820", "8", "", "", "", "59"),
+        "false"
+    );
+}
+
+#[test]
+fn otpauth() {
+    let uri = format!(
+        "otpauth://totp/Example:alice%40example.com?secret={SECRET}&issuer=Example&digits=8"
+    );
+    let parsed = otpauth_parse(&uri);
+    assert!(parsed.contains(r#""issuer":"Example""#), "{parsed}");
+    assert!(
+        parsed.contains(r#""label":"Example:alice@example.com""#),
+        "{parsed}"
+    );
+    assert_eq!(otpauth_code(&uri, "59"), "94287082");
+
+    assert!(is_err(&otpauth_parse("https://example.com")));
+}
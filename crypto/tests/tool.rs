@@ -0,0 +1,133 @@
+mod common;
+
+use common::is_err;
+
+exports! {
+    random_bytes(len_ptr);
+    random_token(len_ptr, alphabet_ptr);
+    random_password(len_ptr, classes_ptr, symbols_ptr);
+    pipeline(recipe_ptr, input_ptr);
+    hash(hash_type, data_ptr);
+    xor(data_ptr, key_ptr);
+    rsa_modulus_from_pem(key_ptr);
+    info_getAuthor();
+    info_getVersion();
+    info_getDescription();
+}
+
+#[test]
+fn random() {
+    assert_eq!(common::decode_len(&random_bytes("32")), 32);
+    assert_ne!(random_bytes("32"), random_bytes("32"));
+    assert!(is_err(&random_bytes("-1")));
+    let too_long = "ERR|length 99999999999 is too long, max is 1048576";
+    assert_eq!(random_bytes("99999999999"), too_long);
+    assert_eq!(random_token("99999999999", ""), too_long);
+    assert_eq!(random_password("99999999999", "", ""), too_long);
+    assert_eq!(common::decode_len(&random_bytes("1048576")), 1048576);
+
+    let token = random_token("40", "hex");
+    assert_eq!(token.len(), 40);
+    assert!(token.chars().all(|c| c.is_ascii_hexdigit()), "{token}");
+    let token = random_token("10", "ab");
+    assert!(token.chars().all(|c| c == 'a' || c == 'b'), "{token}");
+
+    let password = random_password("16", "lower,digits", "");
+    assert_eq!(password.len(), 16);
+    assert!(
+        password.chars().any(|c| c.is_ascii_lowercase()),
+        "{password}"
+    );
+    assert!(password.chars().any(|c| c.is_ascii_digit()), "{password}");
+    assert!(password
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit()));
+    assert!(is_err(&random_password("16", "emoji", "")));
+}
+
+#[test]
+fn pipelines() {
+    let recipe =
+        r#"{"steps": [{"op": "hmac", "hash": "sha256", "key": "utf8:key"}], "output": "hex"}"#;
+    assert_eq!(
+        pipeline(recipe, "utf8:The quick brown fox jumps over the lazy dog"),
+        "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+    );
+
+    let recipe = r#"[
+        {"op": "hash", "hash": "sha256", "save": "digest"},
+        {"op": "aes_encrypt", "key": "hex:000102030405060708090a0b0c0d0e0f", "mode": "ecb", "padding": "pkcs7"},
+        {"op": "aes_decrypt", "key": "hex:000102030405060708090a0b0c0d0e0f", "mode": "ecb", "padding": "pkcs7"},
+        {"op": "concat", "with": "$digest"}
+    ]"#;
+    let digest = "ungWv48Bz+pBQUDeXa4iI7ADYaOWF3qctBD/YfIAFa0=";
+    assert_eq!(
+        pipeline(recipe, "utf8:abc"),
+        pipeline(
+            r#"[{"op": "concat", "input": "$input", "with": "$input"}]"#,
+            digest
+        )
+    );
+
+    let recipe =
+        r#"[{"op": "hash", "hash": "sha256"}, {"op": "hmac", "hash": "nope", "key": "utf8:k"}]"#;
+    assert!(pipeline(recipe, "").starts_with("ERR|step 2 (hmac)"));
+    assert!(is_err(&pipeline("{}", "")));
+}
+
+#[test]
+fn encodings() {
+    let digest = "ungWv48Bz+pBQUDeXa4iI7ADYaOWF3qctBD/YfIAFa0=";
+    assert_eq!(hash("sha256", "utf8:abc"), digest);
+    assert_eq!(
+        hash("hex>sha256", "utf8:abc"),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+    assert_eq!(
+        hash("base64url>sha256", "latin1:abc"),
+        "ungWv48Bz-pBQUDeXa4iI7ADYaOWF3qctBD_YfIAFa0"
+    );
+    assert_eq!(
+        hash("base64url_pad>sha256", "hex:616263"),
+        "ungWv48Bz-pBQUDeXa4iI7ADYaOWF3qctBD_YfIAFa0="
+    );
+    // the prefix goes on the first argument, whatever it is
+    assert_eq!(xor("hex>utf8:abc", "hex:20"), "414243");
+    assert_eq!(random_bytes("hex>16").len(), 32);
+    assert_eq!(
+        rsa_modulus_from_pem(&format!("hex>{}", common::RSA_PUBLIC_KEY))
+            .split_once('|')
+            .map(|(_, e)| e),
+        Some("010001")
+    );
+    assert_eq!(
+        hash("utf8>md5", "utf8:abc"),
+        "ERR|result is not valid utf8, use another output encoding"
+    );
+    assert!(is_err(&hash("base32>sha256", "utf8:abc")));
+    // the choice is per call, the next one is base64 again
+    assert_eq!(hash("sha256", "utf8:abc"), digest);
+}
+
+#[test]
+fn info() {
+    assert_eq!(info_getAuthor(), "_Skill_");
+    assert_eq!(info_getVersion(), "0.7");
+    assert_eq!(
+        info_getDescription(),
+        "Много полезных функций шифрования/хэширования"
+    );
+}
+
+#[test]
+fn bad_arguments() {
+    let invalid = [0xd800, 0];
+    unsafe {
+        assert!(is_err(&common::from_wide(ffi::random_bytes(
+            std::ptr::null()
+        ))));
+        assert!(is_err(&common::from_wide(ffi::random_bytes(
+            invalid.as_ptr()
+        ))));
+    }
+}
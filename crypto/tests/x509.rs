@@ -0,0 +1,137 @@
+mod common;
+
+use common::{is_err, P256_PRIVATE, RSA_PRIVATE_KEY};
+
+exports! {
+    x509_parse(cert_ptr);
+    x509_csr(key_ptr, subject_ptr, sans_ptr, hash_type_ptr);
+    x509_self_signed(key_ptr, subject_ptr, sans_ptr, days_ptr, hash_type_ptr);
+    pkcs12_parse(data_ptr, password_ptr);
+    pkcs12_build(key_ptr, certs_ptr, password_ptr, friendly_name_ptr);
+}
+
+// self-signed with P256_PRIVATE by python cryptography
+const CERT: &str = "-----BEGIN CERTIFICATE-----
+MIIBYjCCAQmgAwIBAgICEjQwCgYIKoZIzj0EAwIwJDEUMBIGA1UEAwwLZXhhbXBs
+ZS5jb20xDDAKBgNVBAoMA09yZzAeFw0yNDAxMDEwMDAwMDBaFw0zNDAxMDEwMDAw
+MDBaMCQxFDASBgNVBAMMC2V4YW1wbGUuY29tMQwwCgYDVQQKDANPcmcwWTATBgcq
+hkjOPQIBBggqhkjOPQMBBwNCAAQm7869DunjSmaRh+GLOpEisvczlFtknMn5+SHp
++drYEpAji96cx7szDRUMZ3BN0lrnBVIFdEtvMb9AcHRYctDmoyswKTAnBgNVHREE
+IDAeggtleGFtcGxlLmNvbYIPd3d3LmV4YW1wbGUuY29tMAoGCCqGSM49BAMCA0cA
+MEQCIAiG3gx2UEFL6UUpYHRwAqajJzF6MzVkRC/BacyiSlC6AiBELuYkD7Tix5M3
+XNchPJdNq5nzPoKx8clT4fAc/wws5w==
+-----END CERTIFICATE-----";
+
+// CERT and P256_PRIVATE with password "secret" and friendly name "test"
+const P12: &str = "MIIENQIBAzCCA+sGCSqGSIb3DQEHAaCCA9wEggPYMIID1DCCAmoGCSqGSIb3DQEHBqCCAlswggJXAgEAMIICUAYJKoZIhvcNAQcBMF8GCSqGSIb3DQEFDTBSMDEGCSqGSIb3DQEFDDAkBBBHX8H1BamuRClhpvX9ruz2AgJOIDAMBggqhkiG9w0CCQUAMB0GCWCGSAFlAwQBKgQQek7mJNDrjKwNt7X9qEiqvICCAeAo7P46JL9H5sL90UjNBiok9F3leFv9z1fCNGbWxQeq29Ko3vWN2xEZMfA7wTgI8fhhEj11vmsgAk1jzsS1VR8LnMPzK5sChkkVdKAM9I2KHYthxi6ZfLeIwvEpehGUPNdv1uPVPc1l/LeYeK1jOiG7xzt8t7rsODT04/8ze3JFJcUu+bLGXdUQOZh24/q+f3u/2K5tIOFzhKE/RWefnomijh979KuvyYnwMfaW/yR43GNXov19VFuN+5yz2UVfQwPICpU0JVLYLZ/vh4HLBHTVfXCfpPs+WsGjxqwRMtmQNliIDvKmP9cY4RVak0xhAjV/jq8FLhMwEOAvp1WPqJdPiai9iZlS8wuRJZd005c688tuefWLWJwj5g9gMFfrSwL9DgwlyrASsinIZCQdtBe6d/XC9yyxbsaxmUGdAwPodHMjUvv0PjAMs82DwDrBonyDrMYBKc96MlOYC07lB2xjxVAMnOfaAGc3Mr10ag31rWTXydnDamAJVwlZTnUzaRG4UC50aJdM/H7glN4VGtZzzAVEhoyomsb2yVguIGRMU+F51kBIwp7u22T/AY0MvPvaVLFQBX8cqqBjZJQJgf3io7cd1voGKLaZHalgb4/+KwGxOF4GTpbAOYgmpbCwbS0wggFiBgkqhkiG9w0BBwGgggFTBIIBTzCCAUswggFHBgsqhkiG9w0BDAoBAqCB9zCB9DBfBgkqhkiG9w0BBQ0wUjAxBgkqhkiG9w0BBQwwJAQQzSLPyx1gcoyIZuV7pAQYNQICTiAwDAYIKoZIhvcNAgkFADAdBglghkgBZQMEASoEEMWzLXHrVxdLbMs0n57s7TIEgZBf/6TXVrHiJZiwaGEjcECeIEDSYU+SbVKqLg17cf05ut+F6kEBAL6fWaU+Ruuj6RDcKvEylGATtfBfn8anE80+kKBEqtjOH9cZ3pvD2te3s1yqUr8cn9H14DDtXt1hsmPFzsuqssifDlA+EGNZcFf7ZH/Q5ynruOSPUiZOstIjfTr7JozOixR3t6glGSFIldYxPjAXBgkqhkiG9w0BCRQxCh4IAHQAZQBzAHQwIwYJKoZIhvcNAQkVMRYEFP5/ngPg+V3HGTyjeaJmshDe3FP1MEEwMTANBglghkgBZQMEAgEFAAQg4nLX0943QjBR6O0e5EL065m7Q2dmB9ssKPNNl0TQZbkECJZrZjd7NHGlAgIIAA==";
+
+#[test]
+fn parse() {
+    let parsed = x509_parse(CERT);
+    for field in [
+        r#""subject":"O=Org,CN=example.com""#,
+        r#""serial":"1234""#,
+        r#""not_after_unix":2019686400"#,
+        r#""sans":["DNS:example.com","DNS:www.example.com"]"#,
+        r#""sha256":"154a6b4c1753adbe9ac8946a2a7299334ab9ee00f02c3455a9dfb622ab371618""#,
+        r#""curve":"secp256r1""#,
+    ] {
+        assert!(parsed.contains(field), "{field} in {parsed}");
+    }
+    // DER in another encoding needs a prefix
+    let lines: Vec<&str> = CERT.lines().collect();
+    let der = lines[1..lines.len() - 1].concat();
+    assert_eq!(x509_parse(&der), parsed);
+    let der = format!("base64url:{}", der.replace('+', "-").replace('/', "_"));
+    assert_eq!(x509_parse(&der), parsed);
+    assert!(is_err(&x509_parse("not a certificate")));
+}
+
+#[test]
+fn generate() {
+    let csr = x509_csr(RSA_PRIVATE_KEY, "CN=example.com,O=Org", "example.com", "");
+    assert!(
+        csr.starts_with("-----BEGIN CERTIFICATE REQUEST-----"),
+        "{csr}"
+    );
+
+    let cert = x509_self_signed(
+        P256_PRIVATE,
+        "CN=example.com",
+        "example.com, 127.0.0.1",
+        "30",
+        "",
+    );
+    let parsed = x509_parse(&cert);
+    assert!(parsed.contains(r#""issuer":"CN=example.com""#), "{parsed}");
+    assert!(parsed.contains("IP:127.0.0.1"), "{parsed}");
+
+    assert!(is_err(&x509_csr("not a key", "CN=example.com", "", "")));
+    // raw 32 bytes could be p256, secp256k1 or ed25519
+    assert_eq!(
+        x509_csr(
+            "AQIDBAUGBwgJCgsMDQ4PEBESExQVFhcYGRobHB0eHyA=",
+            "CN=example.com",
+            "",
+            ""
+        ),
+        "ERR|raw private key fits several curves, use PEM, DER or JWK"
+    );
+    assert!(is_err(&x509_self_signed(
+        P256_PRIVATE,
+        "CN=example.com",
+        "",
+        "many",
+        ""
+    )));
+}
+
+#[test]
+fn pkcs12() {
+    let parsed = pkcs12_parse(P12, "secret");
+    assert!(parsed.contains(r#""friendly_name":"test""#), "{parsed}");
+    assert!(parsed.contains("PRIVATE KEY"), "{parsed}");
+    assert!(is_err(&pkcs12_parse(P12, "wrong")));
+
+    // MAC iteration count patched from 2048 to 0 and to 100000000, lengths adjusted
+    let zero = P12
+        .replace("MIIENQIB", "MIIENAIB")
+        .replace("MEEwMTAN", "MEAwMTAN")
+        .replace("NHGlAgIIAA==", "NHGlAgEA");
+    assert_eq!(
+        pkcs12_parse(&zero, "secret"),
+        "ERR|invalid pkcs12 iteration count: 0, expected 1 to 1000000"
+    );
+    let huge = P12
+        .replace("MIIENQIB", "MIIENwIB")
+        .replace("MEEwMTAN", "MEMwMTAN")
+        .replace("NHGlAgIIAA==", "NHGlAgQF9eEA");
+    assert_eq!(
+        pkcs12_parse(&huge, "secret"),
+        "ERR|invalid pkcs12 iteration count: 100000000, expected 1 to 1000000"
+    );
+
+    // no MAC, one shrouded key bag with zero bytes as ciphertext and PBES2 AES-256-CBC
+    // keyed by PBKDF2-SHA256 with 2048, then 100000000 iterations, then by scrypt N=2^20, r=8
+    let pbkdf2 = "MIHAAgEDMIG6BgkqhkiG9w0BBwGggawEgakwgaYwgaMGCSqGSIb3DQEHAaCBlQSBkjCBjzCBjAYLKoZIhvcNAQwKAQKgfTB7MFcGCSqGSIb3DQEFDTBKMCkGCSqGSIb3DQEFDDAcBAgAAAAAAAAAAAICCAAwDAYIKoZIhvcNAgkFADAdBglghkgBZQMEASoEEAAAAAAAAAAAAAAAAAAAAAAEIAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA";
+    assert_eq!(
+        pkcs12_parse(pbkdf2, "secret"),
+        "ERR|invalid password or corrupted pkcs12"
+    );
+    let pbkdf2 = "MIHCAgEDMIG8BgkqhkiG9w0BBwGgga4EgaswgagwgaUGCSqGSIb3DQEHAaCBlwSBlDCBkTCBjgYLKoZIhvcNAQwKAQKgfzB9MFkGCSqGSIb3DQEFDTBMMCsGCSqGSIb3DQEFDDAeBAgAAAAAAAAAAAIEBfXhADAMBggqhkiG9w0CCQUAMB0GCWCGSAFlAwQBKgQQAAAAAAAAAAAAAAAAAAAAAAQgAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=";
+    assert_eq!(
+        pkcs12_parse(pbkdf2, "secret"),
+        "ERR|invalid pkcs12 iteration count: 100000000, expected 1 to 1000000"
+    );
+    let scrypt = "MIG5AgEDMIGzBgkqhkiG9w0BBwGggaUEgaIwgZ8wgZwGCSqGSIb3DQEHAaCBjgSBizCBiDCBhQYLKoZIhvcNAQwKAQKgdjB0MFAGCSqGSIb3DQEFDTBDMCIGCSsGAQQB2kcECzAVBAgAAAAAAAAAAAIDEAAAAgEIAgEBMB0GCWCGSAFlAwQBKgQQAAAAAAAAAAAAAAAAAAAAAAQgAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=";
+    assert_eq!(
+        pkcs12_parse(scrypt, "secret"),
+        "ERR|memory cost of 1073742848 bytes is too high, max is 1073741824"
+    );
+
+    let built = pkcs12_build(P256_PRIVATE, CERT, "other", "");
+    let rebuilt = pkcs12_parse(&built, "other");
+    assert!(rebuilt.contains(r#""friendly_name":null"#), "{rebuilt}");
+    let cert = CERT.replace('\n', "\\n");
+    assert!(rebuilt.contains(&cert), "{rebuilt}");
+}
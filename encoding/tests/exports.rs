@@ -0,0 +1,73 @@
+//! Calls the exports by name the way Keeper does: null-terminated UTF-16 in and out
+
+extern crate encoding;
+
+fn wide(s: &str) -> Vec<u16> {
+    s.encode_utf16().chain(Some(0)).collect()
+}
+
+unsafe fn from_wide(ptr: *const u16) -> String {
+    let len = (0..).take_while(|&i| *ptr.add(i) != 0).count();
+    String::from_utf16(std::slice::from_raw_parts(ptr, len)).unwrap()
+}
+
+macro_rules! exports {
+    ($($name:ident($($arg:ident),*);)+) => {
+        #[allow(non_snake_case)]
+        mod ffi {
+            extern "system" {
+                $(pub fn $name($($arg: *const u16),*) -> *const u16;)+
+            }
+        }
+
+        $(
+            #[allow(non_snake_case)]
+            fn $name($($arg: &str),*) -> String {
+                $(let $arg = wide($arg);)*
+                unsafe { from_wide(ffi::$name($($arg.as_ptr()),*)) }
+            }
+        )+
+    };
+}
+
+exports! {
+    b64_encode(data);
+    b64_decode(data);
+    hex_encode(data);
+    hex_decode(data);
+    hex_to_b64(data);
+    b64_to_hex(data);
+    info_getAuthor();
+    info_getVersion();
+    info_getDescription();
+}
+
+#[test]
+fn base64() {
+    assert_eq!(b64_encode("test"), "dGVzdA==");
+    assert_eq!(b64_encode("тест"), "0YLQtdGB0YI=");
+    assert_eq!(b64_decode("dGVzdA=="), "test");
+    assert!(b64_decode("dGVzdA=!").starts_with("ERR|"));
+}
+
+#[test]
+fn hex() {
+    assert_eq!(hex_encode("test"), "74657374");
+    assert_eq!(hex_decode("74657374"), "test");
+    assert!(hex_decode("7465737").starts_with("ERR|"));
+}
+
+#[test]
+fn hex_base64() {
+    assert_eq!(hex_to_b64("74657374"), "dGVzdA==");
+    assert_eq!(b64_to_hex("dGVzdA=="), "74657374");
+    assert!(hex_to_b64("zz").starts_with("ERR|"));
+    assert!(b64_to_hex("!").starts_with("ERR|"));
+}
+
+#[test]
+fn info() {
+    assert_eq!(info_getAuthor(), "_Skill_");
+    assert_eq!(info_getVersion(), "0.1");
+    assert!(info_getDescription().starts_with("Некоторые кодировки"));
+}
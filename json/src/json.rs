@@ -1,13 +1,12 @@
-use std::mem;
-use gjson;
-
-use crate::utils::cstring;
+use pk_ffi::{cstring, export, LPCWSTR};
 
 #[no_mangle]
-pub extern "stdcall" fn get(json_ptr: *const u16, path_ptr: *const u16) -> *const u16 {
-    let json = cstring::from_ptr(json_ptr).unwrap();
-    let path = cstring::from_ptr(path_ptr).unwrap();
-    let value = gjson::get(&json, &path);
+pub unsafe extern "system" fn get(json_ptr: LPCWSTR, path_ptr: LPCWSTR) -> LPCWSTR {
+    export(|| {
+        let json = cstring::from_widechar_ptr(json_ptr)?;
+        let path = cstring::from_widechar_ptr(path_ptr)?;
+        let value = gjson::get(&json, &path);
 
-    mem::ManuallyDrop::new(cstring::to_widechar(value.str())).as_ptr()
-}
\ No newline at end of file
+        Ok(value.str().to_owned())
+    })
+}
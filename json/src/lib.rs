@@ -1,12 +1,8 @@
 mod json;
-mod dllmain;
 
-mod utils {
-    pub mod cstring;
+pk_ffi::dll_main!();
+pk_ffi::info! {
+    author: "_Skill_",
+    version: "0.1",
+    description: "Получение нужного значения из json строки по его пути",
 }
-
-pub use crate::{
-    utils::*,
-};
-
-pub const ERR: &str = "ERR|";
\ No newline at end of file
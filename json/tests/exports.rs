@@ -0,0 +1,55 @@
+//! Calls the exports by name the way Keeper does: null-terminated UTF-16 in and out
+
+extern crate json;
+
+fn wide(s: &str) -> Vec<u16> {
+    s.encode_utf16().chain(Some(0)).collect()
+}
+
+unsafe fn from_wide(ptr: *const u16) -> String {
+    let len = (0..).take_while(|&i| *ptr.add(i) != 0).count();
+    String::from_utf16(std::slice::from_raw_parts(ptr, len)).unwrap()
+}
+
+macro_rules! exports {
+    ($($name:ident($($arg:ident),*);)+) => {
+        #[allow(non_snake_case)]
+        mod ffi {
+            extern "system" {
+                $(pub fn $name($($arg: *const u16),*) -> *const u16;)+
+            }
+        }
+
+        $(
+            #[allow(non_snake_case)]
+            fn $name($($arg: &str),*) -> String {
+                $(let $arg = wide($arg);)*
+                unsafe { from_wide(ffi::$name($($arg.as_ptr()),*)) }
+            }
+        )+
+    };
+}
+
+exports! {
+    get(json, path);
+    info_getAuthor();
+    info_getVersion();
+    info_getDescription();
+}
+
+const JSON: &str = r#"{"name": {"first": "Тест", "last": "Test"}, "friends": [{"age": 44}, {"age": 68}]}"#;
+
+#[test]
+fn get_value() {
+    assert_eq!(get(JSON, "name.first"), "Тест");
+    assert_eq!(get(JSON, "friends.1.age"), "68");
+    assert_eq!(get(JSON, "friends.#.age"), "[44,68]");
+    assert_eq!(get(JSON, "missing"), "");
+}
+
+#[test]
+fn info() {
+    assert_eq!(info_getAuthor(), "_Skill_");
+    assert_eq!(info_getVersion(), "0.1");
+    assert!(info_getDescription().contains("json"));
+}
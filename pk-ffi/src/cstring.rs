@@ -0,0 +1,25 @@
+use crate::{results, Error};
+
+#[allow(clippy::upper_case_acronyms)]
+pub type LPCWSTR = *const u16;
+
+/// The pointer stays valid until this thread returns
+/// [`KEEP_RESULTS`](crate::KEEP_RESULTS) more strings
+pub fn to_widechar_ptr<S: AsRef<str>>(s: S) -> LPCWSTR {
+    let wstring: Vec<u16> = s.as_ref().encode_utf16().chain(Some(0)).collect();
+    results::keep(wstring)
+}
+
+/// Null pointers and invalid UTF-16 are errors instead of a crash of Keeper
+///
+/// # Safety
+/// `data_ptr` must be null or point to a null-terminated UTF-16 string
+pub unsafe fn from_widechar_ptr(data_ptr: LPCWSTR) -> Result<String, Error> {
+    if data_ptr.is_null() {
+        return Err(Error::msg("null string pointer"));
+    }
+
+    let len = (0..).take_while(|&i| *data_ptr.add(i) != 0).count();
+    let slice = std::slice::from_raw_parts(data_ptr, len);
+    Ok(String::from_utf16(slice)?)
+}
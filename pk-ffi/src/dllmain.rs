@@ -0,0 +1,40 @@
+pub use winapi::shared::minwindef::{BOOL, DWORD, HINSTANCE, LPVOID};
+
+use winapi::shared::minwindef::TRUE;
+use winapi::um::libloaderapi::DisableThreadLibraryCalls;
+use winapi::um::winnt::{DLL_PROCESS_ATTACH, DLL_PROCESS_DETACH};
+use winapi::um::{consoleapi::AllocConsole, wincon::FreeConsole};
+
+use crate::{debug, DEBUG};
+
+/// Called by the `DllMain` from [`dll_main!`](crate::dll_main)
+pub fn dll_main(
+    h_module: HINSTANCE,
+    dw_reason: DWORD,
+    attach: impl FnOnce(),
+    detach: impl FnOnce(),
+) -> BOOL {
+    match dw_reason {
+        DLL_PROCESS_ATTACH => {
+            unsafe {
+                // We don't need to know if PK creates new threads
+                DisableThreadLibraryCalls(h_module);
+                if DEBUG {
+                    AllocConsole();
+                }
+            }
+            debug!("Loaded");
+            attach();
+        }
+        DLL_PROCESS_DETACH => {
+            detach();
+            if DEBUG {
+                unsafe {
+                    FreeConsole();
+                }
+            }
+        }
+        _ => {}
+    }
+    TRUE
+}
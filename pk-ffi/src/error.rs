@@ -0,0 +1,25 @@
+use std::fmt::{self, Display};
+
+/// Error of an export, Keeper gets it as `ERR|<message>`.
+/// Any `std::error::Error` converts into it, so exports just use `?`
+#[derive(Debug)]
+pub struct Error(String);
+
+impl Error {
+    pub fn msg<S: Into<String>>(msg: S) -> Self {
+        Error(msg.into())
+    }
+}
+
+// no `std::error::Error` impl for `Error` itself, it would conflict with this one
+impl<E: std::error::Error> From<E> for Error {
+    fn from(error: E) -> Self {
+        Error(error.to_string())
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
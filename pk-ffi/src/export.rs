@@ -0,0 +1,43 @@
+use std::{
+    any::Any,
+    panic::{self, AssertUnwindSafe},
+};
+
+use crate::{cstring, Error, ERR, LPCWSTR};
+
+/// Body of an export: `Ok` goes to Keeper as is, errors and panics as `ERR|<message>`
+///
+/// ```
+/// # use pk_ffi::{cstring, export, LPCWSTR};
+/// #[no_mangle]
+/// pub unsafe extern "system" fn parse(number_ptr: LPCWSTR) -> LPCWSTR {
+///     export(|| {
+///         let number: i64 = cstring::from_widechar_ptr(number_ptr)?.parse()?;
+///         Ok(number.to_string())
+///     })
+/// }
+/// ```
+pub fn export<F, S>(f: F) -> LPCWSTR
+where
+    F: FnOnce() -> Result<S, Error>,
+    S: AsRef<str>,
+{
+    // a panic leaves nothing half-done that Keeper could see, the result is the only output
+    let error = match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(result)) => return cstring::to_widechar_ptr(result),
+        Ok(Err(error)) => error.to_string(),
+        Err(panic) => format!("panic: {}", panic_message(&*panic)),
+    };
+
+    cstring::to_widechar_ptr(format!("{ERR}{error}"))
+}
+
+fn panic_message(panic: &(dyn Any + Send)) -> &str {
+    match panic.downcast_ref::<&str>() {
+        Some(message) => message,
+        None => panic
+            .downcast_ref::<String>()
+            .map(String::as_str)
+            .unwrap_or("unknown"),
+    }
+}
@@ -0,0 +1,20 @@
+//! Everything a Keeper dll needs around its exports: UTF-16 strings in and out,
+//! errors and panics as `ERR|...` results, the debug console, `DllMain` and `info_get*`
+
+pub mod cstring;
+#[cfg(windows)]
+#[doc(hidden)]
+pub mod dllmain;
+mod error;
+mod export;
+mod macros;
+mod results;
+
+pub use crate::{cstring::LPCWSTR, error::Error, export::export, results::KEEP_RESULTS};
+
+#[doc(hidden)]
+pub use wchar;
+
+pub const ERR: &str = "ERR|";
+/// Build with `DLL_DEBUG=1 cargo dll` to get a console for `debug!` output
+pub const DEBUG: bool = option_env!("DLL_DEBUG").is_some();
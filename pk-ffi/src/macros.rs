@@ -0,0 +1,54 @@
+/// `println!` to the debug console, does nothing unless built with `DLL_DEBUG`
+#[macro_export]
+macro_rules! debug {
+    ( $($arg:tt)+ ) => {
+        if $crate::DEBUG {
+            println!($($arg)+);
+        }
+    }
+}
+
+/// `DllMain` of the dll: opens the debug console and runs the optional
+/// `attach`/`detach` closures on load and unload. Only compiled on Windows
+#[macro_export]
+macro_rules! dll_main {
+    () => {
+        $crate::dll_main!(attach: || {}, detach: || {});
+    };
+    (attach: $attach:expr, detach: $detach:expr $(,)?) => {
+        #[cfg(windows)]
+        #[no_mangle]
+        #[allow(non_snake_case)]
+        extern "system" fn DllMain(
+            h_module: $crate::dllmain::HINSTANCE,
+            dw_reason: $crate::dllmain::DWORD,
+            _: $crate::dllmain::LPVOID,
+        ) -> $crate::dllmain::BOOL {
+            $crate::dllmain::dll_main(h_module, dw_reason, $attach, $detach)
+        }
+    };
+}
+
+/// `info_getAuthor`, `info_getVersion` and `info_getDescription` exports Keeper shows for the plugin
+#[macro_export]
+macro_rules! info {
+    (author: $author:tt, version: $version:tt, description: $description:tt $(,)?) => {
+        #[no_mangle]
+        pub extern "system" fn info_getAuthor() -> $crate::LPCWSTR {
+            const AUTHOR: &[u16] = $crate::wchar::wchz!($author);
+            AUTHOR.as_ptr()
+        }
+
+        #[no_mangle]
+        pub extern "system" fn info_getVersion() -> $crate::LPCWSTR {
+            const VER: &[u16] = $crate::wchar::wchz!($version);
+            VER.as_ptr()
+        }
+
+        #[no_mangle]
+        pub extern "system" fn info_getDescription() -> $crate::LPCWSTR {
+            const DESC: &[u16] = $crate::wchar::wchz!($description);
+            DESC.as_ptr()
+        }
+    };
+}
@@ -0,0 +1,34 @@
+use std::{cell::RefCell, collections::VecDeque};
+
+/// How many returned strings of a thread are alive at once
+pub const KEEP_RESULTS: usize = 16;
+
+thread_local! {
+    static RESULTS: RefCell<VecDeque<Box<[u16]>>> =
+        RefCell::new(VecDeque::with_capacity(KEEP_RESULTS));
+}
+
+/// Keeps a returned string alive until the thread returns [`KEEP_RESULTS`] newer ones.
+/// Keeper copies the result right after the call, so older buffers can be freed
+/// instead of leaking every result
+pub(crate) fn keep(wstring: Vec<u16>) -> *const u16 {
+    let wstring = wstring.into_boxed_slice();
+    let ptr = wstring.as_ptr();
+
+    let mut wstring = Some(wstring);
+
+    let _ = RESULTS.try_with(|results| {
+        let mut results = results.borrow_mut();
+        if results.len() == KEEP_RESULTS {
+            results.pop_front();
+        }
+        results.extend(wstring.take());
+    });
+
+    // the thread is exiting and its results are gone already, this one can only leak
+    if let Some(wstring) = wstring {
+        Box::leak(wstring);
+    }
+
+    ptr
+}
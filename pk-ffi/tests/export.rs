@@ -0,0 +1,85 @@
+use std::ptr;
+
+use pk_ffi::{cstring, export, Error, KEEP_RESULTS, LPCWSTR};
+
+fn wide(s: &str) -> Vec<u16> {
+    s.encode_utf16().chain(Some(0)).collect()
+}
+
+fn from_wide(ptr: LPCWSTR) -> String {
+    unsafe { cstring::from_widechar_ptr(ptr).unwrap() }
+}
+
+unsafe fn parse(number_ptr: LPCWSTR) -> LPCWSTR {
+    export(|| {
+        let number: i64 = cstring::from_widechar_ptr(number_ptr)?.parse()?;
+        if number < 0 {
+            return Err(Error::msg("negative number"));
+        }
+        Ok(number.to_string())
+    })
+}
+
+#[test]
+fn roundtrip() {
+    let ptr = cstring::to_widechar_ptr("Привет, 🦀");
+    assert_eq!(from_wide(ptr), "Привет, 🦀");
+    assert_eq!(from_wide(wide("").as_ptr()), "");
+}
+
+#[test]
+fn recent_results_stay_valid() {
+    let results: Vec<LPCWSTR> = (0..KEEP_RESULTS)
+        .map(|i| cstring::to_widechar_ptr(i.to_string()))
+        .collect();
+
+    for (i, &ptr) in results.iter().enumerate() {
+        assert_eq!(from_wide(ptr), i.to_string());
+    }
+}
+
+#[test]
+fn errors_are_err_results() {
+    let parse = |s: &str| from_wide(unsafe { parse(wide(s).as_ptr()) });
+
+    assert_eq!(parse("42"), "42");
+    assert_eq!(parse("x"), "ERR|invalid digit found in string");
+    assert_eq!(parse("-1"), "ERR|negative number");
+}
+
+#[test]
+fn bad_pointers_are_errors() {
+    assert_eq!(
+        from_wide(unsafe { parse(ptr::null()) }),
+        "ERR|null string pointer"
+    );
+
+    // lone surrogate
+    let invalid = [0xd800, 0];
+    assert!(from_wide(unsafe { parse(invalid.as_ptr()) }).starts_with("ERR|invalid utf-16"));
+}
+
+#[test]
+fn panics_are_caught() {
+    let result = from_wide(export(|| -> Result<String, Error> { panic!("boom") }));
+    assert_eq!(result, "ERR|panic: boom");
+
+    let index = 10;
+    let result = from_wide(export(|| Ok([1, 2, 3][index].to_string())));
+    assert!(result.starts_with("ERR|panic: index out of bounds"));
+}
+
+mod info {
+    pk_ffi::info! {
+        author: "_Skill_",
+        version: "0.1",
+        description: "Описание",
+    }
+}
+
+#[test]
+fn info() {
+    assert_eq!(from_wide(info::info_getAuthor()), "_Skill_");
+    assert_eq!(from_wide(info::info_getVersion()), "0.1");
+    assert_eq!(from_wide(info::info_getDescription()), "Описание");
+}
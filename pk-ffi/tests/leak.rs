@@ -0,0 +1,62 @@
+//! Own test binary with a single test: the counting allocator sees every allocation of the process
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicIsize, Ordering},
+};
+
+use pk_ffi::{cstring, export, Error, KEEP_RESULTS, LPCWSTR};
+
+struct Counting;
+
+static LIVE_BYTES: AtomicIsize = AtomicIsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        LIVE_BYTES.fetch_add(layout.size() as isize, Ordering::SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        LIVE_BYTES.fetch_sub(layout.size() as isize, Ordering::SeqCst);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+const RESULT_LEN: usize = 1024;
+
+unsafe fn repeat(data_ptr: LPCWSTR) -> LPCWSTR {
+    export(|| Ok(cstring::from_widechar_ptr(data_ptr)?.repeat(RESULT_LEN)))
+}
+
+fn wide(s: &str) -> Vec<u16> {
+    s.encode_utf16().chain(Some(0)).collect()
+}
+
+#[test]
+fn results_are_freed() {
+    let input = wide("a");
+    let call = || unsafe { cstring::from_widechar_ptr(repeat(input.as_ptr())).unwrap() };
+    let err = || export(|| -> Result<String, Error> { Err(Error::msg("error")) });
+
+    // the ring of kept results stays allocated for the whole thread
+    for _ in 0..KEEP_RESULTS {
+        call();
+    }
+    let before = LIVE_BYTES.load(Ordering::SeqCst);
+
+    for _ in 0..20_000 {
+        assert_eq!(call().len(), RESULT_LEN);
+        err();
+    }
+
+    // leaked results would be tens of megabytes
+    let grown = LIVE_BYTES.load(Ordering::SeqCst) - before;
+    assert!(
+        grown < (KEEP_RESULTS * RESULT_LEN * 2) as isize,
+        "grown by {grown} bytes"
+    );
+}
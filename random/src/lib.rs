@@ -1,17 +1,13 @@
 mod array;
-mod dllmain;
 mod random;
 mod range;
 
-mod utils {
-    pub mod cstring;
-    pub mod macros;
+pk_ffi::dll_main!();
+pk_ffi::info! {
+    author: "_Skill_",
+    version: "0.2",
+    description: "Рандомизация чего-либо",
 }
 
-pub use crate::utils::*;
-
-pub const ERR: &str = "ERR|";
-const DEBUG: bool = false;
-
 #[global_allocator]
 static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
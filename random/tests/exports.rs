@@ -0,0 +1,109 @@
+//! Calls the exports by name the way Keeper does: null-terminated UTF-16 in and out
+
+extern crate random;
+
+fn wide(s: &str) -> Vec<u16> {
+    s.encode_utf16().chain(Some(0)).collect()
+}
+
+unsafe fn from_wide(ptr: *const u16) -> String {
+    let len = (0..).take_while(|&i| *ptr.add(i) != 0).count();
+    String::from_utf16(std::slice::from_raw_parts(ptr, len)).unwrap()
+}
+
+macro_rules! exports {
+    ($($name:ident($($arg:ident),*);)+) => {
+        #[allow(non_snake_case)]
+        mod ffi {
+            extern "system" {
+                $(pub fn $name($($arg: *const u16),*) -> *const u16;)+
+            }
+        }
+
+        $(
+            #[allow(non_snake_case)]
+            fn $name($($arg: &str),*) -> String {
+                $(let $arg = wide($arg);)*
+                unsafe { from_wide(ffi::$name($($arg.as_ptr()),*)) }
+            }
+        )+
+    };
+}
+
+exports! {
+    shuffle(array);
+    choice(array);
+    uuidv4();
+    fill_with(string, fill_string, num, index);
+    rand_regex(pattern);
+    range(from, to);
+    rangef(from, to, precision);
+    info_getAuthor();
+    info_getVersion();
+    info_getDescription();
+}
+
+#[test]
+fn shuffle_array() {
+    let shuffled: Vec<u32> = serde_json::from_str(&shuffle("[1,2,3,4,5]")).unwrap();
+    let mut sorted = shuffled.clone();
+    sorted.sort();
+    assert_eq!(sorted, [1, 2, 3, 4, 5]);
+    assert_eq!(shuffle("{}"), "ERR|can't interpret as array");
+    assert!(shuffle("[").starts_with("ERR|"));
+}
+
+#[test]
+fn choice_from_array() {
+    assert!(["1", "\"два\"", "[3]"].contains(&choice(r#"[1,"два",[3]]"#).as_str()));
+    assert_eq!(choice("1"), "ERR|can't interpret as array");
+}
+
+#[test]
+fn uuid() {
+    let uuid = uuidv4();
+    assert_eq!(uuid.len(), 36);
+    assert_eq!(uuid.as_bytes()[14], b'4');
+    assert_ne!(uuid, uuidv4());
+}
+
+#[test]
+fn fill() {
+    assert_eq!(fill_with("7", "0", "3", "0"), "007");
+    assert_eq!(fill_with("7", "0", "3", ""), "007");
+    assert_eq!(fill_with("ab", "-", "4", "1"), "a--b");
+    assert_eq!(fill_with("12345", "0", "3", "0"), "12345");
+    assert!(fill_with("7", "0", "three", "0").starts_with("ERR|"));
+}
+
+#[test]
+fn regex() {
+    let sample = rand_regex("[a-f]{8}");
+    assert_eq!(sample.len(), 8);
+    assert!(sample.chars().all(|c| ('a'..='f').contains(&c)));
+    assert!(rand_regex("(").starts_with("ERR|"));
+}
+
+#[test]
+fn int_range() {
+    assert_eq!(range("1", "2"), "1");
+    assert!((-5..5).contains(&range("-5", "5").parse::<i64>().unwrap()));
+    assert_eq!(range("5", "5"), "ERR|`to` must be larger than `from`");
+    assert!(range("a", "5").starts_with("ERR|"));
+}
+
+#[test]
+fn float_range() {
+    let value = rangef("0", "1", "3");
+    assert_eq!(value.len(), 5);
+    assert!((0.0..=1.0).contains(&value.parse::<f64>().unwrap()));
+    assert_eq!(rangef("1", "0", "2"), "ERR|`to` must be larger than `from`");
+    assert!(rangef("0", "1", "-1").starts_with("ERR|"));
+}
+
+#[test]
+fn info() {
+    assert_eq!(info_getAuthor(), "_Skill_");
+    assert_eq!(info_getVersion(), "0.2");
+    assert_eq!(info_getDescription(), "Рандомизация чего-либо");
+}
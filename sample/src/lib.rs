@@ -1,9 +1,8 @@
 mod sample;
-mod dllmain;
-mod utils {
-    pub mod cstring;
-}
 
-pub use crate::{
-    utils::*,
-};
+pk_ffi::dll_main!();
+pk_ffi::info! {
+    author: "_Skill_",
+    version: "0.1",
+    description: "desc",
+}
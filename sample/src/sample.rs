@@ -1,11 +1,10 @@
-use std::mem;
+use pk_ffi::{cstring, export, LPCWSTR};
 
-#[no_mangle]
 #[export_name = "returnTheSame"]
-pub extern "stdcall" fn return_the_same(data_ptr: *const u16) -> *const u16 {
-    let string = crate::utils::cstring::widechar_to_string(data_ptr);
+pub unsafe extern "system" fn return_the_same(data_ptr: LPCWSTR) -> LPCWSTR {
+    export(|| {
+        let string = cstring::from_widechar_ptr(data_ptr)?;
 
-    let wstring= crate::utils::cstring::string_as_widechar(string.unwrap().as_str());
-
-    mem::ManuallyDrop::new(wstring).as_ptr()
-}
\ No newline at end of file
+        Ok(string)
+    })
+}
@@ -0,0 +1,52 @@
+//! Calls the exports by name the way Keeper does: null-terminated UTF-16 in and out
+
+extern crate sample;
+
+fn wide(s: &str) -> Vec<u16> {
+    s.encode_utf16().chain(Some(0)).collect()
+}
+
+unsafe fn from_wide(ptr: *const u16) -> String {
+    let len = (0..).take_while(|&i| *ptr.add(i) != 0).count();
+    String::from_utf16(std::slice::from_raw_parts(ptr, len)).unwrap()
+}
+
+macro_rules! exports {
+    ($($name:ident($($arg:ident),*);)+) => {
+        #[allow(non_snake_case)]
+        mod ffi {
+            extern "system" {
+                $(pub fn $name($($arg: *const u16),*) -> *const u16;)+
+            }
+        }
+
+        $(
+            #[allow(non_snake_case)]
+            fn $name($($arg: &str),*) -> String {
+                $(let $arg = wide($arg);)*
+                unsafe { from_wide(ffi::$name($($arg.as_ptr()),*)) }
+            }
+        )+
+    };
+}
+
+exports! {
+    returnTheSame(data);
+    info_getAuthor();
+    info_getVersion();
+    info_getDescription();
+}
+
+#[test]
+fn return_the_same() {
+    assert_eq!(returnTheSame(""), "");
+    assert_eq!(returnTheSame("test"), "test");
+    assert_eq!(returnTheSame("Привет, 🦀"), "Привет, 🦀");
+}
+
+#[test]
+fn info() {
+    assert_eq!(info_getAuthor(), "_Skill_");
+    assert_eq!(info_getVersion(), "0.1");
+    assert_eq!(info_getDescription(), "desc");
+}
@@ -1,19 +1,18 @@
-mod dllmain;
 mod svg;
 
 mod utils {
-    pub mod cstring;
-    pub mod macros;
     pub mod svg;
 }
 
 #[macro_use]
 extern crate lazy_static;
 
-pub use crate::utils::*;
-
-pub const ERR: &str = "ERR|";
-pub const DEBUG: bool = false;
+pk_ffi::dll_main!();
+pk_ffi::info! {
+    author: "_Skill_",
+    version: "0.1",
+    description: "SVG to PNG",
+}
 
 #[global_allocator]
 static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
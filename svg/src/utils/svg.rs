@@ -1,3 +1,4 @@
+use pk_ffi::Error;
 use resvg::ScreenSize;
 use tiny_skia::Color;
 use usvg::Options;
@@ -22,11 +23,12 @@ pub fn render(
     svg_data: Vec<u8>,
     size: Option<ScreenSize>,
     color: Option<Color>,
-) -> Result<Vec<u8>, png::EncodingError> {
-    let rtree = usvg::Tree::from_data(&svg_data, &OPT.to_ref()).unwrap();
+) -> Result<Vec<u8>, Error> {
+    let rtree = usvg::Tree::from_data(&svg_data, &OPT.to_ref())?;
     let pixmap_size = size.unwrap_or_else(|| rtree.svg_node().size.to_screen_size());
 
-    let mut pixmap = tiny_skia::Pixmap::new(pixmap_size.width(), pixmap_size.height()).unwrap();
+    let mut pixmap = tiny_skia::Pixmap::new(pixmap_size.width(), pixmap_size.height())
+        .ok_or_else(|| Error::msg("invalid image size"))?;
 
     if let Some(color) = color {
         pixmap.fill(color);
@@ -43,6 +45,6 @@ pub fn render(
         tiny_skia::Transform::default(),
         pixmap.as_mut(),
     )
-    .unwrap();
-    pixmap.encode_png()
+    .ok_or_else(|| Error::msg("can't render svg"))?;
+    Ok(pixmap.encode_png()?)
 }
@@ -0,0 +1,80 @@
+//! Calls the exports by name the way Keeper does: null-terminated UTF-16 in and out
+
+extern crate svg;
+
+fn wide(s: &str) -> Vec<u16> {
+    s.encode_utf16().chain(Some(0)).collect()
+}
+
+unsafe fn from_wide(ptr: *const u16) -> String {
+    let len = (0..).take_while(|&i| *ptr.add(i) != 0).count();
+    String::from_utf16(std::slice::from_raw_parts(ptr, len)).unwrap()
+}
+
+macro_rules! exports {
+    ($($name:ident($($arg:ident),*);)+) => {
+        #[allow(non_snake_case)]
+        mod ffi {
+            extern "system" {
+                $(pub fn $name($($arg: *const u16),*) -> *const u16;)+
+            }
+        }
+
+        $(
+            #[allow(non_snake_case)]
+            fn $name($($arg: &str),*) -> String {
+                $(let $arg = wide($arg);)*
+                unsafe { from_wide(ffi::$name($($arg.as_ptr()),*)) }
+            }
+        )+
+    };
+}
+
+exports! {
+    render(svg_data, width, height, r, g, b, a);
+    info_getAuthor();
+    info_getVersion();
+    info_getDescription();
+}
+
+const SVG: &str = r#"<svg xmlns="http://www.w3.org/2000/svg" width="20" height="10">
+  <rect width="20" height="10" fill="red"/>
+</svg>"#;
+
+fn png_size(png: &str) -> (u32, u32) {
+    let png = base64::decode(png).unwrap_or_else(|_| panic!("not a base64: {png}"));
+    assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+    let size = |at: usize| u32::from_be_bytes(png[at..at + 4].try_into().unwrap());
+    (size(16), size(20))
+}
+
+#[test]
+fn render_png() {
+    let svg = base64::encode(SVG);
+    assert_eq!(
+        png_size(&render(&svg, "0", "0", "0", "0", "0", "0")),
+        (20, 10)
+    );
+    assert_eq!(
+        png_size(&render(&svg, "40", "20", "255", "255", "255", "255")),
+        (40, 20)
+    );
+}
+
+#[test]
+fn render_invalid() {
+    let svg = base64::encode(SVG);
+    assert!(render("<svg/>", "0", "0", "0", "0", "0", "0").starts_with("ERR|"));
+    // used to crash Keeper on unwrap
+    let not_svg = base64::encode("not an svg");
+    assert!(render(&not_svg, "0", "0", "0", "0", "0", "0").starts_with("ERR|"));
+    assert!(render(&svg, "wide", "0", "0", "0", "0", "0").starts_with("ERR|"));
+    assert!(render(&svg, "0", "0", "0", "0", "0", "opaque").starts_with("ERR|"));
+}
+
+#[test]
+fn info() {
+    assert_eq!(info_getAuthor(), "_Skill_");
+    assert_eq!(info_getVersion(), "0.1");
+    assert_eq!(info_getDescription(), "SVG to PNG");
+}
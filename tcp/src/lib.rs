@@ -1,18 +1,17 @@
+#[cfg(windows)]
 mod dllmain;
 mod tcp;
 
 mod utils {
-    pub mod cstring;
     pub mod error;
-    pub mod macros;
     pub mod proxy;
     pub mod statuses;
     pub mod tcp;
     pub mod traits;
 }
 
-use crate::dllmain::DEBUG;
 use once_cell::sync::Lazy;
+use pk_ffi::debug;
 use threadpool::{Builder, ThreadPool};
 
 use crate::{statuses::Task, traits::TcpThread, utils::*};
@@ -22,7 +21,11 @@ use std::{
     time::{Duration, Instant},
 };
 
-pub const ERR: &str = "ERR|";
+pk_ffi::info! {
+    author: "_Skill_",
+    version: "0.2",
+    description: "TCP с поддержкой прокси",
+}
 
 /*#[global_allocator]
 static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
@@ -43,7 +46,7 @@ static THREAD_POOL: Lazy<Mutex<ThreadPool>> = Lazy::new(|| {
     pool.execute(move || {
         let cache = Arc::clone(&CACHE);
         loop {
-            if let Ok(_) = recv.recv_timeout(Duration::from_secs(60)) {
+            if recv.recv_timeout(Duration::from_secs(60)).is_ok() {
                 debug!("Clearing cache last time!");
                 cleanup(&cache);
                 debug!("Interrupted clearing cache!");
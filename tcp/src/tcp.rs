@@ -2,19 +2,16 @@ use crossbeam_channel::{Receiver as _Receiver, Sender as _Sender};
 use std::time::{Duration, Instant};
 
 use crossbeam_channel::bounded;
+use pk_ffi::{cstring, debug, export, LPCWSTR};
 use uuid::Uuid;
-use winapi::um::winnt::LPCWSTR;
 
-use crate::debug;
 use crate::{
-    cstring,
     error::{DllError, GlobalError},
     proxy::Proxy,
     statuses::DllStatus,
     traits::ThreadResult,
-    unwrap_or_err,
     utils::tcp,
-    Task, TcpThread, CACHE, DEBUG, THREAD_POOL,
+    Task, TcpThread, CACHE, THREAD_POOL,
 };
 
 pub const TTL: Duration = Duration::from_secs(30);
@@ -23,334 +20,309 @@ type Sender = _Sender<Result<ThreadResult, GlobalError>>;
 type Receiver = _Receiver<Result<ThreadResult, GlobalError>>;
 
 #[no_mangle]
-pub extern "stdcall" fn connect_ip(
+pub unsafe extern "system" fn connect_ip(
     addr_ptr: LPCWSTR,
     proxy_addr_ptr: LPCWSTR,
     timeout_ptr: LPCWSTR,
     proxy_resolve_ptr: LPCWSTR,
     use_tls_ptr: LPCWSTR,
 ) -> LPCWSTR {
-    let addr = cstring::from_widechar_ptr(addr_ptr);
-    let proxy_addr = cstring::from_widechar_ptr(proxy_addr_ptr);
-    let timeout = cstring::from_widechar_ptr(timeout_ptr);
-    let proxy_resolve: bool = cstring::from_widechar_ptr(proxy_resolve_ptr)
-        .parse()
-        .unwrap_or_default();
-    let use_tls: bool = cstring::from_widechar_ptr(use_tls_ptr)
-        .parse()
-        .unwrap_or_default();
-
-    let timeout: u64 = unwrap_or_err!(timeout.parse());
-    let timeout = match timeout {
-        0 => None,
-        _ => Some(Duration::from_millis(timeout)),
-    };
+    export(|| {
+        let addr = cstring::from_widechar_ptr(addr_ptr)?;
+        let proxy_addr = cstring::from_widechar_ptr(proxy_addr_ptr)?;
+        let timeout = cstring::from_widechar_ptr(timeout_ptr)?;
+        let proxy_resolve: bool = cstring::from_widechar_ptr(proxy_resolve_ptr)?
+            .parse()
+            .unwrap_or_default();
+        let use_tls: bool = cstring::from_widechar_ptr(use_tls_ptr)?
+            .parse()
+            .unwrap_or_default();
+
+        let timeout: u64 = timeout.parse()?;
+        let timeout = match timeout {
+            0 => None,
+            _ => Some(Duration::from_millis(timeout)),
+        };
 
-    let mut proxy: Option<Proxy> = None;
+        let mut proxy: Option<Proxy> = None;
 
-    if proxy_addr != ":" {
-        proxy = Some(unwrap_or_err!(Proxy::from_pk_str(proxy_addr)));
-    }
+        if proxy_addr != ":" {
+            proxy = Some(Proxy::from_pk_str(proxy_addr)?);
+        }
 
-    debug!(
-        "[Connect] Addr: {}, proxy: {:?}, timeout: {:?}, proxy_resolve: {}, use_tls: {}",
-        addr, proxy, timeout, proxy_resolve, use_tls
-    );
-
-    let (flag, control) = thread_control::make_pair();
-    let (sender, recv): (Sender, Receiver) = bounded(1);
-
-    THREAD_POOL.lock().unwrap().execute(move || {
-        flag.alive();
-        debug!("After alive");
-        let result =
-            tcp::connect(addr, proxy, timeout, proxy_resolve, use_tls).map_err(GlobalError::from);
-        debug!("After result");
-        let result = sender.send(result);
-        debug!("Sent: {:?}", result);
-    });
-
-    debug!("After spawn");
-
-    let tcp_thread = TcpThread {
-        stream: None,
-        join_handler: Some(recv),
-        thread_control: control,
-        current_task: Task::Connect,
-        ttl: Instant::now() + TTL,
-    };
+        debug!(
+            "[Connect] Addr: {}, proxy: {:?}, timeout: {:?}, proxy_resolve: {}, use_tls: {}",
+            addr, proxy, timeout, proxy_resolve, use_tls
+        );
+
+        let (flag, control) = thread_control::make_pair();
+        let (sender, recv): (Sender, Receiver) = bounded(1);
+
+        THREAD_POOL.lock()?.execute(move || {
+            flag.alive();
+            debug!("After alive");
+            let result = tcp::connect(addr, proxy, timeout, proxy_resolve, use_tls);
+            debug!("After result");
+            let result = sender.send(result);
+            debug!("Sent: {:?}", result);
+        });
+
+        debug!("After spawn");
+
+        let tcp_thread = TcpThread {
+            stream: None,
+            join_handler: Some(recv),
+            thread_control: control,
+            current_task: Task::Connect,
+            ttl: Instant::now() + TTL,
+        };
 
-    let uuid = Uuid::new_v4().to_hyphenated().to_string();
+        let uuid = Uuid::new_v4().to_hyphenated().to_string();
 
-    let mut w = unwrap_or_err!(CACHE.write());
-    w.insert(uuid.clone(), tcp_thread);
+        let mut w = CACHE.write()?;
+        w.insert(uuid.clone(), tcp_thread);
 
-    debug!("Uuid: {}", uuid);
+        debug!("Uuid: {}", uuid);
 
-    cstring::to_widechar_ptr(uuid)
+        Ok(uuid)
+    })
 }
 
 #[no_mangle]
-pub extern "stdcall" fn send_data(uuid_ptr: LPCWSTR, data_ptr: LPCWSTR) -> LPCWSTR {
-    let uuid = cstring::from_widechar_ptr(uuid_ptr);
-    if let Err(error) = stream_exists(&uuid) {
-        return cstring::to_widechar_ptr(error.to_string());
-    }
+pub unsafe extern "system" fn send_data(uuid_ptr: LPCWSTR, data_ptr: LPCWSTR) -> LPCWSTR {
+    export(|| {
+        let uuid = cstring::from_widechar_ptr(uuid_ptr)?;
+        stream_exists(&uuid)?;
 
-    let data_str = cstring::from_widechar_ptr(data_ptr);
-    let data = unwrap_or_err!(base64::decode(&data_str));
+        let data_str = cstring::from_widechar_ptr(data_ptr)?;
+        let data = base64::decode(&data_str)?;
 
-    let mut w = unwrap_or_err!(CACHE.write());
-    let mut tcp_thread = match w.get_mut(&uuid) {
-        Some(tcp_thread) => tcp_thread,
-        None => return cstring::to_widechar_ptr(DllError::ConnectionNotFound.to_string()),
-    };
+        let mut w = CACHE.write()?;
+        let tcp_thread = w.get_mut(&uuid).ok_or(DllError::ConnectionNotFound)?;
 
-    tcp_thread.increase_ttl();
+        tcp_thread.increase_ttl();
 
-    let stream = tcp_thread.stream.take().unwrap();
+        let stream = tcp_thread.stream.take().unwrap();
 
-    debug!("[Send] uuid: {}, data: {}", uuid, data_str);
+        debug!("[Send] uuid: {}, data: {}", uuid, data_str);
 
-    let (flag, control) = thread_control::make_pair();
+        let (flag, control) = thread_control::make_pair();
 
-    let (sender, recv): (Sender, Receiver) = bounded(1);
+        let (sender, recv): (Sender, Receiver) = bounded(1);
 
-    THREAD_POOL.lock().unwrap().execute(move || {
-        flag.alive();
-        let result = tcp::send_data(stream, data).map_err(GlobalError::from);
-        let result = sender.send(result);
-        debug!("Sent: {:?}", result);
-    });
+        THREAD_POOL.lock()?.execute(move || {
+            flag.alive();
+            let result = tcp::send_data(stream, data).map_err(GlobalError::from);
+            let result = sender.send(result);
+            debug!("Sent: {:?}", result);
+        });
 
-    tcp_thread.thread_control = control;
-    tcp_thread.join_handler = Some(recv);
-    tcp_thread.current_task = Task::SendData;
+        tcp_thread.thread_control = control;
+        tcp_thread.join_handler = Some(recv);
+        tcp_thread.current_task = Task::SendData;
 
-    cstring::to_widechar_ptr(DllStatus::ThreadSpawned.as_str())
+        Ok(DllStatus::ThreadSpawned.as_str())
+    })
 }
 
 #[no_mangle]
-pub extern "stdcall" fn recv_exact(uuid_ptr: LPCWSTR, len_ptr: LPCWSTR) -> LPCWSTR {
-    let uuid = cstring::from_widechar_ptr(uuid_ptr);
-    if let Err(error) = stream_exists(&uuid) {
-        return cstring::to_widechar_ptr(error.to_string());
-    }
+pub unsafe extern "system" fn recv_exact(uuid_ptr: LPCWSTR, len_ptr: LPCWSTR) -> LPCWSTR {
+    export(|| {
+        let uuid = cstring::from_widechar_ptr(uuid_ptr)?;
+        stream_exists(&uuid)?;
 
-    let len = cstring::from_widechar_ptr(len_ptr);
-    let len: usize = unwrap_or_err!(len.parse());
+        let len = cstring::from_widechar_ptr(len_ptr)?;
+        let len: usize = len.parse()?;
 
-    let mut w = unwrap_or_err!(CACHE.write());
-    let mut tcp_thread = match w.get_mut(&uuid) {
-        Some(tcp_thread) => tcp_thread,
-        None => return cstring::to_widechar_ptr(DllError::ConnectionNotFound.to_string()),
-    };
+        let mut w = CACHE.write()?;
+        let tcp_thread = w.get_mut(&uuid).ok_or(DllError::ConnectionNotFound)?;
 
-    tcp_thread.increase_ttl();
+        tcp_thread.increase_ttl();
 
-    let stream = tcp_thread.stream.take().unwrap();
+        let stream = tcp_thread.stream.take().unwrap();
 
-    debug!("[RecvExact] uuid: {}, len: {}", uuid, len);
+        debug!("[RecvExact] uuid: {}, len: {}", uuid, len);
 
-    let (flag, control) = thread_control::make_pair();
+        let (flag, control) = thread_control::make_pair();
 
-    let (sender, recv): (Sender, Receiver) = bounded(1);
+        let (sender, recv): (Sender, Receiver) = bounded(1);
 
-    THREAD_POOL.lock().unwrap().execute(move || {
-        flag.alive();
-        let result = tcp::read_exact(stream, len).map_err(GlobalError::from);
-        let result = sender.send(result);
-        debug!("Sent: {:?}", result);
-    });
+        THREAD_POOL.lock()?.execute(move || {
+            flag.alive();
+            let result = tcp::read_exact(stream, len).map_err(GlobalError::from);
+            let result = sender.send(result);
+            debug!("Sent: {:?}", result);
+        });
 
-    tcp_thread.thread_control = control;
-    tcp_thread.join_handler = Some(recv);
-    tcp_thread.current_task = Task::RecvExact;
+        tcp_thread.thread_control = control;
+        tcp_thread.join_handler = Some(recv);
+        tcp_thread.current_task = Task::RecvExact;
 
-    cstring::to_widechar_ptr(DllStatus::ThreadSpawned.as_str())
+        Ok(DllStatus::ThreadSpawned.as_str())
+    })
 }
 
 #[no_mangle]
-pub extern "stdcall" fn recv_until(uuid_ptr: LPCWSTR, until_ptr: LPCWSTR) -> LPCWSTR {
-    let uuid = cstring::from_widechar_ptr(uuid_ptr);
-    if let Err(error) = stream_exists(&uuid) {
-        return cstring::to_widechar_ptr(error.to_string());
-    }
+pub unsafe extern "system" fn recv_until(uuid_ptr: LPCWSTR, until_ptr: LPCWSTR) -> LPCWSTR {
+    export(|| {
+        let uuid = cstring::from_widechar_ptr(uuid_ptr)?;
+        stream_exists(&uuid)?;
 
-    let until_str = cstring::from_widechar_ptr(until_ptr);
-    let until = unwrap_or_err!(base64::decode(&until_str));
+        let until_str = cstring::from_widechar_ptr(until_ptr)?;
+        let until = base64::decode(&until_str)?;
 
-    let mut w = unwrap_or_err!(CACHE.write());
-    let mut tcp_thread = match w.get_mut(&uuid) {
-        Some(tcp_thread) => tcp_thread,
-        None => return cstring::to_widechar_ptr(DllError::ConnectionNotFound.to_string()),
-    };
+        let mut w = CACHE.write()?;
+        let tcp_thread = w.get_mut(&uuid).ok_or(DllError::ConnectionNotFound)?;
 
-    tcp_thread.increase_ttl();
+        tcp_thread.increase_ttl();
 
-    let stream = tcp_thread.stream.take().unwrap();
+        let stream = tcp_thread.stream.take().unwrap();
 
-    debug!("[RecvUntil] uuid: {}, until: {}", uuid, until_str);
+        debug!("[RecvUntil] uuid: {}, until: {}", uuid, until_str);
 
-    let (flag, control) = thread_control::make_pair();
+        let (flag, control) = thread_control::make_pair();
 
-    let (sender, recv): (Sender, Receiver) = bounded(1);
+        let (sender, recv): (Sender, Receiver) = bounded(1);
 
-    THREAD_POOL.lock().unwrap().execute(move || {
-        flag.alive();
-        let result = tcp::read_until(stream, until).map_err(GlobalError::from);
-        let result = sender.send(result);
-        debug!("Sent: {:?}", result);
-    });
+        THREAD_POOL.lock()?.execute(move || {
+            flag.alive();
+            let result = tcp::read_until(stream, until).map_err(GlobalError::from);
+            let result = sender.send(result);
+            debug!("Sent: {:?}", result);
+        });
 
-    tcp_thread.thread_control = control;
-    tcp_thread.join_handler = Some(recv);
-    tcp_thread.current_task = Task::RecvUntil;
+        tcp_thread.thread_control = control;
+        tcp_thread.join_handler = Some(recv);
+        tcp_thread.current_task = Task::RecvUntil;
 
-    cstring::to_widechar_ptr(DllStatus::ThreadSpawned.as_str())
+        Ok(DllStatus::ThreadSpawned.as_str())
+    })
 }
 
 #[no_mangle]
-pub extern "stdcall" fn recv_end(uuid_ptr: LPCWSTR) -> LPCWSTR {
-    let uuid = cstring::from_widechar_ptr(uuid_ptr);
-    if let Err(error) = stream_exists(&uuid) {
-        return cstring::to_widechar_ptr(error.to_string());
-    }
+pub unsafe extern "system" fn recv_end(uuid_ptr: LPCWSTR) -> LPCWSTR {
+    export(|| {
+        let uuid = cstring::from_widechar_ptr(uuid_ptr)?;
+        stream_exists(&uuid)?;
 
-    let mut w = unwrap_or_err!(CACHE.write());
-    let mut tcp_thread = match w.get_mut(&uuid) {
-        Some(tcp_thread) => tcp_thread,
-        None => return cstring::to_widechar_ptr(DllError::ConnectionNotFound.to_string()),
-    };
+        let mut w = CACHE.write()?;
+        let tcp_thread = w.get_mut(&uuid).ok_or(DllError::ConnectionNotFound)?;
 
-    tcp_thread.increase_ttl();
+        tcp_thread.increase_ttl();
 
-    let stream = tcp_thread.stream.take().unwrap();
+        let stream = tcp_thread.stream.take().unwrap();
 
-    let (flag, control) = thread_control::make_pair();
+        let (flag, control) = thread_control::make_pair();
 
-    debug!("[RecvEnd] uuid: {}", uuid);
+        debug!("[RecvEnd] uuid: {}", uuid);
 
-    let (sender, recv): (Sender, Receiver) = bounded(1);
+        let (sender, recv): (Sender, Receiver) = bounded(1);
 
-    THREAD_POOL.lock().unwrap().execute(move || {
-        flag.alive();
-        let result = tcp::read_to_end(stream).map_err(GlobalError::from);
-        let result = sender.send(result);
-        debug!("Sent: {:?}", result);
-    });
+        THREAD_POOL.lock()?.execute(move || {
+            flag.alive();
+            let result = tcp::read_to_end(stream).map_err(GlobalError::from);
+            let result = sender.send(result);
+            debug!("Sent: {:?}", result);
+        });
 
-    tcp_thread.thread_control = control;
-    tcp_thread.join_handler = Some(recv);
-    tcp_thread.current_task = Task::RecvEnd;
+        tcp_thread.thread_control = control;
+        tcp_thread.join_handler = Some(recv);
+        tcp_thread.current_task = Task::RecvEnd;
 
-    cstring::to_widechar_ptr(DllStatus::ThreadSpawned.as_str())
+        Ok(DllStatus::ThreadSpawned.as_str())
+    })
 }
 
 #[no_mangle]
-pub extern "stdcall" fn disconnect(uuid_ptr: LPCWSTR) -> LPCWSTR {
-    let uuid = cstring::from_widechar_ptr(uuid_ptr);
+pub unsafe extern "system" fn disconnect(uuid_ptr: LPCWSTR) -> LPCWSTR {
+    export(|| {
+        let uuid = cstring::from_widechar_ptr(uuid_ptr)?;
 
-    debug!("[Disconnect] uuid: {}", uuid);
+        debug!("[Disconnect] uuid: {}", uuid);
 
-    let mut mx = unwrap_or_err!(CACHE.write());
-    match mx.remove(&uuid) {
-        Some(_) => cstring::to_widechar_ptr(DllStatus::Ok.as_str()),
-        None => cstring::to_widechar_ptr(DllError::ConnectionNotFound.to_string()),
-    }
+        let mut mx = CACHE.write()?;
+        mx.remove(&uuid).ok_or(DllError::ConnectionNotFound)?;
+
+        Ok(DllStatus::Ok.as_str())
+    })
 }
 
 #[no_mangle]
-pub extern "stdcall" fn task_status(uuid_ptr: LPCWSTR) -> LPCWSTR {
-    let uuid = cstring::from_widechar_ptr(uuid_ptr);
+pub unsafe extern "system" fn task_status(uuid_ptr: LPCWSTR) -> LPCWSTR {
+    export(|| {
+        let uuid = cstring::from_widechar_ptr(uuid_ptr)?;
 
-    if let Err(error) = is_task_running(&uuid) {
-        return cstring::to_widechar_ptr(error.to_string());
-    }
+        is_task_running(&uuid)?;
 
-    let mut r = unwrap_or_err!(CACHE.write());
-    let mut tcp_thread = match r.get_mut(&uuid) {
-        Some(stream) => stream,
-        None => return cstring::to_widechar_ptr(DllError::ConnectionNotFound.to_string()),
-    };
+        let mut r = CACHE.write()?;
+        let tcp_thread = r.get_mut(&uuid).ok_or(DllError::ConnectionNotFound)?;
 
-    if !tcp_thread.thread_control.is_done() {
-        return cstring::to_widechar_ptr(DllStatus::NotYetReady.as_str());
-    }
+        if !tcp_thread.thread_control.is_done() {
+            return Ok(DllStatus::NotYetReady.as_str().to_owned());
+        }
 
-    tcp_thread.increase_ttl();
+        tcp_thread.increase_ttl();
 
-    let thread_result = unwrap_or_err!(tcp_thread.join_handler.take().unwrap().recv().unwrap());
+        let thread_result = tcp_thread.join_handler.take().unwrap().recv()??;
 
-    tcp_thread.stream = Some(thread_result.stream);
+        tcp_thread.stream = Some(thread_result.stream);
 
-    debug!("[TaskStatus] uuid: {}, tcp_thread: {:?}", uuid, tcp_thread);
+        debug!("[TaskStatus] uuid: {}, tcp_thread: {:?}", uuid, tcp_thread);
 
-    match tcp_thread.current_task {
-        Task::RecvExact | Task::RecvUntil | Task::RecvEnd => {
-            let result = base64::encode(thread_result.buffer.unwrap());
-            return cstring::to_widechar_ptr(&result);
+        match tcp_thread.current_task {
+            Task::RecvExact | Task::RecvUntil | Task::RecvEnd => {
+                Ok(base64::encode(thread_result.buffer.unwrap()))
+            }
+            _ => Ok(tcp_thread.current_task.as_str().to_owned()),
         }
-        _ => (),
-    };
-
-    cstring::to_widechar_ptr(tcp_thread.current_task.as_str())
+    })
 }
 
 #[no_mangle]
-pub extern "stdcall" fn set_read_timeout(uuid_ptr: LPCWSTR, timeout_ptr: LPCWSTR) -> LPCWSTR {
-    let uuid = cstring::from_widechar_ptr(uuid_ptr);
-    let timeout = cstring::from_widechar_ptr(timeout_ptr);
-
-    let timeout = match unwrap_or_err!(timeout.parse::<u64>()) {
-        0 => None,
-        timeout => Some(Duration::from_millis(timeout)),
-    };
+pub unsafe extern "system" fn set_read_timeout(uuid_ptr: LPCWSTR, timeout_ptr: LPCWSTR) -> LPCWSTR {
+    export(|| {
+        let uuid = cstring::from_widechar_ptr(uuid_ptr)?;
+        let timeout = cstring::from_widechar_ptr(timeout_ptr)?;
+
+        let timeout = match timeout.parse::<u64>()? {
+            0 => None,
+            timeout => Some(Duration::from_millis(timeout)),
+        };
 
-    let r = unwrap_or_err!(CACHE.read());
-    let tcp_thread = match r.get(&uuid) {
-        Some(tcp_thread) => tcp_thread,
-        None => return cstring::to_widechar_ptr(DllError::ConnectionNotFound.to_string()),
-    };
+        let r = CACHE.read()?;
+        let tcp_thread = r.get(&uuid).ok_or(DllError::ConnectionNotFound)?;
+        let stream = tcp_thread.stream.as_ref().ok_or(DllError::NoTcpStream)?;
 
-    if tcp_thread.stream.is_none() {
-        return cstring::to_widechar_ptr(DllError::NoTcpStream.to_string());
-    }
+        debug!("[SetReadTimeout] uuid: {}, timeout: {:?}", uuid, timeout);
 
-    debug!("[SetReadTimeout] uuid: {}, timeout: {:?}", uuid, timeout);
-
-    unwrap_or_err!(tcp_thread
-        .stream
-        .as_ref()
-        .unwrap()
-        .set_read_timeout(timeout));
-    cstring::to_widechar_ptr("OK")
+        stream.set_read_timeout(timeout)?;
+        Ok("OK")
+    })
 }
 
 #[no_mangle]
-pub extern "stdcall" fn set_write_timeout(uuid_ptr: LPCWSTR, timeout_ptr: LPCWSTR) -> LPCWSTR {
-    let uuid = cstring::from_widechar_ptr(uuid_ptr);
-    let timeout = cstring::from_widechar_ptr(timeout_ptr);
-
-    let timeout = match unwrap_or_err!(timeout.parse::<u64>()) {
-        0 => None,
-        timeout => Some(Duration::from_millis(timeout)),
-    };
+pub unsafe extern "system" fn set_write_timeout(
+    uuid_ptr: LPCWSTR,
+    timeout_ptr: LPCWSTR,
+) -> LPCWSTR {
+    export(|| {
+        let uuid = cstring::from_widechar_ptr(uuid_ptr)?;
+        let timeout = cstring::from_widechar_ptr(timeout_ptr)?;
 
-    let r = unwrap_or_err!(CACHE.read());
-    let stream = match r.get(&uuid) {
-        Some(stream) => stream,
-        None => return cstring::to_widechar_ptr(DllError::ConnectionNotFound.to_string()),
-    };
+        let timeout = match timeout.parse::<u64>()? {
+            0 => None,
+            timeout => Some(Duration::from_millis(timeout)),
+        };
 
-    if stream.stream.is_none() {
-        return cstring::to_widechar_ptr(DllError::NoTcpStream.to_string());
-    }
+        let r = CACHE.read()?;
+        let tcp_thread = r.get(&uuid).ok_or(DllError::ConnectionNotFound)?;
+        let stream = tcp_thread.stream.as_ref().ok_or(DllError::NoTcpStream)?;
 
-    debug!("[SetWriteTimeout] uuid: {}, timeout: {:?}", uuid, timeout);
+        debug!("[SetWriteTimeout] uuid: {}, timeout: {:?}", uuid, timeout);
 
-    unwrap_or_err!(stream.stream.as_ref().unwrap().set_write_timeout(timeout));
-    cstring::to_widechar_ptr("OK")
+        stream.set_write_timeout(timeout)?;
+        Ok("OK")
+    })
 }
 
 fn is_task_running(uuid: &str) -> Result<(), DllError> {
@@ -43,14 +43,14 @@ pub enum ConnectionError {
 
 #[derive(Debug, Error)]
 pub enum DllError {
-    #[error("ERR|connection not found")]
+    #[error("connection not found")]
     ConnectionNotFound,
 
-    #[error("ERR|no active task")]
+    #[error("no active task")]
     NoTaskRunning,
 
     #[error(
-        "ERR|no tcp stream (either certain task is running or connection has not created yet)"
+        "no tcp stream (either certain task is running or connection has not created yet)"
     )]
     NoTcpStream,
 }
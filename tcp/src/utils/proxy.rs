@@ -91,7 +91,7 @@ where
     let auth = match auth {
         Authentication::None => "".to_owned(),
         Authentication::Password { username, password } => {
-            let creds = base64::encode(&format!("{}:{}", username, password));
+            let creds = base64::encode(format!("{}:{}", username, password));
 
             format!("Proxy-Authorization: basic {}\r\n", creds)
         }
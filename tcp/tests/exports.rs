@@ -0,0 +1,141 @@
+//! Calls the exports by name the way Keeper does: null-terminated UTF-16 in and out
+
+extern crate tcp;
+
+fn wide(s: &str) -> Vec<u16> {
+    s.encode_utf16().chain(Some(0)).collect()
+}
+
+unsafe fn from_wide(ptr: *const u16) -> String {
+    let len = (0..).take_while(|&i| *ptr.add(i) != 0).count();
+    String::from_utf16(std::slice::from_raw_parts(ptr, len)).unwrap()
+}
+
+macro_rules! exports {
+    ($($name:ident($($arg:ident),*);)+) => {
+        #[allow(non_snake_case)]
+        mod ffi {
+            extern "system" {
+                $(pub fn $name($($arg: *const u16),*) -> *const u16;)+
+            }
+        }
+
+        $(
+            #[allow(non_snake_case)]
+            fn $name($($arg: &str),*) -> String {
+                $(let $arg = wide($arg);)*
+                unsafe { from_wide(ffi::$name($($arg.as_ptr()),*)) }
+            }
+        )+
+    };
+}
+
+exports! {
+    connect_ip(addr, proxy_addr, timeout, proxy_resolve, use_tls);
+    send_data(uuid, data);
+    recv_exact(uuid, len);
+    recv_until(uuid, until);
+    recv_end(uuid);
+    disconnect(uuid);
+    task_status(uuid);
+    set_read_timeout(uuid, timeout);
+    set_write_timeout(uuid, timeout);
+    info_getAuthor();
+    info_getVersion();
+    info_getDescription();
+}
+
+use std::{
+    io::{Read, Write},
+    net::TcpListener,
+    thread,
+    time::Duration,
+};
+
+const NOT_FOUND: &str = "ERR|connection not found";
+
+/// Polls the running task like Keeper does until it's done
+fn wait(uuid: &str) -> String {
+    for _ in 0..500 {
+        match task_status(uuid) {
+            status if status == "WAIT" => thread::sleep(Duration::from_millis(10)),
+            status => return status,
+        }
+    }
+    panic!("task of {uuid} is still running");
+}
+
+#[test]
+fn connect_send_receive() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    let server = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = [0u8; 9];
+        stream.read_exact(&mut request[..5]).unwrap();
+        stream.write_all(b"world\r\n").unwrap();
+        stream.read_exact(&mut request[5..]).unwrap();
+        stream.write_all(b"rest").unwrap();
+        request
+    });
+
+    let uuid = connect_ip(&addr, ":", "1000", "false", "false");
+    assert_eq!(uuid.len(), 36);
+    assert_eq!(wait(&uuid), "CONNECTED");
+    assert_eq!(set_read_timeout(&uuid, "1000"), "OK");
+    assert_eq!(set_write_timeout(&uuid, "0"), "OK");
+
+    assert_eq!(send_data(&uuid, &base64::encode("hello")), "THREAD_SPAWNED");
+    assert_eq!(wait(&uuid), "SENT");
+    assert_eq!(recv_exact(&uuid, "2"), "THREAD_SPAWNED");
+    assert_eq!(wait(&uuid), base64::encode("wo"));
+    assert_eq!(recv_until(&uuid, &base64::encode("\r\n")), "THREAD_SPAWNED");
+    assert_eq!(wait(&uuid), base64::encode("rld\r\n"));
+
+    assert_eq!(send_data(&uuid, &base64::encode("more")), "THREAD_SPAWNED");
+    assert_eq!(wait(&uuid), "SENT");
+    assert_eq!(recv_end(&uuid), "THREAD_SPAWNED");
+    assert_eq!(wait(&uuid), base64::encode("rest"));
+    assert_eq!(&server.join().unwrap(), b"hellomore");
+
+    assert_eq!(disconnect(&uuid), "OK");
+    assert_eq!(disconnect(&uuid), NOT_FOUND);
+}
+
+#[test]
+fn connect_refused() {
+    let addr = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .to_string();
+
+    let uuid = connect_ip(&addr, ":", "1000", "false", "false");
+    assert!(wait(&uuid).starts_with("ERR|"));
+    assert_eq!(task_status(&uuid), "ERR|no active task");
+    assert!(send_data(&uuid, "").starts_with("ERR|no tcp stream"));
+    assert!(set_read_timeout(&uuid, "0").starts_with("ERR|no tcp stream"));
+    assert_eq!(disconnect(&uuid), "OK");
+}
+
+#[test]
+fn invalid_arguments() {
+    assert!(connect_ip("127.0.0.1:1", ":", "soon", "", "").starts_with("ERR|"));
+    assert!(connect_ip("127.0.0.1:1", "proxy", "0", "", "").starts_with("ERR|"));
+
+    assert_eq!(task_status("unknown"), NOT_FOUND);
+    assert_eq!(send_data("unknown", ""), NOT_FOUND);
+    assert_eq!(recv_exact("unknown", "1"), NOT_FOUND);
+    assert_eq!(recv_until("unknown", "DQo="), NOT_FOUND);
+    assert_eq!(recv_end("unknown"), NOT_FOUND);
+    assert_eq!(set_read_timeout("unknown", "0"), NOT_FOUND);
+    assert_eq!(set_write_timeout("unknown", "0"), NOT_FOUND);
+    assert!(set_write_timeout("unknown", "-1").starts_with("ERR|"));
+}
+
+#[test]
+fn info() {
+    assert_eq!(info_getAuthor(), "_Skill_");
+    assert_eq!(info_getVersion(), "0.2");
+    assert_eq!(info_getDescription(), "TCP с поддержкой прокси");
+}
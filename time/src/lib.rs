@@ -1,15 +1,11 @@
-mod dllmain;
 mod time;
 
-mod utils {
-    pub mod cstring;
-    pub mod macros;
+pk_ffi::dll_main!();
+pk_ffi::info! {
+    author: "_Skill_",
+    version: "0.1",
+    description: "Работа с временем",
 }
 
-pub use crate::utils::*;
-
-pub const ERR: &str = "ERR|";
-pub const DEBUG: bool = false;
-
 #[global_allocator]
 static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
@@ -0,0 +1,67 @@
+//! Calls the exports by name the way Keeper does: null-terminated UTF-16 in and out
+
+extern crate time;
+
+fn wide(s: &str) -> Vec<u16> {
+    s.encode_utf16().chain(Some(0)).collect()
+}
+
+unsafe fn from_wide(ptr: *const u16) -> String {
+    let len = (0..).take_while(|&i| *ptr.add(i) != 0).count();
+    String::from_utf16(std::slice::from_raw_parts(ptr, len)).unwrap()
+}
+
+macro_rules! exports {
+    ($($name:ident($($arg:ident),*);)+) => {
+        #[allow(non_snake_case)]
+        mod ffi {
+            extern "system" {
+                $(pub fn $name($($arg: *const u16),*) -> *const u16;)+
+            }
+        }
+
+        $(
+            #[allow(non_snake_case)]
+            fn $name($($arg: &str),*) -> String {
+                $(let $arg = wide($arg);)*
+                unsafe { from_wide(ffi::$name($($arg.as_ptr()),*)) }
+            }
+        )+
+    };
+}
+
+exports! {
+    format(timestamp, format, timezone);
+    parse(date, format);
+    info_getAuthor();
+    info_getVersion();
+    info_getDescription();
+}
+
+#[test]
+fn format_timestamp() {
+    assert_eq!(format("0", "%Y-%m-%d %H:%M:%S", "0"), "1970-01-01 00:00:00");
+    assert_eq!(
+        format("1700000000", "%d.%m.%Y %H:%M", "10800"),
+        "15.11.2023 01:13"
+    );
+    // unknown timezone falls back to UTC
+    assert_eq!(format("1700000000", "%H:%M %z", "utc"), "22:13 +0000");
+    assert!(format("now", "%Y", "0").starts_with("ERR|"));
+}
+
+#[test]
+fn parse_date() {
+    assert_eq!(
+        parse("2023-11-15 01:13:20 +0300", "%Y-%m-%d %H:%M:%S %z"),
+        "1700000000"
+    );
+    assert!(parse("15.11.2023", "%Y-%m-%d").starts_with("ERR|"));
+}
+
+#[test]
+fn info() {
+    assert_eq!(info_getAuthor(), "_Skill_");
+    assert_eq!(info_getVersion(), "0.1");
+    assert_eq!(info_getDescription(), "Работа с временем");
+}
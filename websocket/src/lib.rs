@@ -1,18 +1,20 @@
+// tungstenite errors are large, boxing them buys nothing for a few calls per second
+#![allow(clippy::result_large_err)]
+
+#[cfg(windows)]
 mod dllmain;
 mod websocket;
 
 mod utils {
-    pub mod cstring;
     pub mod error;
-    pub mod macros;
     pub mod proxy;
     pub mod statuses;
     pub mod traits;
     pub mod websocket;
 }
 
-use crate::dllmain::DEBUG;
 use once_cell::sync::Lazy;
+use pk_ffi::debug;
 use threadpool::{Builder, ThreadPool};
 
 use crate::{statuses::Task, traits::TcpThread, utils::*};
@@ -25,7 +27,11 @@ use std::{
     time::{Duration, Instant},
 };
 
-pub const ERR: &str = "ERR|";
+pk_ffi::info! {
+    author: "_Skill_",
+    version: "0.1",
+    description: "Просто реализация WebSocket",
+}
 
 /*#[global_allocator]
 static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
@@ -15,6 +15,8 @@ use super::{error::GlobalError, statuses::Task};
 #[derive(Debug)]
 pub struct ThreadResult {
     pub stream: WebSocket<MaybeTlsStream<TcpStream>>,
+    // handshake response, kept for debug output
+    #[allow(dead_code)]
     pub resp: Option<Response<()>>,
     pub buffer: Option<Message>,
 }
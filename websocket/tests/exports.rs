@@ -0,0 +1,156 @@
+//! Calls the exports by name the way Keeper does: null-terminated UTF-16 in and out
+
+extern crate websocket;
+
+fn wide(s: &str) -> Vec<u16> {
+    s.encode_utf16().chain(Some(0)).collect()
+}
+
+unsafe fn from_wide(ptr: *const u16) -> String {
+    let len = (0..).take_while(|&i| *ptr.add(i) != 0).count();
+    String::from_utf16(std::slice::from_raw_parts(ptr, len)).unwrap()
+}
+
+macro_rules! exports {
+    ($($name:ident($($arg:ident),*);)+) => {
+        #[allow(non_snake_case)]
+        mod ffi {
+            extern "system" {
+                $(pub fn $name($($arg: *const u16),*) -> *const u16;)+
+            }
+        }
+
+        $(
+            #[allow(non_snake_case)]
+            fn $name($($arg: &str),*) -> String {
+                $(let $arg = wide($arg);)*
+                unsafe { from_wide(ffi::$name($($arg.as_ptr()),*)) }
+            }
+        )+
+    };
+}
+
+exports! {
+    connect_ip(url, proxy_addr, timeout, proxy_resolve);
+    send_message(uuid, message_type, data);
+    read_message(uuid);
+    disconnect(uuid, code, reason);
+    task_status(uuid);
+    set_read_timeout(uuid, timeout);
+    set_write_timeout(uuid, timeout);
+    info_getAuthor();
+    info_getVersion();
+    info_getDescription();
+}
+
+use std::{net::TcpListener, thread, time::Duration};
+
+use tungstenite::Message;
+
+const NOT_FOUND: &str = "ERR|connection not found";
+
+/// Polls the running task like Keeper does until it's done
+fn wait(uuid: &str) -> String {
+    for _ in 0..500 {
+        match task_status(uuid) {
+            status if status == "WAIT" => thread::sleep(Duration::from_millis(10)),
+            status => return status,
+        }
+    }
+    panic!("task of {uuid} is still running");
+}
+
+#[test]
+fn connect_send_read() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("ws://{}/", listener.local_addr().unwrap());
+    let server = thread::spawn(move || {
+        let mut ws = tungstenite::accept(listener.accept().unwrap().0).unwrap();
+        let mut received = Vec::new();
+        loop {
+            let message = ws.read_message().unwrap();
+            match message {
+                Message::Text(_) => ws.write_message(Message::text("привет")).unwrap(),
+                Message::Binary(_) => ws.write_message(Message::binary([1, 2, 3])).unwrap(),
+                _ => {}
+            }
+            let close = message.is_close();
+            received.push(message);
+            if close {
+                return received;
+            }
+        }
+    });
+
+    let uuid = connect_ip(&url, ":", "1000", "false");
+    assert_eq!(uuid.len(), 36);
+    assert_eq!(wait(&uuid), "CONNECTED");
+    assert_eq!(set_read_timeout(&uuid, "1000"), "OK");
+    assert_eq!(set_write_timeout(&uuid, "0"), "OK");
+
+    assert_eq!(send_message(&uuid, "text", "hello"), "THREAD_SPAWNED");
+    assert_eq!(wait(&uuid), "SENT");
+    assert_eq!(read_message(&uuid), "THREAD_SPAWNED");
+    assert_eq!(wait(&uuid), "TEXT|привет");
+
+    assert_eq!(send_message(&uuid, "binary", "AAE="), "THREAD_SPAWNED");
+    assert_eq!(wait(&uuid), "SENT");
+    assert_eq!(read_message(&uuid), "THREAD_SPAWNED");
+    assert_eq!(wait(&uuid), "BINARY|AQID");
+
+    assert_eq!(
+        send_message(&uuid, "ping", ""),
+        "ERR|unsupported message type: ping"
+    );
+    assert!(send_message(&uuid, "binary", "!").starts_with("ERR|"));
+
+    assert_eq!(disconnect(&uuid, "1000", "bye"), "OK");
+    assert_eq!(disconnect(&uuid, "", ""), NOT_FOUND);
+
+    let received = server.join().unwrap();
+    assert_eq!(received[0], Message::text("hello"));
+    assert_eq!(received[1], Message::binary([0, 1]));
+    match &received[2] {
+        Message::Close(Some(frame)) => {
+            assert_eq!(u16::from(frame.code), 1000);
+            assert_eq!(frame.reason, "bye");
+        }
+        message => panic!("expected a close frame, got {message:?}"),
+    }
+}
+
+#[test]
+fn connect_refused() {
+    let addr = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+
+    let uuid = connect_ip(&format!("ws://{addr}/"), ":", "1000", "false");
+    assert!(wait(&uuid).starts_with("ERR|"));
+    assert_eq!(task_status(&uuid), "ERR|no active task");
+    assert!(read_message(&uuid).starts_with("ERR|no tcp stream"));
+    assert!(set_read_timeout(&uuid, "0").starts_with("ERR|no tcp stream"));
+    assert_eq!(disconnect(&uuid, "", ""), "OK");
+}
+
+#[test]
+fn invalid_arguments() {
+    assert!(connect_ip("not a url", ":", "0", "").starts_with("ERR|"));
+    assert!(connect_ip("ws://127.0.0.1:1/", ":", "soon", "").starts_with("ERR|"));
+    assert!(connect_ip("ws://127.0.0.1:1/", "proxy", "0", "").starts_with("ERR|"));
+
+    assert_eq!(task_status("unknown"), NOT_FOUND);
+    assert_eq!(send_message("unknown", "text", ""), NOT_FOUND);
+    assert_eq!(read_message("unknown"), NOT_FOUND);
+    assert_eq!(set_read_timeout("unknown", "0"), NOT_FOUND);
+    assert_eq!(set_write_timeout("unknown", "0"), NOT_FOUND);
+    assert!(set_write_timeout("unknown", "-1").starts_with("ERR|"));
+}
+
+#[test]
+fn info() {
+    assert_eq!(info_getAuthor(), "_Skill_");
+    assert_eq!(info_getVersion(), "0.1");
+    assert_eq!(info_getDescription(), "Просто реализация WebSocket");
+}